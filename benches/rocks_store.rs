@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use rocksdb::DB;
+use solana_sdk::pubkey::Pubkey;
+use sparse_merkle_tree::H256;
+
+use relayer::smt::account_smt::{DatabaseStoreAccountSMT, SMTAccount};
+use relayer::smt::rocks_store::RocksStore;
+
+// the account updates of one busy slot
+const ACCOUNTS_PER_SLOT: usize = 1_000;
+
+fn prepare_accounts() -> Vec<(H256, SMTAccount)> {
+    (0..ACCOUNTS_PER_SLOT)
+        .map(|i| {
+            let account = SMTAccount {
+                pubkey: Pubkey::new_unique(),
+                lamports: i as i64,
+                owner: Pubkey::default(),
+                executable: false,
+                rent_epoch: 0,
+                data: vec![0u8; 128],
+            };
+            (account.smt_key(), account)
+        })
+        .collect()
+}
+
+fn prepare_smt() -> (tempfile::TempDir, DatabaseStoreAccountSMT) {
+    let dir = tempfile::tempdir().unwrap();
+    let db = Arc::new(DB::open_default(dir.path()).unwrap());
+    let smt = DatabaseStoreAccountSMT::new_with_store(RocksStore::new_shared(db)).unwrap();
+    (dir, smt)
+}
+
+fn bench_apply_slot(c: &mut Criterion) {
+    let mut group = c.benchmark_group("apply_slot");
+    group.sample_size(10);
+
+    group.bench_function("unbatched", |b| {
+        b.iter_batched(
+            || (prepare_smt(), prepare_accounts()),
+            |((_dir, mut smt), accounts)| {
+                smt.store_mut().set_version(1);
+                smt.update_all(accounts).unwrap();
+            },
+            BatchSize::PerIteration,
+        )
+    });
+
+    group.bench_function("batched", |b| {
+        b.iter_batched(
+            || (prepare_smt(), prepare_accounts()),
+            |((_dir, mut smt), accounts)| {
+                smt.store_mut().set_version(1);
+                smt.store_mut().begin_batch();
+                smt.update_all(accounts).unwrap();
+                smt.store_mut().commit_batch(vec![]).unwrap();
+            },
+            BatchSize::PerIteration,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_apply_slot);
+criterion_main!(benches);
@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use relayer::smt::serde::slice_to_branch_node;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = slice_to_branch_node(data);
+});
@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use relayer::smt::account_smt::SMTAccount;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = SMTAccount::try_from(data.to_vec());
+});
@@ -1,14 +1,33 @@
--- CREATE TABLE brief
--- (
---     id         bigserial PRIMARY KEY,
---     slot       BIGINT    UNIQUE NOT NULL,
---     tx_info_hash  BYTEA     NOT NULL,
---     hash_account       VARCHAR(256)  DEFAULT '',
---     transaction_number INT           DEFAULT 0,
---     updated_on TIMESTAMP default current_timestamp
--- );
--- CREATE INDEX index_brief_root_hash ON brief (root_hash);
--- CREATE INDEX index_brief_hash_account ON brief (hash_account);
+CREATE TABLE brief
+(
+    id         bigserial PRIMARY KEY,
+    slot       BIGINT    UNIQUE NOT NULL,
+    root_hash  VARCHAR(256)  NOT NULL,
+    hash_account       VARCHAR(256)  DEFAULT '',
+    transaction_number INT           DEFAULT 0,
+    challenged BOOLEAN   NOT NULL DEFAULT false,
+    updated_on TIMESTAMP default current_timestamp
+);
+CREATE INDEX index_brief_root_hash ON brief (root_hash);
+CREATE INDEX index_brief_hash_account ON brief (hash_account);
+
+CREATE TABLE brief_reclaim
+(
+    id         bigserial PRIMARY KEY,
+    slot       BIGINT    UNIQUE NOT NULL,
+    lamports   BIGINT    NOT NULL,
+    updated_on TIMESTAMP default current_timestamp
+);
+
+CREATE TYPE bridge_lifecycle AS ENUM (
+    'detected',
+    'committed',
+    'proven',
+    'challenge_window_elapsed',
+    'claimed',
+    'rejected',
+    'orphaned'
+);
 
 CREATE TABLE bridge_transaction
 (
@@ -20,5 +39,108 @@ CREATE TABLE bridge_transaction
     is_generated_proof BOOLEAN NOT NULL, 
     current_mt_root BYTEA,
     root_program_slot BIGINT DEFAULT 0,
+    sender     VARCHAR(64)  NOT NULL DEFAULT '',
+    recipient  VARCHAR(64)  NOT NULL DEFAULT '',
+    amount     BIGINT       NOT NULL DEFAULT 0,
+    bridge_tx_index BIGINT  NOT NULL DEFAULT 0,
+    status     bridge_lifecycle NOT NULL DEFAULT 'detected',
+    l1_signature VARCHAR(256) NOT NULL DEFAULT '',
+    proven_on  TIMESTAMP,
+    claim_status VARCHAR(32) NOT NULL DEFAULT 'unclaimed',
+    claim_slot BIGINT       NOT NULL DEFAULT 0,
+    claim_attempts INT      NOT NULL DEFAULT 0,
+    l1_commit_slot BIGINT   NOT NULL DEFAULT 0,
+    committed_on TIMESTAMP,
+    claimable_on TIMESTAMP,
+    finalized  BOOLEAN   NOT NULL DEFAULT false,
+    created_on TIMESTAMP NOT NULL default current_timestamp,
+    updated_on TIMESTAMP default current_timestamp
+);
+CREATE INDEX index_bridge_transaction_slot ON bridge_transaction (slot, id);
+CREATE INDEX index_bridge_transaction_tx_info_hash ON bridge_transaction (tx_info_hash);
+CREATE INDEX index_bridge_transaction_sender ON bridge_transaction (sender, slot, id);
+CREATE INDEX index_bridge_transaction_recipient ON bridge_transaction (recipient, slot, id);
+CREATE INDEX index_bridge_transaction_claim_status ON bridge_transaction (claim_status, slot, id);
+CREATE INDEX index_bridge_transaction_claimable_on ON bridge_transaction (status, claimable_on);
+
+CREATE TABLE root_commitment
+(
+    id         bigserial PRIMARY KEY,
+    slot       BIGINT    UNIQUE NOT NULL,
+    merkle_tree_root BYTEA NOT NULL,
+    l1_slot    BIGINT    NOT NULL DEFAULT 0,
+    committed_on TIMESTAMP NOT NULL,
+    claimable_on TIMESTAMP NOT NULL,
+    finalized  BOOLEAN   NOT NULL DEFAULT false,
+    created_on TIMESTAMP NOT NULL default current_timestamp
+);
+
+CREATE INDEX index_root_commitment_l1_slot ON root_commitment (l1_slot, slot);
+
+CREATE TABLE scan_checkpoint
+(
+    name       VARCHAR(64) PRIMARY KEY,
+    slot       BIGINT    NOT NULL DEFAULT 0,
+    updated_on TIMESTAMP NOT NULL default current_timestamp
+);
+
+CREATE TABLE root_observation
+(
+    id         bigserial PRIMARY KEY,
+    slot       BIGINT    NOT NULL,
+    merkle_tree_root BYTEA NOT NULL,
+    first_seen_on TIMESTAMP NOT NULL default current_timestamp,
+    last_seen_on TIMESTAMP NOT NULL default current_timestamp,
+    UNIQUE (slot, merkle_tree_root)
+);
+
+CREATE TABLE bridge_transition
+(
+    id         bigserial PRIMARY KEY,
+    signature  VARCHAR(256) NOT NULL,
+    from_status bridge_lifecycle NOT NULL,
+    to_status  bridge_lifecycle NOT NULL,
+    created_on TIMESTAMP NOT NULL default current_timestamp
+);
+CREATE INDEX index_bridge_transition_signature ON bridge_transition (signature, id);
+
+CREATE TABLE claim_submission
+(
+    id         bigserial PRIMARY KEY,
+    leaf_index BIGINT    UNIQUE NOT NULL,
+    signature  VARCHAR(256) NOT NULL,
+    status     VARCHAR(32)  NOT NULL,
+    attempts   INT          NOT NULL DEFAULT 0,
+    priority_fee BIGINT     NOT NULL DEFAULT 0,
+    l1_signature VARCHAR(256) NOT NULL DEFAULT '',
+    last_error VARCHAR(1024) NOT NULL DEFAULT '',
+    next_attempt_on TIMESTAMP NOT NULL default current_timestamp,
+    submitted_on TIMESTAMP,
+    updated_on TIMESTAMP default current_timestamp
+);
+CREATE INDEX index_claim_submission_status ON claim_submission (status, next_attempt_on);
+
+CREATE TABLE proof_event
+(
+    id         bigserial PRIMARY KEY,
+    signature  VARCHAR(256) NOT NULL,
+    sender     VARCHAR(64)  NOT NULL DEFAULT '',
+    recipient  VARCHAR(64)  NOT NULL DEFAULT '',
+    root_program_slot BIGINT NOT NULL,
+    created_on TIMESTAMP default current_timestamp
+);
+
+CREATE TABLE challenge
+(
+    id         bigserial PRIMARY KEY,
+    slot       BIGINT    NOT NULL,
+    kind       VARCHAR(32)  NOT NULL DEFAULT 'merkle_root',
+    local_root BYTEA     NOT NULL,
+    chain_root BYTEA     NOT NULL,
+    hash_account       VARCHAR(256)  DEFAULT '',
+    transaction_number INT           DEFAULT 0,
+    signature  VARCHAR(256) DEFAULT '',
+    status     VARCHAR(32)  NOT NULL,
     updated_on TIMESTAMP default current_timestamp
-)
\ No newline at end of file
+);
+CREATE UNIQUE INDEX index_challenge_slot_kind ON challenge (slot, kind);
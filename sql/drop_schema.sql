@@ -1 +1,11 @@
 DROP TABLE bridge_transaction;
+DROP TABLE bridge_transition;
+DROP TABLE root_commitment;
+DROP TABLE root_observation;
+DROP TABLE scan_checkpoint;
+DROP TABLE proof_event;
+DROP TABLE claim_submission;
+DROP TABLE challenge;
+DROP TABLE brief;
+DROP TABLE brief_reclaim;
+DROP TYPE bridge_lifecycle;
@@ -0,0 +1,155 @@
+-- Upgrade a database created from the first schema, which only had `bridge_transaction`, to
+-- create_schema.sql. Every statement can run again on an upgraded database.
+-- The enum is created outside the transaction, ALTER TYPE ... ADD VALUE can't run inside one.
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'bridge_lifecycle') THEN
+        CREATE TYPE bridge_lifecycle AS ENUM (
+            'detected',
+            'committed',
+            'proven',
+            'challenge_window_elapsed',
+            'claimed',
+            'rejected',
+            'orphaned'
+        );
+    END IF;
+END
+$$;
+ALTER TYPE bridge_lifecycle ADD VALUE IF NOT EXISTS 'rejected' BEFORE 'orphaned';
+
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS brief
+(
+    id         bigserial PRIMARY KEY,
+    slot       BIGINT    UNIQUE NOT NULL,
+    root_hash  VARCHAR(256)  NOT NULL,
+    hash_account       VARCHAR(256)  DEFAULT '',
+    transaction_number INT           DEFAULT 0,
+    challenged BOOLEAN   NOT NULL DEFAULT false,
+    updated_on TIMESTAMP default current_timestamp
+);
+CREATE TABLE IF NOT EXISTS brief_reclaim
+(
+    id         bigserial PRIMARY KEY,
+    slot       BIGINT    UNIQUE NOT NULL,
+    lamports   BIGINT    NOT NULL,
+    updated_on TIMESTAMP default current_timestamp
+);
+CREATE INDEX IF NOT EXISTS index_brief_root_hash ON brief (root_hash);
+CREATE INDEX IF NOT EXISTS index_brief_hash_account ON brief (hash_account);
+
+-- sender, recipient and amount of messages filtered before the upgrade stay at their defaults
+ALTER TABLE bridge_transaction
+    ADD COLUMN IF NOT EXISTS sender     VARCHAR(64)  NOT NULL DEFAULT '',
+    ADD COLUMN IF NOT EXISTS recipient  VARCHAR(64)  NOT NULL DEFAULT '',
+    ADD COLUMN IF NOT EXISTS amount     BIGINT       NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS bridge_tx_index BIGINT  NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS status     bridge_lifecycle NOT NULL DEFAULT 'detected',
+    ADD COLUMN IF NOT EXISTS l1_signature VARCHAR(256) NOT NULL DEFAULT '',
+    ADD COLUMN IF NOT EXISTS proven_on  TIMESTAMP,
+    ADD COLUMN IF NOT EXISTS claim_status VARCHAR(32) NOT NULL DEFAULT 'unclaimed',
+    ADD COLUMN IF NOT EXISTS claim_slot BIGINT       NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS claim_attempts INT      NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS l1_commit_slot BIGINT   NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS committed_on TIMESTAMP,
+    ADD COLUMN IF NOT EXISTS claimable_on TIMESTAMP,
+    ADD COLUMN IF NOT EXISTS finalized  BOOLEAN   NOT NULL DEFAULT false,
+    ADD COLUMN IF NOT EXISTS created_on TIMESTAMP NOT NULL default current_timestamp;
+
+-- before the lifecycle a message was either waiting for its proof or proven
+UPDATE bridge_transaction
+SET status = CASE WHEN is_generated_proof THEN 'proven'::bridge_lifecycle ELSE 'detected'::bridge_lifecycle END
+WHERE status = 'detected';
+
+CREATE INDEX IF NOT EXISTS index_bridge_transaction_slot ON bridge_transaction (slot, id);
+CREATE INDEX IF NOT EXISTS index_bridge_transaction_tx_info_hash ON bridge_transaction (tx_info_hash);
+CREATE INDEX IF NOT EXISTS index_bridge_transaction_sender ON bridge_transaction (sender, slot, id);
+CREATE INDEX IF NOT EXISTS index_bridge_transaction_recipient ON bridge_transaction (recipient, slot, id);
+CREATE INDEX IF NOT EXISTS index_bridge_transaction_claim_status ON bridge_transaction (claim_status, slot, id);
+CREATE INDEX IF NOT EXISTS index_bridge_transaction_claimable_on ON bridge_transaction (status, claimable_on);
+
+CREATE TABLE IF NOT EXISTS root_commitment
+(
+    id         bigserial PRIMARY KEY,
+    slot       BIGINT    UNIQUE NOT NULL,
+    merkle_tree_root BYTEA NOT NULL,
+    l1_slot    BIGINT    NOT NULL DEFAULT 0,
+    committed_on TIMESTAMP NOT NULL,
+    claimable_on TIMESTAMP NOT NULL,
+    finalized  BOOLEAN   NOT NULL DEFAULT false,
+    created_on TIMESTAMP NOT NULL default current_timestamp
+);
+
+CREATE INDEX IF NOT EXISTS index_root_commitment_l1_slot ON root_commitment (l1_slot, slot);
+
+CREATE TABLE IF NOT EXISTS scan_checkpoint
+(
+    name       VARCHAR(64) PRIMARY KEY,
+    slot       BIGINT    NOT NULL DEFAULT 0,
+    updated_on TIMESTAMP NOT NULL default current_timestamp
+);
+
+CREATE TABLE IF NOT EXISTS root_observation
+(
+    id         bigserial PRIMARY KEY,
+    slot       BIGINT    NOT NULL,
+    merkle_tree_root BYTEA NOT NULL,
+    first_seen_on TIMESTAMP NOT NULL default current_timestamp,
+    last_seen_on TIMESTAMP NOT NULL default current_timestamp,
+    UNIQUE (slot, merkle_tree_root)
+);
+
+CREATE TABLE IF NOT EXISTS bridge_transition
+(
+    id         bigserial PRIMARY KEY,
+    signature  VARCHAR(256) NOT NULL,
+    from_status bridge_lifecycle NOT NULL,
+    to_status  bridge_lifecycle NOT NULL,
+    created_on TIMESTAMP NOT NULL default current_timestamp
+);
+CREATE INDEX IF NOT EXISTS index_bridge_transition_signature ON bridge_transition (signature, id);
+
+CREATE TABLE IF NOT EXISTS claim_submission
+(
+    id         bigserial PRIMARY KEY,
+    leaf_index BIGINT    UNIQUE NOT NULL,
+    signature  VARCHAR(256) NOT NULL,
+    status     VARCHAR(32)  NOT NULL,
+    attempts   INT          NOT NULL DEFAULT 0,
+    priority_fee BIGINT     NOT NULL DEFAULT 0,
+    l1_signature VARCHAR(256) NOT NULL DEFAULT '',
+    last_error VARCHAR(1024) NOT NULL DEFAULT '',
+    next_attempt_on TIMESTAMP NOT NULL default current_timestamp,
+    submitted_on TIMESTAMP,
+    updated_on TIMESTAMP default current_timestamp
+);
+CREATE INDEX IF NOT EXISTS index_claim_submission_status ON claim_submission (status, next_attempt_on);
+
+CREATE TABLE IF NOT EXISTS proof_event
+(
+    id         bigserial PRIMARY KEY,
+    signature  VARCHAR(256) NOT NULL,
+    sender     VARCHAR(64)  NOT NULL DEFAULT '',
+    recipient  VARCHAR(64)  NOT NULL DEFAULT '',
+    root_program_slot BIGINT NOT NULL,
+    created_on TIMESTAMP default current_timestamp
+);
+
+CREATE TABLE IF NOT EXISTS challenge
+(
+    id         bigserial PRIMARY KEY,
+    slot       BIGINT    NOT NULL,
+    kind       VARCHAR(32)  NOT NULL DEFAULT 'merkle_root',
+    local_root BYTEA     NOT NULL,
+    chain_root BYTEA     NOT NULL,
+    hash_account       VARCHAR(256)  DEFAULT '',
+    transaction_number INT           DEFAULT 0,
+    signature  VARCHAR(256) DEFAULT '',
+    status     VARCHAR(32)  NOT NULL,
+    updated_on TIMESTAMP default current_timestamp
+);
+CREATE UNIQUE INDEX IF NOT EXISTS index_challenge_slot_kind ON challenge (slot, kind);
+
+COMMIT;
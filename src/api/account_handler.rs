@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::api::response::ApiResponse;
+use crate::api::withdrawal_handler::parse_number;
+use crate::smt::account_proof::AccountProver;
+
+// GET /account/{pubkey}?slot={commitment slot}
+// proof of the account against the world state root committed at slot, the latest verified commitment by default
+pub fn prove(prover: &AccountProver, pubkey: &str, params: &HashMap<String, String>) -> ApiResponse {
+    let pubkey = match Pubkey::from_str(pubkey) {
+        Ok(pubkey) => pubkey,
+        Err(_) => return ApiResponse::bad_request("pubkey must be a base58 encoded public key"),
+    };
+
+    let slot = match parse_number(params, "slot") {
+        Ok(Some(slot)) if slot >= 0 => slot as u64,
+        Ok(Some(_)) => return ApiResponse::bad_request("slot must be a non-negative integer"),
+        Ok(None) => match prover.latest_slot() {
+            Ok(Some(slot)) => slot,
+            Ok(None) => return ApiResponse::not_found("no verified world state yet"),
+            Err(e) => return ApiResponse::internal(&e),
+        },
+        Err(e) => return ApiResponse::bad_request(&e),
+    };
+
+    match prover.is_committed(slot) {
+        Ok(true) => {}
+        Ok(false) => return ApiResponse::not_found(&format!("world state of slot {} is not verified or was pruned", slot)),
+        Err(e) => return ApiResponse::internal(&e),
+    }
+
+    match prover.prove(&pubkey, slot) {
+        Ok(proof) => ApiResponse::ok(&proof),
+        Err(e) => ApiResponse::internal(&e),
+    }
+}
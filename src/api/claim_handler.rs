@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::api::response::ApiResponse;
+use crate::api::withdrawal_handler::{parse_cursor, parse_limit};
+use crate::models::bridge_transaction_model::{BridgeLifecycle, ClaimStatus};
+use crate::services::chain_service::ChainService;
+use crate::services::proof_service::ProofService;
+
+// GET /claim/{l2 signature}?payer={pubkey}
+// the payer defaults to the recipient of the withdrawal
+pub fn build(proof_service: &ProofService, chain_service: &ChainService, signature: &str, params: &HashMap<String, String>) -> ApiResponse {
+    let payer = match params.get("payer").map(|p| Pubkey::from_str(p)).transpose() {
+        Ok(payer) => payer,
+        Err(_) => return ApiResponse::bad_request("payer must be a base58 pubkey"),
+    };
+
+    let (row, leaf_index) = match proof_service.find_row_by_signature(signature) {
+        Ok(Some(found)) => found,
+        Ok(None) => return ApiResponse::not_found("withdrawal not found"),
+        Err(e) => return ApiResponse::internal(&e),
+    };
+    if !matches!(row.status, BridgeLifecycle::Proven | BridgeLifecycle::ChallengeWindowElapsed) {
+        return ApiResponse::error(409, &format!("withdrawal is {}, not claimable", row.status.as_str()));
+    }
+
+    match chain_service.build_claim_transaction(&row, leaf_index, payer) {
+        Ok(claim) => ApiResponse::ok(&claim),
+        Err(e) => ApiResponse::internal(&e),
+    }
+}
+
+// GET /claims/{unclaimed|claimed|double_claimed}?cursor=&limit=
+// proven withdrawals in the claim status, oldest first
+pub fn backlog(proof_service: &ProofService, claim_status: &str, params: &HashMap<String, String>) -> ApiResponse {
+    let claim_status = match ClaimStatus::from_str(claim_status) {
+        Ok(claim_status) => claim_status,
+        Err(message) => return ApiResponse::bad_request(&message),
+    };
+    let (cursor, limit) = match parse_cursor(params).and_then(|cursor| Ok((cursor, parse_limit(params)?))) {
+        Ok(parsed) => parsed,
+        Err(message) => return ApiResponse::bad_request(&message),
+    };
+
+    match proof_service.claim_backlog(claim_status, cursor.unwrap_or((0, 0)), limit) {
+        Ok(backlog) => ApiResponse::ok(&backlog),
+        Err(e) => ApiResponse::internal(&e),
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt::Write;
+
+use crate::api::response::ApiResponse;
+use crate::models::bridge_transaction_model::BridgeLifecycle;
+use crate::models::scan_checkpoint_model::ScanProgress;
+use crate::services::proof_service::ProofService;
+
+// GET /metrics
+// prometheus text exposition
+pub fn render(service: &ProofService) -> ApiResponse {
+    let counts = match service.count_by_status() {
+        Ok(counts) => counts,
+        Err(e) => return ApiResponse::internal(&e),
+    };
+    let progress = match service.scan_progress() {
+        Ok(progress) => progress,
+        Err(e) => return ApiResponse::internal(&e),
+    };
+
+    ApiResponse::metrics(bridge_messages(&counts) + &scan_progress(&progress))
+}
+
+fn bridge_messages(counts: &[(BridgeLifecycle, i64)]) -> String {
+    let mut text = String::new();
+    let _ = writeln!(text, "# HELP relayer_bridge_messages Bridge messages per lifecycle status.");
+    let _ = writeln!(text, "# TYPE relayer_bridge_messages gauge");
+    for (status, count) in counts {
+        let _ = writeln!(text, "relayer_bridge_messages{{status=\"{}\"}} {}", status.as_str(), count);
+    }
+
+    text
+}
+
+fn scan_progress(progress: &ScanProgress) -> String {
+    let mut text = String::new();
+    let _ = writeln!(text, "# HELP relayer_filter_slot Highest L2 slot the filter scanned for bridge messages.");
+    let _ = writeln!(text, "# TYPE relayer_filter_slot gauge");
+    let _ = writeln!(text, "relayer_filter_slot {}", progress.filter_slot);
+    let _ = writeln!(text, "# HELP relayer_filter_lag_slots L2 slots between the latest block and the filter.");
+    let _ = writeln!(text, "# TYPE relayer_filter_lag_slots gauge");
+    let _ = writeln!(text, "relayer_filter_lag_slots {}", progress.filter_lag());
+    let _ = writeln!(text, "# HELP relayer_filter_commitment_lag_slots L2 slots the filter must scan before the monitor can verify its commitment.");
+    let _ = writeln!(text, "# TYPE relayer_filter_commitment_lag_slots gauge");
+    let _ = writeln!(text, "relayer_filter_commitment_lag_slots {}", progress.commitment_lag());
+
+    text
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::api::metrics_handler::{bridge_messages, scan_progress};
+    use crate::models::bridge_transaction_model::BridgeLifecycle;
+    use crate::models::scan_checkpoint_model::ScanProgress;
+
+    #[test]
+    fn test_bridge_messages() {
+        let text = bridge_messages(&[(BridgeLifecycle::Detected, 3), (BridgeLifecycle::ChallengeWindowElapsed, 0)]);
+        assert!(text.contains("# TYPE relayer_bridge_messages gauge\n"));
+        assert!(text.contains("relayer_bridge_messages{status=\"detected\"} 3\n"));
+        assert!(text.contains("relayer_bridge_messages{status=\"challenge_window_elapsed\"} 0\n"));
+    }
+
+    #[test]
+    fn test_scan_progress() {
+        let text = scan_progress(&ScanProgress { filter_slot: 90, monitor_target_slot: 100, head_slot: 120 });
+        assert!(text.contains("relayer_filter_slot 90\n"));
+        assert!(text.contains("relayer_filter_lag_slots 30\n"));
+        assert!(text.contains("relayer_filter_commitment_lag_slots 10\n"));
+
+        // caught up with the commitment
+        let text = scan_progress(&ScanProgress { filter_slot: 110, monitor_target_slot: 100, head_slot: 110 });
+        assert!(text.contains("relayer_filter_commitment_lag_slots 0\n"));
+    }
+}
@@ -0,0 +1,8 @@
+pub mod response;
+pub mod server;
+pub mod proof_handler;
+pub mod withdrawal_handler;
+pub mod subscription;
+pub mod claim_handler;
+pub mod metrics_handler;
+pub mod account_handler;
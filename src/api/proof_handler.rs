@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use crate::api::response::ApiResponse;
+use crate::api::withdrawal_handler::parse_number;
+use crate::common::node_error::NodeError;
+use crate::models::bridge_transaction_model::BridgeTxRow;
+use crate::services::proof_service::ProofService;
+
+// every route takes ?l1_slot={l1 slot} to prove against the latest root committed at or before
+// that slot instead of the root the withdrawal was proven against
+
+// GET /proof/signature/{l2 signature}
+pub fn by_signature(service: &ProofService, signature: &str, params: &HashMap<String, String>) -> ApiResponse {
+    match parse_number(params, "l1_slot") {
+        Ok(Some(l1_slot)) => against(service, service.find_row_by_signature(signature), l1_slot),
+        Ok(None) => ApiResponse::found(service.find_by_signature(signature), "withdrawal"),
+        Err(e) => ApiResponse::bad_request(&e),
+    }
+}
+
+// GET /proof/leaf_hash/{hex leaf hash}
+pub fn by_leaf_hash(service: &ProofService, leaf_hash: &str, params: &HashMap<String, String>) -> ApiResponse {
+    let leaf_hash = match hex::decode(leaf_hash.trim_start_matches("0x")) {
+        Ok(leaf_hash) if leaf_hash.len() == 32 => leaf_hash,
+        _ => return ApiResponse::bad_request("leaf hash must be 32 hex encoded bytes"),
+    };
+
+    match parse_number(params, "l1_slot") {
+        Ok(Some(l1_slot)) => against(service, service.find_row_by_leaf_hash(&leaf_hash), l1_slot),
+        Ok(None) => ApiResponse::found(service.find_by_leaf_hash(&leaf_hash), "withdrawal"),
+        Err(e) => ApiResponse::bad_request(&e),
+    }
+}
+
+// GET /proof/leaf_index/{index}
+pub fn by_leaf_index(service: &ProofService, leaf_index: &str, params: &HashMap<String, String>) -> ApiResponse {
+    let leaf_index = match leaf_index.parse::<i64>() {
+        Ok(leaf_index) if leaf_index >= 0 => leaf_index,
+        _ => return ApiResponse::bad_request("leaf index must be a non-negative integer"),
+    };
+
+    match parse_number(params, "l1_slot") {
+        Ok(Some(l1_slot)) => against(service, service.find_row_by_leaf_index(leaf_index), l1_slot),
+        Ok(None) => ApiResponse::found(service.find_by_leaf_index(leaf_index), "withdrawal"),
+        Err(e) => ApiResponse::bad_request(&e),
+    }
+}
+
+fn against(service: &ProofService, found: Result<Option<(BridgeTxRow, i64)>, NodeError>, l1_slot: i64) -> ApiResponse {
+    let (row, leaf_index) = match found {
+        Ok(Some(found)) => found,
+        Ok(None) => return ApiResponse::not_found("withdrawal not found"),
+        Err(e) => return ApiResponse::internal(&e),
+    };
+    let commitment = match service.commitment_at(l1_slot) {
+        Ok(Some(commitment)) => commitment,
+        Ok(None) => return ApiResponse::not_found(&format!("no root committed at or before l1 slot {}", l1_slot)),
+        Err(e) => return ApiResponse::internal(&e),
+    };
+    if row.slot > commitment.slot {
+        return ApiResponse::error(409, &format!("withdrawal of slot {} is after the root of slot {} committed at l1 slot {}",
+            row.slot, commitment.slot, commitment.l1_slot));
+    }
+
+    match service.prove_against(&row, leaf_index, &commitment) {
+        Ok(proof) => ApiResponse::ok(&proof),
+        Err(e) => ApiResponse::internal(&e),
+    }
+}
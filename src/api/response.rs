@@ -0,0 +1,70 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::common::node_error::NodeError;
+
+const JSON: &str = "application/json";
+
+// the prometheus text exposition format
+const METRICS: &str = "text/plain; version=0.0.4";
+
+#[derive(Debug, Clone)]
+pub struct ApiResponse {
+    pub status: u16,
+    pub body: Value,
+    pub content_type: &'static str,
+}
+
+impl ApiResponse {
+    pub fn ok<T: Serialize>(data: &T) -> Self {
+        match serde_json::to_value(data) {
+            Ok(body) => ApiResponse { status: 200, body, content_type: JSON },
+            Err(e) => Self::error(500, &format!("serialize response fail: {}", e)),
+        }
+    }
+
+    pub fn not_found(message: &str) -> Self {
+        Self::error(404, message)
+    }
+
+    pub fn bad_request(message: &str) -> Self {
+        Self::error(400, message)
+    }
+
+    pub fn internal(error: &NodeError) -> Self {
+        Self::error(500, &error.message)
+    }
+
+    pub fn error(status: u16, message: &str) -> Self {
+        ApiResponse {
+            status,
+            body: json!({ "error": message }),
+            content_type: JSON,
+        }
+    }
+
+    pub fn metrics(text: String) -> Self {
+        ApiResponse {
+            status: 200,
+            body: Value::String(text),
+            content_type: METRICS,
+        }
+    }
+
+    /// The bytes written to the client, a text body is sent as is.
+    pub fn payload(&self) -> String {
+        match &self.body {
+            Value::String(text) if self.content_type != JSON => text.clone(),
+            body => body.to_string(),
+        }
+    }
+
+    /// `Ok(None)` of a lookup is answered with 404.
+    pub fn found<T: Serialize>(result: Result<Option<T>, NodeError>, what: &str) -> Self {
+        match result {
+            Ok(Some(data)) => Self::ok(&data),
+            Ok(None) => Self::not_found(&format!("{} not found", what)),
+            Err(e) => Self::internal(&e),
+        }
+    }
+}
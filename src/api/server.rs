@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::thread;
+
+use log::{error, info};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::api::{account_handler, claim_handler, metrics_handler, proof_handler, subscription, withdrawal_handler};
+use crate::api::response::ApiResponse;
+use crate::common::node_configs::{ApiConfiguration, ChainConfiguration, StoreConfiguration};
+use crate::common::node_error::NodeError;
+use crate::services::chain_service::ChainService;
+use crate::services::proof_service::ProofService;
+use crate::smt::account_proof::AccountProver;
+use crate::utils::store_util::{create_pool, PgConnectionPool};
+use crate::utils::uuid_util::generate_uuid;
+
+// requests are served by this many threads
+const WORKER_THREADS: usize = 4;
+
+/// The embedded HTTP server answering JSON queries about withdrawals, with websocket
+/// subscriptions to proof-ready events on `ws_port`.
+pub struct ApiServer {
+    client_pool: Option<PgConnectionPool>,
+    store_config: Option<StoreConfiguration>,
+    chain_config: Option<ChainConfiguration>,
+    api_config: Option<ApiConfiguration>,
+    account_prover: Option<AccountProver>,
+}
+
+impl ApiServer {
+    pub fn new() -> Self {
+        Self {
+            client_pool: None,
+            store_config: None,
+            chain_config: None,
+            api_config: None,
+            account_prover: None,
+        }
+    }
+
+    pub fn load_store_config(mut self, store_config: &StoreConfiguration) -> Self {
+        self.store_config = Some(store_config.clone());
+        self
+    }
+
+    pub fn load_chain_config(mut self, chain_config: &ChainConfiguration) -> Self {
+        self.chain_config = Some(chain_config.clone());
+        self
+    }
+
+    pub fn load_api_config(mut self, api_config: &ApiConfiguration) -> Self {
+        self.api_config = Some(api_config.clone());
+        self
+    }
+
+    pub fn load_account_prover(mut self, account_prover: &AccountProver) -> Self {
+        self.account_prover = Some(account_prover.clone());
+        self
+    }
+
+    pub fn connect_store(&mut self) -> Result<(), NodeError> {
+        let pool = create_pool(self.store_config.clone().unwrap(), WORKER_THREADS as u32);
+
+        self.client_pool = Some(pool);
+
+        Ok(())
+    }
+
+    pub fn start(&mut self) -> Result<(), NodeError> {
+        self.connect_store()?;
+
+        let api_config = self.api_config.clone().unwrap();
+        let address = format!("{}:{}", api_config.bind_address, api_config.port);
+        let server = Server::http(&address).map_err(|e| {
+            NodeError::new(generate_uuid(), format!("start api server fail. address: {}, err: {:?}", address, e))
+        })?;
+        let server = Arc::new(server);
+        info!("api server listening on {}", address);
+
+        let ws_address = format!("{}:{}", api_config.bind_address, api_config.ws_port);
+        let ws_pool = self.client_pool.clone().unwrap();
+        let _ = thread::spawn(move || {
+            if let Err(e) = subscription::serve(&ws_address, ws_pool) {
+                error!("subscription server exit. {:?}", e);
+            }
+        });
+
+        // the workers share the proofs computed against past roots
+        let proof_service = ProofService::new(self.client_pool.clone().unwrap());
+        let mut workers = vec![];
+        for _ in 0..WORKER_THREADS {
+            let server = server.clone();
+            let chain_service = match &self.chain_config {
+                Some(chain_config) => Some(ChainService::new(chain_config)?),
+                None => None,
+            };
+            let router = Router {
+                proof_service: proof_service.clone(),
+                chain_service,
+                account_prover: self.account_prover.clone(),
+            };
+            workers.push(thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    router.handle(request);
+                }
+            }));
+        }
+
+        workers.into_iter().for_each(|worker| {
+            let _ = worker.join();
+        });
+
+        Ok(())
+    }
+}
+
+struct Router {
+    proof_service: ProofService,
+    // building claim transactions needs the L1 rpc
+    chain_service: Option<ChainService>,
+    // account proofs are served from the state verifier's tree
+    account_prover: Option<AccountProver>,
+}
+
+impl Router {
+    fn handle(&self, request: Request) {
+        let url = request.url().to_string();
+        let (path, query) = url.split_once('?').unwrap_or((url.as_str(), ""));
+        let params = query_params(query);
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+        let response = match (request.method(), segments.as_slice()) {
+            (Method::Get, ["proof", "signature", signature]) => proof_handler::by_signature(&self.proof_service, signature, &params),
+            (Method::Get, ["proof", "leaf_hash", leaf_hash]) => proof_handler::by_leaf_hash(&self.proof_service, leaf_hash, &params),
+            (Method::Get, ["proof", "leaf_index", leaf_index]) => proof_handler::by_leaf_index(&self.proof_service, leaf_index, &params),
+            (Method::Get, ["claims", claim_status]) => claim_handler::backlog(&self.proof_service, claim_status, &params),
+            (Method::Get, ["transitions", signature]) => withdrawal_handler::transitions(&self.proof_service, signature),
+            (Method::Get, ["metrics"]) => metrics_handler::render(&self.proof_service),
+            (Method::Get, ["withdrawals", address]) => withdrawal_handler::history(&self.proof_service, address, &params),
+            (Method::Get, ["claim", signature]) => match &self.chain_service {
+                Some(chain_service) => claim_handler::build(&self.proof_service, chain_service, signature, &params),
+                None => ApiResponse::error(503, "claim transactions are not available without the chain configuration"),
+            },
+            (Method::Get, ["account", pubkey]) => match &self.account_prover {
+                Some(account_prover) => account_handler::prove(account_prover, pubkey, &params),
+                None => ApiResponse::error(503, "account proofs are not available without the state verifier"),
+            },
+            _ => ApiResponse::not_found(&format!("no route for {} {}", request.method(), path)),
+        };
+
+        let content_type = Header::from_bytes(&b"Content-Type"[..], response.content_type.as_bytes()).unwrap();
+        let http_response = Response::from_string(response.payload())
+            .with_status_code(response.status)
+            .with_header(content_type);
+        if let Err(e) = request.respond(http_response) {
+            error!("write api response fail. url: {}, err: {:?}", url, e);
+        }
+    }
+}
+
+// `a=1&b=2` into a map with names and values percent-decoded, a repeated name keeps the last value
+fn query_params(query: &str) -> HashMap<String, String> {
+    form_urlencoded::parse(query.as_bytes())
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect()
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::api::server::query_params;
+
+    #[test]
+    fn test_query_params() {
+        let params = query_params("status=detected%2Cproven&cursor=MTA6Mw%3D%3D&role=sender&role=any&note=a+b%20c");
+        assert_eq!(params["status"], "detected,proven");
+        assert_eq!(params["cursor"], "MTA6Mw==");
+        assert_eq!(params["role"], "any");
+        assert_eq!(params["note"], "a b c");
+        assert!(query_params("").is_empty());
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::HashSet;
+use std::io::ErrorKind;
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tungstenite::{Message, WebSocket};
+
+use crate::common::node_error::NodeError;
+use crate::models::bridge_transaction_model::WithdrawalProof;
+use crate::models::proof_event_model::ProofEventRow;
+use crate::repositories::proof_event_repo::ProofEventRepo;
+use crate::services::proof_service::ProofService;
+use crate::utils::store_util::PgConnectionPool;
+use crate::utils::uuid_util::generate_uuid;
+
+// how often a connection looks for new proof events
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// the most events replayed or pushed in one poll
+const BATCH_EVENTS: i64 = 100;
+
+/// A client request, e.g. `{"op": "subscribe", "signatures": ["..."], "addresses": ["..."], "last_event_id": 42}`.
+///
+/// Every pushed proof carries its `event_id`, a client that reconnects with the last id it saw
+/// receives the proofs persisted in between. Without `last_event_id` only new proofs are pushed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SubscribeRequest {
+    pub op: String,
+    #[serde(default)]
+    pub signatures: Vec<String>,
+    #[serde(default)]
+    pub addresses: Vec<String>,
+    #[serde(default)]
+    pub last_event_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Subscription {
+    signatures: HashSet<String>,
+    addresses: HashSet<String>,
+}
+
+impl Subscription {
+    pub fn add(&mut self, request: &SubscribeRequest) {
+        self.signatures.extend(request.signatures.iter().cloned());
+        self.addresses.extend(request.addresses.iter().cloned());
+    }
+
+    pub fn remove(&mut self, request: &SubscribeRequest) {
+        request.signatures.iter().for_each(|s| { self.signatures.remove(s); });
+        request.addresses.iter().for_each(|a| { self.addresses.remove(a); });
+    }
+
+    pub fn matches(&self, event: &ProofEventRow) -> bool {
+        self.signatures.contains(&event.signature)
+            || self.addresses.contains(&event.sender)
+            || self.addresses.contains(&event.recipient)
+    }
+}
+
+/// Accept websocket connections and push the proofs of subscribed withdrawals.
+pub fn serve(address: &str, pool: PgConnectionPool) -> Result<(), NodeError> {
+    let listener = TcpListener::bind(address).map_err(|e| {
+        NodeError::new(generate_uuid(), format!("start subscription server fail. address: {}, err: {:?}", address, e))
+    })?;
+    info!("subscription server listening on {}", address);
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                error!("accept subscription connection fail. {:?}", e);
+                continue;
+            }
+        };
+
+        let pool = pool.clone();
+        let _ = thread::spawn(move || {
+            let peer = stream.peer_addr().map(|a| a.to_string()).unwrap_or_default();
+            if let Err(e) = Connection::accept(stream, pool).and_then(|mut c| c.run()) {
+                info!("subscription connection closed. peer: {}, reason: {}", peer, e.message);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+struct Connection {
+    socket: WebSocket<TcpStream>,
+    proof_service: ProofService,
+    event_repo: ProofEventRepo,
+    subscription: Subscription,
+    // the last event id pushed or skipped, `None` until the first subscribe
+    cursor: Option<i64>,
+}
+
+impl Connection {
+    fn accept(stream: TcpStream, pool: PgConnectionPool) -> Result<Self, NodeError> {
+        // reads time out so the connection can poll for events between client messages
+        stream.set_read_timeout(Some(POLL_INTERVAL)).map_err(|e| {
+            NodeError::new(generate_uuid(), format!("set read timeout fail. {:?}", e))
+        })?;
+        let socket = tungstenite::accept(stream).map_err(|e| {
+            NodeError::new(generate_uuid(), format!("websocket handshake fail. {:?}", e))
+        })?;
+
+        Ok(Connection {
+            socket,
+            proof_service: ProofService::new(pool.clone()),
+            event_repo: ProofEventRepo { pool: Box::from(pool) },
+            subscription: Subscription::default(),
+            cursor: None,
+        })
+    }
+
+    fn run(&mut self) -> Result<(), NodeError> {
+        loop {
+            match self.socket.read() {
+                Ok(Message::Text(text)) => self.on_request(&text)?,
+                Ok(Message::Close(_)) => return Err(NodeError::new(generate_uuid(), "closed by client".to_string())),
+                Ok(_) => {}
+                Err(tungstenite::Error::Io(e)) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+                Err(e) => return Err(NodeError::new(generate_uuid(), format!("{:?}", e))),
+            }
+
+            self.push_events()?;
+        }
+    }
+
+    fn on_request(&mut self, text: &str) -> Result<(), NodeError> {
+        let request: SubscribeRequest = match serde_json::from_str(text) {
+            Ok(request) => request,
+            Err(e) => return self.send(json!({ "type": "error", "message": format!("invalid request: {}", e) })),
+        };
+
+        match request.op.as_str() {
+            "subscribe" => {
+                self.subscription.add(&request);
+                // an explicit resume point wins, otherwise keep the position of the connection
+                let cursor = match (request.last_event_id, self.cursor) {
+                    (Some(last_event_id), _) => last_event_id,
+                    (None, Some(cursor)) => cursor,
+                    (None, None) => self.event_repo.last_id()?,
+                };
+                self.cursor = Some(cursor);
+                self.send(json!({ "type": "subscribed", "last_event_id": cursor }))
+            }
+            "unsubscribe" => {
+                self.subscription.remove(&request);
+                self.send(json!({ "type": "unsubscribed" }))
+            }
+            other => self.send(json!({ "type": "error", "message": format!("unknown op: {}", other) })),
+        }
+    }
+
+    fn push_events(&mut self) -> Result<(), NodeError> {
+        let mut cursor = match self.cursor {
+            Some(cursor) => cursor,
+            None => return Ok(()),
+        };
+
+        loop {
+            let events = self.event_repo.after(cursor, BATCH_EVENTS)?;
+            for event in events.iter() {
+                if self.subscription.matches(event) {
+                    if let Some(proof) = self.proof_service.find_by_signature(&event.signature)? {
+                        self.send_proof(event.id, &proof)?;
+                    }
+                }
+                cursor = event.id;
+                self.cursor = Some(cursor);
+            }
+
+            if (events.len() as i64) < BATCH_EVENTS {
+                return Ok(());
+            }
+        }
+    }
+
+    fn send_proof(&mut self, event_id: i64, proof: &WithdrawalProof) -> Result<(), NodeError> {
+        self.send(json!({ "type": "proof", "event_id": event_id, "proof": proof }))
+    }
+
+    fn send(&mut self, message: serde_json::Value) -> Result<(), NodeError> {
+        self.socket.send(Message::Text(message.to_string())).map_err(|e| {
+            NodeError::new(generate_uuid(), format!("send message fail. {:?}", e))
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::api::subscription::{SubscribeRequest, Subscription};
+    use crate::models::proof_event_model::ProofEventRow;
+
+    fn prepare_event(signature: &str, sender: &str, recipient: &str) -> ProofEventRow {
+        ProofEventRow {
+            id: 1,
+            signature: signature.to_string(),
+            sender: sender.to_string(),
+            recipient: recipient.to_string(),
+            root_program_slot: 9,
+            created_on: chrono::NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn test_subscription() {
+        let request: SubscribeRequest = serde_json::from_str(
+            r#"{"op": "subscribe", "signatures": ["sig"], "addresses": ["alice"], "last_event_id": 3}"#).unwrap();
+        assert_eq!(request.last_event_id, Some(3));
+
+        let mut subscription = Subscription::default();
+        subscription.add(&request);
+        assert!(subscription.matches(&prepare_event("sig", "bob", "bob")));
+        assert!(subscription.matches(&prepare_event("other", "bob", "alice")));
+        assert!(!subscription.matches(&prepare_event("other", "bob", "bob")));
+
+        subscription.remove(&SubscribeRequest { op: "unsubscribe".to_string(), addresses: vec!["alice".to_string()], ..Default::default() });
+        assert!(!subscription.matches(&prepare_event("other", "bob", "alice")));
+    }
+}
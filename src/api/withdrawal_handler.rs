@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDateTime};
+
+use crate::api::response::ApiResponse;
+use crate::models::bridge_transaction_model::{AddressRole, BridgeLifecycle, WithdrawalQuery};
+use crate::services::proof_service::ProofService;
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+// GET /withdrawals/{address}?role=sender|recipient|any&status=detected,proven&from_slot=&to_slot=
+//     &from_time=&to_time=&cursor=&limit=
+// times are unix seconds
+pub fn history(service: &ProofService, address: &str, params: &HashMap<String, String>) -> ApiResponse {
+    match parse_query(address, params) {
+        Ok(query) => match service.history(&query) {
+            Ok(page) => ApiResponse::ok(&page),
+            Err(e) => ApiResponse::internal(&e),
+        },
+        Err(message) => ApiResponse::bad_request(&message),
+    }
+}
+
+// GET /transitions/{l2 signature}
+// the lifecycle transitions of a withdrawal, oldest first
+pub fn transitions(service: &ProofService, signature: &str) -> ApiResponse {
+    match service.transitions(signature) {
+        Ok(transitions) => ApiResponse::ok(&transitions),
+        Err(e) => ApiResponse::internal(&e),
+    }
+}
+
+fn parse_query(address: &str, params: &HashMap<String, String>) -> Result<WithdrawalQuery, String> {
+    let role = match params.get("role").map(|s| s.as_str()) {
+        None | Some("any") => AddressRole::Any,
+        Some("sender") => AddressRole::Sender,
+        Some("recipient") => AddressRole::Recipient,
+        Some(other) => return Err(format!("unknown role: {}", other)),
+    };
+
+    let statuses = match params.get("status") {
+        Some(statuses) => statuses.split(',')
+            .map(BridgeLifecycle::from_str)
+            .collect::<Result<Vec<_>, _>>()?,
+        None => vec![],
+    };
+
+    let cursor = parse_cursor(params)?;
+    let limit = parse_limit(params)?;
+
+    Ok(WithdrawalQuery {
+        address: address.to_string(),
+        role,
+        statuses,
+        from_slot: parse_number(params, "from_slot")?,
+        to_slot: parse_number(params, "to_slot")?,
+        from_time: parse_time(params, "from_time")?,
+        to_time: parse_time(params, "to_time")?,
+        cursor,
+        limit,
+    })
+}
+
+// `slot:id` of the last row of the previous page
+pub(crate) fn parse_cursor(params: &HashMap<String, String>) -> Result<Option<(i64, i64)>, String> {
+    params.get("cursor")
+        .map(|cursor| cursor.split_once(':')
+            .and_then(|(slot, id)| Some((slot.parse().ok()?, id.parse().ok()?)))
+            .ok_or_else(|| format!("invalid cursor: {}", cursor)))
+        .transpose()
+}
+
+pub(crate) fn parse_limit(params: &HashMap<String, String>) -> Result<i64, String> {
+    let limit = parse_number(params, "limit")?.unwrap_or(DEFAULT_LIMIT);
+    if limit <= 0 || limit > MAX_LIMIT {
+        return Err(format!("limit must be in [1, {}]", MAX_LIMIT));
+    }
+
+    Ok(limit)
+}
+
+pub(crate) fn parse_number(params: &HashMap<String, String>, name: &str) -> Result<Option<i64>, String> {
+    params.get(name)
+        .map(|value| value.parse::<i64>().map_err(|_| format!("{} must be an integer", name)))
+        .transpose()
+}
+
+fn parse_time(params: &HashMap<String, String>, name: &str) -> Result<Option<NaiveDateTime>, String> {
+    parse_number(params, name)?
+        .map(|seconds| DateTime::from_timestamp(seconds, 0).map(|t| t.naive_utc()).ok_or_else(|| format!("{} is out of range", name)))
+        .transpose()
+}
+
+#[cfg(test)]
+pub mod test {
+    use std::collections::HashMap;
+
+    use crate::api::withdrawal_handler::parse_query;
+    use crate::models::bridge_transaction_model::{AddressRole, BridgeLifecycle};
+
+    fn params(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let query = parse_query("addr", &params(&[])).unwrap();
+        assert_eq!(query.role, AddressRole::Any);
+        assert!(query.statuses.is_empty() && query.cursor.is_none());
+        assert_eq!(query.limit, 50);
+
+        let query = parse_query("addr", &params(&[
+            ("role", "sender"), ("status", "proven,claimed"), ("cursor", "12:7"), ("limit", "10"), ("from_time", "60"),
+        ])).unwrap();
+        assert_eq!(query.role, AddressRole::Sender);
+        assert_eq!(query.statuses, vec![BridgeLifecycle::Proven, BridgeLifecycle::Claimed]);
+        assert_eq!(query.cursor, Some((12, 7)));
+        assert_eq!(query.limit, 10);
+        assert_eq!(query.from_time.unwrap().and_utc().timestamp(), 60);
+
+        assert!(parse_query("addr", &params(&[("status", "lost")])).is_err());
+        assert!(parse_query("addr", &params(&[("cursor", "12")])).is_err());
+        assert!(parse_query("addr", &params(&[("limit", "0")])).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+use log::{error, info, warn};
+
+use crate::common::node_configs::{ChainConfiguration, StoreConfiguration};
+use crate::common::node_error::NodeError;
+use crate::models::brief_reclaim_model::BriefReclaimRecord;
+use crate::repositories::brief_reclaim_repo::BriefReclaimRepo;
+use crate::repositories::brief_repo::BriefRepo;
+use crate::services::chain_service::ChainService;
+use crate::utils::store_util::{create_pool, PgConnectionPool};
+use crate::utils::time_util;
+
+// the most brief accounts closed in one round
+const BATCH_BRIEFS: i64 = 50;
+
+/// Close the brief accounts of the fraud proof program once they can no longer be challenged.
+///
+/// A brief is closed when the tally moved `brief_challenge_window_slots` past it and its slot is
+/// covered by an L1 commitment. Briefs with an open challenge, local or on chain, are kept.
+pub struct BriefCollector {
+    client_pool: Option<PgConnectionPool>,
+    chain_service: Option<ChainService>,
+    store_config: Option<StoreConfiguration>,
+    chain_config: Option<ChainConfiguration>,
+}
+
+impl BriefCollector {
+    pub fn new() -> Self {
+        Self {
+            client_pool: None,
+            chain_service: None,
+            store_config: None,
+            chain_config: None,
+        }
+    }
+
+    pub fn load_store_config(mut self, store_config: &StoreConfiguration) -> Self {
+        self.store_config = Some(store_config.clone());
+        self
+    }
+
+    pub fn load_chain_config(mut self, chain_config: &ChainConfiguration) -> Self {
+        self.chain_config = Some(chain_config.clone());
+        self
+    }
+
+    pub fn connect_store(&mut self) -> Result<(), NodeError> {
+        let pool = create_pool(self.store_config.clone().unwrap(), 2);
+
+        self.client_pool = Some(pool);
+
+        Ok(())
+    }
+
+    pub fn connect_chain(&mut self) -> Result<(), NodeError> {
+        let chain_service = ChainService::new(&self.chain_config.clone().unwrap())?;
+
+        self.chain_service = Some(chain_service);
+
+        Ok(())
+    }
+
+    pub fn start(&mut self) -> Result<(), NodeError> {
+        self.connect_store()?;
+        self.connect_chain()?;
+
+        loop {
+            match self.collect() {
+                Ok(0) => time_util::sleep_seconds(10),
+                Ok(_) => {}
+                Err(e) => {
+                    error!("{:?}", e);
+                    time_util::sleep_seconds(10);
+                }
+            }
+        }
+    }
+
+    // the last slot whose brief can be closed, `None` while no brief is out of the window
+    fn final_slot(&mut self) -> Option<i64> {
+        let window = self.chain_config.as_ref().unwrap().brief_challenge_window_slots;
+        let chain_service = self.chain_service.as_mut().unwrap();
+
+        let present_slot = chain_service.get_present_slot()?;
+        let committed_slot = chain_service.get_latest_slot_from_chain()?;
+
+        present_slot.checked_sub(window).map(|slot| slot.min(committed_slot) as i64)
+    }
+
+    // close one batch of briefs, returns the number of closed accounts
+    fn collect(&mut self) -> Result<usize, NodeError> {
+        let final_slot = match self.final_slot() {
+            Some(slot) => slot,
+            None => return Ok(0),
+        };
+
+        let brief_repo = BriefRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+        let reclaim_repo = BriefReclaimRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+
+        // briefs with an open local challenge are left out by the query
+        let briefs = brief_repo.unreclaimed(final_slot, BATCH_BRIEFS)?;
+
+        let chain_service = self.chain_service.as_mut().unwrap();
+        let mut closed = 0;
+        for brief in briefs {
+            match chain_service.is_challenge_account_exist(brief.slot as u64) {
+                Ok(false) => {}
+                Ok(true) => {
+                    info!("brief is challenged on chain, keep it. slot: {}", brief.slot);
+                    brief_repo.mark_challenged(brief.slot)?;
+                    continue;
+                }
+                // not knowing is no reason to close it
+                Err(e) => {
+                    warn!("challenge account unknown, skip the brief. slot: {}, err: {:?}", brief.slot, e);
+                    continue;
+                }
+            }
+
+            let lamports = match chain_service.destroy_brief_account(brief.slot as u64) {
+                Some(lamports) => lamports,
+                None => continue,
+            };
+
+            reclaim_repo.insert(BriefReclaimRecord {
+                slot: brief.slot,
+                lamports: lamports as i64,
+            })?;
+            info!("brief account closed. slot: {}, reclaimed lamports: {}", brief.slot, lamports);
+            closed += 1;
+        }
+
+        Ok(closed)
+    }
+}
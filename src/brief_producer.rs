@@ -0,0 +1,238 @@
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use itertools::Itertools;
+use log::{error, info};
+use solana_sdk::hash::Hash;
+
+use crate::common::node_configs::{ChainConfiguration, StoreConfiguration};
+use crate::common::node_error::NodeError;
+use crate::contract::chain_brief::ChainBrief;
+use crate::models::account_audit_row::AccountAuditRow;
+use crate::models::brief_model::BriefRecord;
+use crate::repositories::account_audit_repo::AccountAuditRepo;
+use crate::repositories::block_repo::BlockRepo;
+use crate::repositories::brief_repo::BriefRepo;
+use crate::services::chain_service::ChainService;
+use crate::smt::state_tree::{SharedStateTree, StateTree};
+use crate::utils::account_util::compute_slot_ha;
+use crate::utils::store_util::{create_pool, PgConnectionPool};
+use crate::utils::time_util;
+use crate::utils::uuid_util::generate_uuid;
+
+// the most L2 slots read from account_audit in one round
+const BATCH_SLOTS: i64 = 100;
+
+/// Publish a brief of every L2 slot to the fraud proof program.
+///
+/// A brief is stored in the `brief` table before it is submitted, so a restart submits the same
+/// brief again instead of computing a new one. Production resumes after the tally account's `present_slot`.
+pub struct BriefProducer {
+    client_pool: Option<PgConnectionPool>,
+    chain_service: Option<ChainService>,
+    state_tree: Option<SharedStateTree>,
+    store_config: Option<StoreConfiguration>,
+    chain_config: Option<ChainConfiguration>,
+}
+
+impl BriefProducer {
+    pub fn new() -> Self {
+        Self {
+            client_pool: None,
+            chain_service: None,
+            state_tree: None,
+            store_config: None,
+            chain_config: None,
+        }
+    }
+
+    pub fn load_store_config(mut self, store_config: &StoreConfiguration) -> Self {
+        self.store_config = Some(store_config.clone());
+        self
+    }
+
+    pub fn load_chain_config(mut self, chain_config: &ChainConfiguration) -> Self {
+        self.chain_config = Some(chain_config.clone());
+        self
+    }
+
+    pub fn load_state_tree(mut self, state_tree: &SharedStateTree) -> Self {
+        self.state_tree = Some(state_tree.clone());
+        self
+    }
+
+    pub fn connect_store(&mut self) -> Result<(), NodeError> {
+        let pool = create_pool(self.store_config.clone().unwrap(), 2);
+
+        self.client_pool = Some(pool);
+
+        Ok(())
+    }
+
+    pub fn connect_chain(&mut self) -> Result<(), NodeError> {
+        let mut chain_service = ChainService::new(&self.chain_config.clone().unwrap())?;
+
+        if !chain_service.create_state_account() || !chain_service.create_tally_account() {
+            return Err(NodeError::new(generate_uuid(), "prepare fraud proof accounts fail".to_string()));
+        }
+
+        self.chain_service = Some(chain_service);
+
+        Ok(())
+    }
+
+    pub fn open_state(&mut self) -> Result<(), NodeError> {
+        let state_tree = StateTree::open(Path::new("./relayer/verifier/smt"))?;
+        self.state_tree = Some(Arc::new(Mutex::new(state_tree)));
+
+        Ok(())
+    }
+
+    pub fn start(&mut self) -> Result<(), NodeError> {
+        self.connect_store()?;
+        self.connect_chain()?;
+        if self.state_tree.is_none() {
+            self.open_state()?;
+        }
+
+        loop {
+            let present_slot = match self.chain_service.as_ref().unwrap().get_present_slot() {
+                Some(present_slot) => present_slot as i64,
+                None => {
+                    error!("read present slot fail, retry later");
+                    time_util::sleep_seconds(1);
+                    continue;
+                }
+            };
+            let last_slot = self.last_audit_slot();
+            if last_slot <= present_slot {
+                time_util::sleep_seconds(1);
+                continue;
+            }
+
+            if let Err(e) = self.produce(present_slot, (present_slot + BATCH_SLOTS).min(last_slot)) {
+                error!("{:?}", e);
+                time_util::sleep_seconds(1);
+            }
+        }
+    }
+
+    // the latest slot the plugin finished writing, its account_audit rows are complete
+    fn last_audit_slot(&self) -> i64 {
+        let mut repo = BlockRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+
+        repo.show().map(|row| row.slot).unwrap_or(0)
+    }
+
+    // publish the briefs of the slots in `(present_slot, to_slot]` that have account changes
+    fn produce(&mut self, present_slot: i64, to_slot: i64) -> Result<(), NodeError> {
+        self.catch_up(present_slot)?;
+
+        let mut old_ha = self.hash_account(present_slot)?;
+
+        let repo = AccountAuditRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+        let rows = repo.range(present_slot + 1, to_slot)?;
+
+        for (slot, slot_rows) in &rows.into_iter().group_by(|row| row.slot) {
+            let slot_rows: Vec<AccountAuditRow> = slot_rows.collect();
+            let brief = self.brief(slot, &slot_rows, &old_ha)?;
+
+            if !self.chain_service.as_mut().unwrap().create_brief_account(brief.clone()) {
+                return Err(NodeError::new(generate_uuid(), format!("submit brief fail, retry later. slot: {}", slot)));
+            }
+            info!("brief published. slot: {}, hash account: {}, transactions: {}",
+                slot, brief.hash_account, brief.transaction_number);
+
+            old_ha = parse_hash(&brief.hash_account)?;
+        }
+
+        Ok(())
+    }
+
+    // the stored brief of `slot`, or a new one computed from its account_audit rows
+    fn brief(&mut self, slot: i64, rows: &[AccountAuditRow], old_ha: &Hash) -> Result<ChainBrief, NodeError> {
+        let repo = BriefRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+        let mut state_tree = self.state_tree.as_ref().unwrap().lock().unwrap();
+        if let Ok(row) = repo.find_by_slot(slot) {
+            if state_tree.applied_slot()? < slot as u64 {
+                state_tree.apply(slot as u64, rows)?;
+            }
+            return Ok(ChainBrief {
+                slot: row.slot as u64,
+                root_hash: row.root_hash,
+                hash_account: row.hash_account,
+                transaction_number: row.transaction_number as u32,
+            });
+        }
+
+        // the tree is applied before the brief is stored, a crash in between or the state verifier leaves
+        // the tree at or after `slot`
+        let root = if state_tree.applied_slot()? < slot as u64 {
+            state_tree.apply(slot as u64, rows)?
+        } else {
+            state_tree.root_at(slot as u64)?
+        };
+        drop(state_tree);
+
+        let (hash_account, transaction_number) = compute_slot_ha(rows, old_ha)?;
+        let brief = ChainBrief {
+            slot: slot as u64,
+            root_hash: bs58::encode(&root).into_string(),
+            hash_account: hash_account.to_string(),
+            transaction_number,
+        };
+
+        repo.insert(vec![BriefRecord::from(brief.clone())])?;
+
+        Ok(brief)
+    }
+
+    // bring the tree to `present_slot` when the briefs before it were published by another node
+    fn catch_up(&mut self, present_slot: i64) -> Result<(), NodeError> {
+        let mut state_tree = self.state_tree.as_ref().unwrap().lock().unwrap();
+        let applied_slot = state_tree.applied_slot()? as i64;
+        if applied_slot >= present_slot {
+            return Ok(());
+        }
+
+        let repo = AccountAuditRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+        let rows = repo.range(applied_slot + 1, present_slot)?;
+
+        for (slot, slot_rows) in &rows.into_iter().group_by(|row| row.slot) {
+            let slot_rows: Vec<AccountAuditRow> = slot_rows.collect();
+            state_tree.apply(slot as u64, &slot_rows)?;
+        }
+        if state_tree.applied_slot()? < present_slot as u64 {
+            state_tree.apply(present_slot as u64, &[])?;
+        }
+        info!("brief state tree caught up. from slot: {}, to slot: {}", applied_slot, present_slot);
+
+        Ok(())
+    }
+
+    // the hash account the brief after `present_slot` chains from
+    fn hash_account(&self, present_slot: i64) -> Result<Hash, NodeError> {
+        if present_slot == 0 {
+            return Ok(Hash::default());
+        }
+
+        let repo = BriefRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+        let hash_account = match repo.find_by_slot(present_slot) {
+            Ok(row) => row.hash_account,
+            Err(_) => self.chain_service.as_ref().unwrap().fetch_brief(present_slot as u64)
+                .map(|brief| brief.hash_account)
+                .ok_or_else(|| {
+                    NodeError::new(generate_uuid(), format!("find brief fail. slot: {}", present_slot))
+                })?,
+        };
+
+        parse_hash(&hash_account)
+    }
+}
+
+fn parse_hash(hash_account: &str) -> Result<Hash, NodeError> {
+    Hash::from_str(hash_account).map_err(|e| {
+        NodeError::new(generate_uuid(), format!("invalid hash account: {}, err: {:?}", hash_account, e))
+    })
+}
@@ -0,0 +1,165 @@
+use log::{error, info};
+use solana_sdk::signature::Signature;
+use std::str::FromStr;
+
+use crate::common::node_configs::{ChainConfiguration, StoreConfiguration};
+use crate::common::node_error::NodeError;
+use crate::contract::chain_brief::ChainBrief;
+use crate::contract::chain_challenge::ChainChallenge;
+use crate::models::challenge_model::{ChallengeRecord, ChallengeStatus};
+use crate::repositories::challenge_repo::ChallengeRepo;
+use crate::services::chain_challenge_service::ChallengeSubmission;
+use crate::services::chain_service::ChainService;
+use crate::utils::store_util::{create_pool, PgConnectionPool};
+use crate::utils::time_util;
+
+pub struct Challenger {
+    client_pool: Option<PgConnectionPool>,
+    chain_service: Option<ChainService>,
+    store_config: Option<StoreConfiguration>,
+    chain_config: Option<ChainConfiguration>,
+}
+
+impl Challenger {
+    pub fn new() -> Self {
+        Self {
+            client_pool: None,
+            chain_service: None,
+            store_config: None,
+            chain_config: None,
+        }
+    }
+
+    pub fn load_store_config(mut self, store_config: &StoreConfiguration) -> Self {
+        self.store_config = Some(store_config.clone());
+        self
+    }
+
+    pub fn load_chain_config(mut self, chain_config: &ChainConfiguration) -> Self {
+        self.chain_config = Some(chain_config.clone());
+        self
+    }
+
+    pub fn connect_store(&mut self) -> Result<(), NodeError> {
+        let pool = create_pool(self.store_config.clone().unwrap(), 2);
+
+        self.client_pool = Some(pool);
+
+        Ok(())
+    }
+
+    pub fn connect_chain(&mut self) -> Result<(), NodeError> {
+        let chain_service = ChainService::new(&self.chain_config.clone().unwrap())?;
+
+        self.chain_service = Some(chain_service);
+
+        Ok(())
+    }
+
+    pub fn start(&mut self) -> Result<(), NodeError> {
+        self.connect_store()?;
+        self.connect_chain()?;
+
+        loop {
+            let open_challenges = self.open_challenges().unwrap_or_default();
+            for challenge in open_challenges {
+                match challenge.status() {
+                    Ok(ChallengeStatus::Pending) => self.submit(challenge),
+                    Ok(ChallengeStatus::Submitted) => self.track(challenge),
+                    Ok(_) => {}
+                    Err(e) => error!("challenge skipped. slot: {}, err: {}", challenge.slot, e),
+                }
+            }
+
+            time_util::sleep_seconds(1);
+        }
+    }
+
+    fn open_challenges(&self) -> Result<Vec<ChallengeRecord>, NodeError> {
+        let repo = ChallengeRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+
+        let rows = repo.open()?;
+
+        Ok(rows.into_iter().map(ChallengeRecord::from).collect())
+    }
+
+    fn challenge_update(&self, record: ChallengeRecord) -> Result<ChallengeRecord, NodeError> {
+        let repo = ChallengeRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+
+        let row = repo.update(record)?;
+
+        Ok(ChallengeRecord::from(row))
+    }
+
+    fn submit(&mut self, mut challenge: ChallengeRecord) {
+        let roots = (<[u8; 32]>::try_from(challenge.local_root.as_slice()), <[u8; 32]>::try_from(challenge.chain_root.as_slice()));
+        let (local_root, chain_root) = match roots {
+            (Ok(local_root), Ok(chain_root)) => (local_root, chain_root),
+            _ => {
+                error!("challenge roots are not 32 bytes, challenge failed. slot: {}, local root: {}, chain root: {}",
+                    challenge.slot, hex::encode(&challenge.local_root), hex::encode(&challenge.chain_root));
+                challenge.status = ChallengeStatus::Failed.as_str().to_string();
+                if let Err(e) = self.challenge_update(challenge) {
+                    error!("update challenge fail. {:?}", e);
+                }
+                return;
+            }
+        };
+
+        let chain_challenge = ChainChallenge {
+            slot: challenge.slot as u64,
+            local_root,
+            chain_root,
+            brief: ChainBrief {
+                slot: challenge.slot as u64,
+                root_hash: bs58::encode(&local_root).into_string(),
+                hash_account: challenge.hash_account.clone(),
+                transaction_number: challenge.transaction_number as u32,
+            },
+        };
+
+        match self.chain_service.as_ref().unwrap().submit_challenge(chain_challenge) {
+            Ok(ChallengeSubmission::Sent(signature)) => {
+                info!("challenge submitted. slot: {}, signature: {}", challenge.slot, signature);
+                challenge.signature = signature.to_string();
+                challenge.status = ChallengeStatus::Submitted.as_str().to_string();
+            }
+            // ours from a lost update or another challenger's, either way the slot is challenged
+            Ok(ChallengeSubmission::Exists) => {
+                info!("challenge account already on chain, challenge resolved. slot: {}", challenge.slot);
+                challenge.status = ChallengeStatus::Accepted.as_str().to_string();
+            }
+            Err(e) => {
+                error!("submit challenge fail, retry later. slot: {}, err: {:?}", challenge.slot, e);
+                return;
+            }
+        }
+        if let Err(e) = self.challenge_update(challenge) {
+            error!("update challenge fail. {:?}", e);
+        }
+    }
+
+    fn track(&mut self, mut challenge: ChallengeRecord) {
+        let chain_service = self.chain_service.as_ref().unwrap();
+
+        let signature = match Signature::from_str(&challenge.signature) {
+            Ok(signature) => signature,
+            Err(e) => {
+                error!("invalid challenge signature. slot: {}, err: {:?}", challenge.slot, e);
+                return;
+            }
+        };
+
+        let status = match chain_service.get_challenge_status(&signature) {
+            Some(true) => ChallengeStatus::Accepted,
+            Some(false) => ChallengeStatus::Rejected,
+            None => return,
+        };
+
+        info!("challenge resolved. slot: {}, signature: {}, status: {:?}", challenge.slot, signature, status);
+        challenge.status = status.as_str().to_string();
+        if let Err(e) = self.challenge_update(challenge) {
+            error!("update challenge fail. {:?}", e);
+        }
+    }
+}
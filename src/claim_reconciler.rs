@@ -0,0 +1,143 @@
+use log::{error, info, warn};
+
+use crate::common::node_configs::{ChainConfiguration, StoreConfiguration};
+use crate::common::node_error::NodeError;
+use crate::models::bridge_transaction_model::{BridgeTxRow, ClaimStatus};
+use crate::repositories::bridge_tx_repo::BridgeTxRepo;
+use crate::services::chain_claim_service;
+use crate::services::chain_service::ChainService;
+use crate::utils::store_util::{create_pool, PgConnectionPool};
+use crate::utils::time_util;
+
+// the most withdrawals checked in one round
+const BATCH_WITHDRAWALS: i64 = 100;
+
+// claimed withdrawals are checked again for later claim transactions every this many passes
+const RECHECK_PASSES: u64 = 30;
+
+/// Reconcile the claim status of proven withdrawals with their claimed marker accounts on L1.
+///
+/// Unclaimed withdrawals are checked every pass, claimed ones every `RECHECK_PASSES` passes so that
+/// claim transactions hitting an already claimed withdrawal get flagged as double claims.
+pub struct ClaimReconciler {
+    client_pool: Option<PgConnectionPool>,
+    chain_service: Option<ChainService>,
+    store_config: Option<StoreConfiguration>,
+    chain_config: Option<ChainConfiguration>,
+}
+
+impl ClaimReconciler {
+    pub fn new() -> Self {
+        Self {
+            client_pool: None,
+            chain_service: None,
+            store_config: None,
+            chain_config: None,
+        }
+    }
+
+    pub fn load_store_config(mut self, store_config: &StoreConfiguration) -> Self {
+        self.store_config = Some(store_config.clone());
+        self
+    }
+
+    pub fn load_chain_config(mut self, chain_config: &ChainConfiguration) -> Self {
+        self.chain_config = Some(chain_config.clone());
+        self
+    }
+
+    pub fn connect_store(&mut self) -> Result<(), NodeError> {
+        let pool = create_pool(self.store_config.clone().unwrap(), 2);
+
+        self.client_pool = Some(pool);
+
+        Ok(())
+    }
+
+    pub fn connect_chain(&mut self) -> Result<(), NodeError> {
+        let chain_service = ChainService::new(&self.chain_config.clone().unwrap())?;
+
+        self.chain_service = Some(chain_service);
+
+        Ok(())
+    }
+
+    pub fn start(&mut self) -> Result<(), NodeError> {
+        self.connect_store()?;
+        self.connect_chain()?;
+
+        let mut passes: u64 = 0;
+        loop {
+            if let Err(e) = self.reconcile(ClaimStatus::Unclaimed) {
+                error!("reconcile unclaimed withdrawals fail. {:?}", e);
+            }
+            if passes % RECHECK_PASSES == 0 {
+                if let Err(e) = self.reconcile(ClaimStatus::Claimed) {
+                    error!("reconcile claimed withdrawals fail. {:?}", e);
+                }
+            }
+            passes += 1;
+
+            time_util::sleep_seconds(10);
+        }
+    }
+
+    /// Check every proven withdrawal in `claim_status` once.
+    fn reconcile(&self, claim_status: ClaimStatus) -> Result<(), NodeError> {
+        let repo = BridgeTxRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+
+        let mut cursor = (0, 0);
+        loop {
+            let rows = repo.by_claim_status(claim_status, cursor, BATCH_WITHDRAWALS)?;
+            // one rpc for the whole batch tells which unclaimed withdrawals have a marker account now,
+            // an invalid leaf hash is reported by `reconcile_row`
+            let has_marker = match claim_status {
+                ClaimStatus::Unclaimed => {
+                    let leaf_hashes: Vec<[u8; 32]> = rows.iter()
+                        .map(|row| chain_claim_service::leaf_hash(row).unwrap_or_default())
+                        .collect();
+                    self.chain_service.as_ref().unwrap().are_claimed(&leaf_hashes)?
+                }
+                _ => vec![true; rows.len()],
+            };
+            for (row, _) in rows.iter().zip(has_marker).filter(|(_, has_marker)| *has_marker) {
+                if let Err(e) = self.reconcile_row(&repo, row) {
+                    error!("reconcile claim fail. signature: {}, err: {:?}", row.signature, e);
+                }
+            }
+
+            match rows.last() {
+                Some(last) => cursor = (last.slot, last.id),
+                None => return Ok(()),
+            }
+            if (rows.len() as i64) < BATCH_WITHDRAWALS {
+                return Ok(());
+            }
+        }
+    }
+
+    // `row` has a claimed marker account
+    fn reconcile_row(&self, repo: &BridgeTxRepo, row: &BridgeTxRow) -> Result<(), NodeError> {
+        let chain_service = self.chain_service.as_ref().unwrap();
+
+        let marker = match chain_service.claim_marker(&chain_claim_service::leaf_hash(row)?)? {
+            Some(marker) => marker,
+            None => return Ok(()),
+        };
+        // nothing new on the marker account since the last check
+        if row.claim_attempts as usize == marker.transactions {
+            return Ok(());
+        }
+
+        if marker.is_double_claimed() {
+            warn!("double claim. signature: {}, claim signature: {}, claim slot: {}, claims: {}",
+                row.signature, marker.signature, marker.slot, marker.claims);
+        } else {
+            info!("withdrawal claimed. signature: {}, claim signature: {}, claim slot: {}",
+                row.signature, marker.signature, marker.slot);
+        }
+        repo.record_claim(&row.signature, &marker)?;
+
+        Ok(())
+    }
+}
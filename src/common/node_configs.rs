@@ -6,6 +6,8 @@ pub struct NodeConfiguration {
     pub chain: ChainConfiguration,
     pub store: StoreConfiguration,
     pub contract: ContractConfiguration,
+    #[serde(default)]
+    pub api: ApiConfiguration,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -18,11 +20,94 @@ pub struct ContractConfiguration {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ChainConfiguration {
     pub url: String,
+    // websocket endpoint of the L1 node, derived from `url` when empty
+    #[serde(default)]
+    pub ws_url: String,
+    // commitment of L1 reads, proofs against roots read below `finalized` are provisional until the root finalizes
+    #[serde(default = "default_commitment")]
+    pub read_commitment: String,
+    // commitment L1 transactions are sent and confirmed at
+    #[serde(default = "default_commitment")]
+    pub send_commitment: String,
     pub fraud_proof_native_program_id: String,
     // keypair base58 string
     pub execute_keypair: String,
     pub l1_root_mgr_program_id: String,
     pub l1_slots_account_pubkey: String,
+    // the authority the slots account must have, not checked when empty
+    #[serde(default)]
+    pub l1_root_mgr_authority: String,
+    // the roots of this many latest commitments are read again every round to catch a rewrite
+    #[serde(default = "default_root_recheck_count")]
+    pub root_recheck_count: u64,
+    // the L1 bridge program withdrawals are claimed from
+    #[serde(default)]
+    pub l1_bridge_program_id: String,
+    // submit a challenge to the fraud proof program when the local root mismatches the root on chain
+    #[serde(default)]
+    pub enable_challenger: bool,
+    // replay account_audit into the account smt and check the world state root of every L1 commitment
+    #[serde(default)]
+    pub enable_state_verifier: bool,
+    // publish a brief of every L2 slot to the fraud proof program
+    #[serde(default)]
+    pub enable_brief_producer: bool,
+    // close brief accounts whose challenge window elapsed and reclaim their rent
+    #[serde(default)]
+    pub enable_brief_collector: bool,
+    // a brief can be challenged until the tally moved this many slots past it
+    #[serde(default = "default_brief_challenge_window_slots")]
+    pub brief_challenge_window_slots: u64,
+    // the world state of the commitments within this many slots of the latest one stays provable
+    #[serde(default = "default_state_retention_slots")]
+    pub state_retention_slots: u64,
+    // claim proven withdrawals on L1 with the execute keypair once their challenge period elapsed
+    #[serde(default)]
+    pub enable_finalizer: bool,
+    // a withdrawal can be claimed this long after its root was committed on L1
+    #[serde(default = "default_challenge_period_seconds")]
+    pub challenge_period_seconds: u64,
+    // read the challenge period from the fraud proof state account, `challenge_period_seconds` when it is missing
+    #[serde(default)]
+    pub challenge_period_from_state: bool,
+    #[serde(default = "default_finalizer_max_attempts")]
+    pub finalizer_max_attempts: u32,
+    // micro-lamports per compute unit of the first claim attempt, doubled on every retry
+    #[serde(default)]
+    pub finalizer_priority_fee: u64,
+    #[serde(default = "default_finalizer_max_priority_fee")]
+    pub finalizer_max_priority_fee: u64,
+    // follow the claimed marker accounts of proven withdrawals on L1
+    #[serde(default)]
+    pub enable_claim_reconciler: bool,
+}
+
+fn default_commitment() -> String {
+    "confirmed".to_string()
+}
+
+fn default_root_recheck_count() -> u64 {
+    8
+}
+
+fn default_challenge_period_seconds() -> u64 {
+    604_800
+}
+
+fn default_finalizer_max_attempts() -> u32 {
+    5
+}
+
+fn default_finalizer_max_priority_fee() -> u64 {
+    1_000_000
+}
+
+fn default_state_retention_slots() -> u64 {
+    432_000
+}
+
+fn default_brief_challenge_window_slots() -> u64 {
+    216_000
 }
 
 
@@ -35,6 +120,32 @@ pub struct StoreConfiguration {
     pub schema: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ApiConfiguration {
+    // serve the http api
+    #[serde(default)]
+    pub enable: bool,
+    pub bind_address: String,
+    pub port: u16,
+    // websocket subscriptions to proof-ready events
+    #[serde(default = "default_ws_port")]
+    pub ws_port: u16,
+}
+
+fn default_ws_port() -> u16 {
+    9900
+}
+
+impl Default for ApiConfiguration {
+    fn default() -> Self {
+        ApiConfiguration {
+            enable: false,
+            bind_address: "127.0.0.1".to_string(),
+            port: 9899,
+            ws_port: default_ws_port(),
+        }
+    }
+}
 
 impl NodeConfiguration {
     pub fn load_from_file(file_name: &str) -> Result<NodeConfiguration, ConfigError> {
@@ -0,0 +1,89 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::contract::chain_brief::ChainBrief;
+
+#[derive(BorshSerialize, BorshDeserialize)]
+#[derive(Default)]
+#[derive(Debug, Clone)]
+#[derive(Eq, PartialEq)]
+pub struct ChainChallenge {
+    pub slot: u64,
+    pub local_root: [u8; 32],
+    pub chain_root: [u8; 32],
+    pub brief: ChainBrief,
+}
+
+
+impl ChainChallenge {
+    // slot: u64 needs 8 bytes
+    // local_root: [u8; 32] needs 32 bytes
+    // chain_root: [u8; 32] needs 32 bytes
+    // brief: ChainBrief needs ChainBrief::size() bytes
+    pub fn size() -> usize {
+        let slot_size: usize = 8;
+        let local_root_size: usize = 32;
+        let chain_root_size: usize = 32;
+        let brief_size: usize = ChainBrief::size();
+        let total_size: usize = slot_size + local_root_size + chain_root_size + brief_size;
+
+        return total_size;
+    }
+
+    //init
+    pub fn init_size() -> usize {
+        return Self::size();
+    }
+
+    //max
+    pub fn total_size() -> usize {
+        return Self::size();
+    }
+}
+
+
+#[cfg(test)]
+pub mod test {
+    use borsh::{BorshDeserialize, BorshSerialize};
+
+    use crate::contract::chain_brief::ChainBrief;
+    use crate::contract::chain_challenge::ChainChallenge;
+
+    fn prepare_challenge() -> ChainChallenge {
+        let slot: u64 = 11;
+        let brief = ChainBrief {
+            slot,
+            root_hash: "CodF5mXgscuEnvfYHVfKwGPosffRucTtAm4BQpyyjL8U".to_string(),
+            hash_account: "BsVLhVaLeZpVnwxWqUF4bnpfLurcKYLq576Xg34RX3yQ".to_string(),
+            transaction_number: 5,
+        };
+
+        let challenge = ChainChallenge {
+            slot,
+            local_root: [1u8; 32],
+            chain_root: [2u8; 32],
+            brief,
+        };
+
+        return challenge;
+    }
+
+
+    #[test]
+    fn test_basic() {
+        let challenge = prepare_challenge();
+        let mut data = Vec::new();
+        challenge.serialize(&mut data).unwrap();
+        println!("serialized data {:?}", data);
+        assert_eq!(ChainChallenge::size(), data.len());
+    }
+
+    #[test]
+    fn test_convert() {
+        let challenge_actual = prepare_challenge();
+        let mut data = Vec::new();
+        challenge_actual.serialize(&mut data).unwrap();
+        let challenge_expect = ChainChallenge::deserialize(&mut &data[..]).unwrap();
+        println!("deserialized data: {:?}", challenge_expect);
+        assert_eq!(challenge_expect, challenge_actual);
+    }
+}
@@ -0,0 +1,93 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+// the deepest bridge merkle tree a claim can prove
+pub const MAX_PROOF_LEN: usize = 32;
+
+#[derive(BorshSerialize, BorshDeserialize)]
+#[derive(Default)]
+#[derive(Debug, Clone)]
+#[derive(Eq, PartialEq)]
+pub struct ChainClaim {
+    pub root_slot: u64,
+    pub leaf_index: u64,
+    pub from: [u8; 32],
+    pub to: [u8; 32],
+    pub amount: u64,
+    pub bridge_tx_index: u64,
+    pub message_type: u8,
+    pub proof: Vec<[u8; 32]>,
+}
+
+
+impl ChainClaim {
+    // root_slot: u64 needs 8 bytes
+    // leaf_index: u64 needs 8 bytes
+    // from: [u8; 32] needs 32 bytes
+    // to: [u8; 32] needs 32 bytes
+    // amount: u64 needs 8 bytes
+    // bridge_tx_index: u64 needs 8 bytes
+    // message_type: u8 needs 1 byte
+    // proof: Vec<[u8; 32]> needs 4+32*n bytes
+    pub fn size(proof_len: usize) -> usize {
+        let root_slot_size: usize = 8;
+        let leaf_index_size: usize = 8;
+        let from_size: usize = 32;
+        let to_size: usize = 32;
+        let amount_size: usize = 8;
+        let bridge_tx_index_size: usize = 8;
+        let message_type_size: usize = 1;
+        let proof_size: usize = 4 + 32 * proof_len;
+        let total_size: usize = root_slot_size + leaf_index_size + from_size + to_size + amount_size
+            + bridge_tx_index_size + message_type_size + proof_size;
+
+        return total_size;
+    }
+
+    //init
+    pub fn init_size() -> usize {
+        return Self::size(0);
+    }
+
+    //max
+    pub fn total_size() -> usize {
+        return Self::size(MAX_PROOF_LEN);
+    }
+}
+
+
+#[cfg(test)]
+pub mod test {
+    use borsh::{BorshDeserialize, BorshSerialize};
+
+    use crate::contract::chain_claim::ChainClaim;
+
+    fn prepare_claim() -> ChainClaim {
+        ChainClaim {
+            root_slot: 12,
+            leaf_index: 3,
+            from: [1u8; 32],
+            to: [1u8; 32],
+            amount: 1_000,
+            bridge_tx_index: 3,
+            message_type: 0,
+            proof: vec![[2u8; 32], [3u8; 32]],
+        }
+    }
+
+    #[test]
+    fn test_basic() {
+        let claim = prepare_claim();
+        let mut data: Vec<u8> = Vec::new();
+        claim.serialize(&mut data).unwrap();
+        assert_eq!(ChainClaim::size(2), data.len());
+    }
+
+    #[test]
+    fn test_convert() {
+        let claim_actual = prepare_claim();
+        let mut data: Vec<u8> = Vec::new();
+        claim_actual.serialize(&mut data).unwrap();
+        let claim_expect = ChainClaim::deserialize(&mut &data[..]).unwrap();
+        assert_eq!(claim_expect, claim_actual);
+    }
+}
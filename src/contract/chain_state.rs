@@ -0,0 +1,36 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// The leading fields of the fraud proof program's anchor `State` account, read after its 8 byte
+/// discriminator. No IDL of the program is vendored here, so the layout is an assumption:
+/// `ChainStateService::fetch_state_account` checks the owner and the `account:State`
+/// discriminator before decoding, and the node falls back to `challenge_period_seconds` when
+/// the account does not match. Only read with `challenge_period_from_state` set.
+#[derive(BorshSerialize, BorshDeserialize)]
+#[derive(Default)]
+#[derive(Debug, Clone)]
+#[derive(Eq, PartialEq)]
+pub struct ChainState {
+    // seconds a committed root can be challenged
+    pub challenge_period: u64,
+}
+
+
+#[cfg(test)]
+pub mod test {
+    use borsh::{BorshDeserialize, BorshSerialize};
+
+    use crate::contract::chain_state::ChainState;
+
+    #[test]
+    fn test_convert() {
+        let state_actual = ChainState {
+            challenge_period: 604_800,
+        };
+        let mut data = Vec::new();
+        state_actual.serialize(&mut data).unwrap();
+        // fields appended to the account later are not read
+        data.extend_from_slice(&[1u8; 16]);
+        let state_expect = ChainState::deserialize(&mut &data[..]).unwrap();
+        assert_eq!(state_expect, state_actual);
+    }
+}
@@ -2,4 +2,10 @@ pub mod chain_tally;
 
 pub mod chain_brief;
 
+pub mod chain_challenge;
+
 pub mod wrap_slot;
+
+pub mod chain_claim;
+
+pub mod chain_state;
@@ -1,5 +1,6 @@
 diesel::table! {
     use diesel::sql_types::*;
+    use crate::models::bridge_transaction_model::BridgeLifecycleMapping;
 
     #[sql_name="bridge_transaction"]
     table_bridge_transaction(column_id) {
@@ -27,6 +28,51 @@ diesel::table! {
         #[sql_name = "root_program_slot"]
         column_root_program_slot -> Int8,
 
+        #[sql_name = "sender"]
+        column_sender -> VarChar,
+
+        #[sql_name = "recipient"]
+        column_recipient -> VarChar,
+
+        #[sql_name = "amount"]
+        column_amount -> Int8,
+
+        #[sql_name = "bridge_tx_index"]
+        column_bridge_tx_index -> Int8,
+
+        #[sql_name = "status"]
+        column_status -> BridgeLifecycleMapping,
+
+        #[sql_name = "l1_signature"]
+        column_l1_signature -> VarChar,
+
+        #[sql_name = "proven_on"]
+        column_proven_on -> Nullable<Timestamp>,
+
+        #[sql_name = "claim_status"]
+        column_claim_status -> VarChar,
+
+        #[sql_name = "claim_slot"]
+        column_claim_slot -> Int8,
+
+        #[sql_name = "claim_attempts"]
+        column_claim_attempts -> Int4,
+
+        #[sql_name = "l1_commit_slot"]
+        column_l1_commit_slot -> Int8,
+
+        #[sql_name = "committed_on"]
+        column_committed_on -> Nullable<Timestamp>,
+
+        #[sql_name = "claimable_on"]
+        column_claimable_on -> Nullable<Timestamp>,
+
+        #[sql_name = "finalized"]
+        column_finalized -> Bool,
+
+        #[sql_name = "created_on"]
+        column_created_on -> Timestamp,
+
         #[sql_name = "updated_on"]
         column_updated_on -> Timestamp,
     }
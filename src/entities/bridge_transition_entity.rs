@@ -0,0 +1,22 @@
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::models::bridge_transaction_model::BridgeLifecycleMapping;
+
+    #[sql_name="bridge_transition"]
+    table_bridge_transition(column_id) {
+        #[sql_name = "id"]
+        column_id -> Int8,
+
+        #[sql_name = "signature"]
+        column_signature -> VarChar,
+
+        #[sql_name = "from_status"]
+        column_from_status -> BridgeLifecycleMapping,
+
+        #[sql_name = "to_status"]
+        column_to_status -> BridgeLifecycleMapping,
+
+        #[sql_name = "created_on"]
+        column_created_on -> Timestamp,
+    }
+}
@@ -18,6 +18,9 @@ diesel::table! {
         #[sql_name = "transaction_number"]
         column_transaction_number -> Int4,
 
+        #[sql_name = "challenged"]
+        column_challenged -> Bool,
+
         #[sql_name = "updated_on"]
         column_updated_on -> Timestamp,
     }
@@ -0,0 +1,22 @@
+use crate::entities::brief_entity::table_brief;
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    #[sql_name="brief_reclaim"]
+    table_brief_reclaim(column_id) {
+        #[sql_name = "id"]
+        column_id -> Int8,
+
+        #[sql_name = "slot"]
+        column_slot -> Int8,
+
+        #[sql_name = "lamports"]
+        column_lamports -> Int8,
+
+        #[sql_name = "updated_on"]
+        column_updated_on -> Timestamp,
+    }
+}
+
+diesel::allow_tables_to_appear_in_same_query!(table_brief, table_brief_reclaim);
@@ -0,0 +1,36 @@
+diesel::table! {
+    use diesel::sql_types::*;
+
+    #[sql_name="challenge"]
+    table_challenge(column_id) {
+        #[sql_name = "id"]
+        column_id -> Int8,
+
+        #[sql_name = "slot"]
+        column_slot -> Int8,
+
+        #[sql_name = "kind"]
+        column_kind -> VarChar,
+
+        #[sql_name = "local_root"]
+        column_local_root -> Bytea,
+
+        #[sql_name = "chain_root"]
+        column_chain_root -> Bytea,
+
+        #[sql_name = "hash_account"]
+        column_hash_account -> VarChar,
+
+        #[sql_name = "transaction_number"]
+        column_transaction_number -> Int4,
+
+        #[sql_name = "signature"]
+        column_signature -> VarChar,
+
+        #[sql_name = "status"]
+        column_status -> VarChar,
+
+        #[sql_name = "updated_on"]
+        column_updated_on -> Timestamp,
+    }
+}
@@ -0,0 +1,39 @@
+diesel::table! {
+    use diesel::sql_types::*;
+
+    #[sql_name="claim_submission"]
+    table_claim_submission(column_id) {
+        #[sql_name = "id"]
+        column_id -> Int8,
+
+        #[sql_name = "leaf_index"]
+        column_leaf_index -> Int8,
+
+        #[sql_name = "signature"]
+        column_signature -> VarChar,
+
+        #[sql_name = "status"]
+        column_status -> VarChar,
+
+        #[sql_name = "attempts"]
+        column_attempts -> Int4,
+
+        #[sql_name = "priority_fee"]
+        column_priority_fee -> Int8,
+
+        #[sql_name = "l1_signature"]
+        column_l1_signature -> VarChar,
+
+        #[sql_name = "last_error"]
+        column_last_error -> VarChar,
+
+        #[sql_name = "next_attempt_on"]
+        column_next_attempt_on -> Timestamp,
+
+        #[sql_name = "submitted_on"]
+        column_submitted_on -> Nullable<Timestamp>,
+
+        #[sql_name = "updated_on"]
+        column_updated_on -> Timestamp,
+    }
+}
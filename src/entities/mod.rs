@@ -3,3 +3,11 @@ pub mod brief_entity;
 pub mod sql_types;
 pub mod bridge_transaction_entity;
 pub mod block_entity;
+pub mod challenge_entity;
+pub mod brief_reclaim_entity;
+pub mod proof_event_entity;
+pub mod claim_submission_entity;
+pub mod bridge_transition_entity;
+pub mod root_commitment_entity;
+pub mod root_observation_entity;
+pub mod scan_checkpoint_entity;
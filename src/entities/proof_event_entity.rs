@@ -0,0 +1,24 @@
+diesel::table! {
+    use diesel::sql_types::*;
+
+    #[sql_name="proof_event"]
+    table_proof_event(column_id) {
+        #[sql_name = "id"]
+        column_id -> Int8,
+
+        #[sql_name = "signature"]
+        column_signature -> VarChar,
+
+        #[sql_name = "sender"]
+        column_sender -> VarChar,
+
+        #[sql_name = "recipient"]
+        column_recipient -> VarChar,
+
+        #[sql_name = "root_program_slot"]
+        column_root_program_slot -> Int8,
+
+        #[sql_name = "created_on"]
+        column_created_on -> Timestamp,
+    }
+}
@@ -0,0 +1,30 @@
+diesel::table! {
+    use diesel::sql_types::*;
+
+    #[sql_name="root_commitment"]
+    table_root_commitment(column_id) {
+        #[sql_name = "id"]
+        column_id -> Int8,
+
+        #[sql_name = "slot"]
+        column_slot -> Int8,
+
+        #[sql_name = "merkle_tree_root"]
+        column_merkle_tree_root -> Bytea,
+
+        #[sql_name = "l1_slot"]
+        column_l1_slot -> Int8,
+
+        #[sql_name = "committed_on"]
+        column_committed_on -> Timestamp,
+
+        #[sql_name = "claimable_on"]
+        column_claimable_on -> Timestamp,
+
+        #[sql_name = "finalized"]
+        column_finalized -> Bool,
+
+        #[sql_name = "created_on"]
+        column_created_on -> Timestamp,
+    }
+}
@@ -0,0 +1,21 @@
+diesel::table! {
+    use diesel::sql_types::*;
+
+    #[sql_name="root_observation"]
+    table_root_observation(column_id) {
+        #[sql_name = "id"]
+        column_id -> Int8,
+
+        #[sql_name = "slot"]
+        column_slot -> Int8,
+
+        #[sql_name = "merkle_tree_root"]
+        column_merkle_tree_root -> Bytea,
+
+        #[sql_name = "first_seen_on"]
+        column_first_seen_on -> Timestamp,
+
+        #[sql_name = "last_seen_on"]
+        column_last_seen_on -> Timestamp,
+    }
+}
@@ -0,0 +1,15 @@
+diesel::table! {
+    use diesel::sql_types::*;
+
+    #[sql_name="scan_checkpoint"]
+    table_scan_checkpoint(column_name) {
+        #[sql_name = "name"]
+        column_name -> VarChar,
+
+        #[sql_name = "slot"]
+        column_slot -> Int8,
+
+        #[sql_name = "updated_on"]
+        column_updated_on -> Timestamp,
+    }
+}
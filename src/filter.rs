@@ -2,6 +2,7 @@ use log::{error, info};
 use crate::common::node_configs::{ChainConfiguration, ContractConfiguration, StoreConfiguration};
 use crate::common::node_error::NodeError;
 use crate::models::bridge_transaction_model::BridgeTxRecord;
+use crate::models::scan_checkpoint_model::FILTER_CHECKPOINT;
 use crate::services::execute_service::ExecuteService;
 use crate::utils::store_util::{create_one, create_pool, PgConnectionPool};
 use crate::utils::time_util;
@@ -73,6 +74,10 @@ impl Filter {
                 let count = execute_service.insert_bridge_txs(bridge_txs).unwrap();
                 info!("insert {:?} bridge txs into pgdb", count);
             }
+            // only once the bridge txs are in postgres, the monitor reads this one
+            if let Err(e) = execute_service.update_scan_checkpoint(FILTER_CHECKPOINT, end_slot) {
+                error!("update filter checkpoint fail. slot: {}, err: {:?}", end_slot, e);
+            }
             execute_service.update_last_slot(max_slot);
         }
     }
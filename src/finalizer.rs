@@ -0,0 +1,253 @@
+use std::str::FromStr;
+
+use log::{error, info};
+use solana_sdk::signature::Signature;
+
+use crate::common::node_configs::{ChainConfiguration, StoreConfiguration};
+use crate::common::node_error::NodeError;
+use crate::models::bridge_transaction_model::BridgeLifecycle;
+use crate::models::claim_submission_model::{ClaimSubmissionRecord, ClaimSubmissionStatus};
+use crate::models::scan_checkpoint_model::FINALIZER_CURSOR;
+use crate::repositories::bridge_tx_repo::BridgeTxRepo;
+use crate::repositories::claim_submission_repo::ClaimSubmissionRepo;
+use crate::repositories::scan_checkpoint_repo::ScanCheckpointRepo;
+use crate::services::chain_claim_service;
+use crate::services::chain_service::ChainService;
+use crate::utils::store_util::{create_pool, PgConnectionPool};
+use crate::utils::time_util;
+use crate::utils::uuid_util::generate_uuid;
+
+// the most withdrawals enqueued or claims processed in one round
+const BATCH_CLAIMS: i64 = 100;
+
+// a claim transaction not seen by then has an expired blockhash and is sent again
+const CONFIRM_TIMEOUT_SECONDS: i64 = 90;
+
+/// Claim proven withdrawals on L1 with the execute keypair once the challenge window of their root elapsed.
+///
+/// Claims go through the `claim_submission` queue, one per leaf index, so a restart neither
+/// loses nor repeats a claim. A withdrawal someone else already claimed is only recorded.
+pub struct Finalizer {
+    client_pool: Option<PgConnectionPool>,
+    chain_service: Option<ChainService>,
+    store_config: Option<StoreConfiguration>,
+    chain_config: Option<ChainConfiguration>,
+    // the last `(slot, id)` enqueued, resumed from the `FINALIZER_CURSOR` checkpoint
+    cursor: Option<(i64, i64)>,
+}
+
+impl Finalizer {
+    pub fn new() -> Self {
+        Self {
+            client_pool: None,
+            chain_service: None,
+            store_config: None,
+            chain_config: None,
+            cursor: None,
+        }
+    }
+
+    pub fn load_store_config(mut self, store_config: &StoreConfiguration) -> Self {
+        self.store_config = Some(store_config.clone());
+        self
+    }
+
+    pub fn load_chain_config(mut self, chain_config: &ChainConfiguration) -> Self {
+        self.chain_config = Some(chain_config.clone());
+        self
+    }
+
+    pub fn connect_store(&mut self) -> Result<(), NodeError> {
+        let pool = create_pool(self.store_config.clone().unwrap(), 2);
+
+        self.client_pool = Some(pool);
+
+        Ok(())
+    }
+
+    pub fn connect_chain(&mut self) -> Result<(), NodeError> {
+        let chain_service = ChainService::new(&self.chain_config.clone().unwrap())?;
+
+        self.chain_service = Some(chain_service);
+
+        Ok(())
+    }
+
+    pub fn start(&mut self) -> Result<(), NodeError> {
+        self.connect_store()?;
+        self.connect_chain()?;
+
+        loop {
+            if let Err(e) = self.enqueue() {
+                error!("enqueue claims fail. {:?}", e);
+            }
+
+            match self.process() {
+                Ok(0) => time_util::sleep_seconds(5),
+                Ok(_) => time_util::sleep_seconds(1),
+                Err(e) => {
+                    error!("{:?}", e);
+                    time_util::sleep_seconds(5);
+                }
+            }
+        }
+    }
+
+    /// Queue the withdrawals whose challenge period elapsed, moving them to `ChallengeWindowElapsed`.
+    fn enqueue(&mut self) -> Result<(), NodeError> {
+        let chain_config = self.chain_config.clone().unwrap();
+        let bridge_tx_repo = BridgeTxRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+        let claim_repo = ClaimSubmissionRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+
+        let checkpoint_repo = ScanCheckpointRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+
+        let now = chrono::Utc::now().naive_utc();
+
+        // only the slot is stored, the withdrawals of that slot still `Proven` are looked at again.
+        // a rewritten root moves the checkpoint back before the withdrawals proven again
+        let checkpoint = checkpoint_repo.find(FINALIZER_CURSOR)?.map_or(0, |row| row.slot);
+        let mut cursor = match self.cursor {
+            Some(cursor) if cursor.0 <= checkpoint => cursor,
+            _ => (checkpoint, 0),
+        };
+        loop {
+            let rows = bridge_tx_repo.claimable(now, cursor, BATCH_CLAIMS)?;
+
+            let mut records = vec![];
+            for row in rows.iter() {
+                let leaf_index = bridge_tx_repo.leaf_index(row)?;
+                records.push(ClaimSubmissionRecord::queued(leaf_index, row.signature.clone(), chain_config.finalizer_priority_fee as i64, now));
+            }
+            if !records.is_empty() {
+                let queued = claim_repo.insert(records)?;
+                if !queued.is_empty() {
+                    info!("claims queued. count: {}", queued.len());
+                }
+            }
+            // only once queued, a withdrawal leaves `Proven` and is not looked at again
+            for row in rows.iter() {
+                bridge_tx_repo.transition(&row.signature, BridgeLifecycle::ChallengeWindowElapsed)?;
+            }
+
+            match rows.last() {
+                Some(last) => {
+                    cursor = (last.slot, last.id);
+                    checkpoint_repo.upsert(FINALIZER_CURSOR, last.slot)?;
+                    self.cursor = Some(cursor);
+                }
+                None => return Ok(()),
+            }
+            if (rows.len() as i64) < BATCH_CLAIMS {
+                return Ok(());
+            }
+        }
+    }
+
+    fn process(&self) -> Result<usize, NodeError> {
+        let claim_repo = ClaimSubmissionRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+
+        let now = chrono::Utc::now().naive_utc();
+        let requeued = claim_repo.requeue_failed(now)?;
+        if requeued > 0 {
+            info!("failed claims queued again. count: {}", requeued);
+        }
+
+        let claims = claim_repo.due(now, BATCH_CLAIMS)?;
+        let count = claims.len();
+        for claim in claims {
+            let claim = ClaimSubmissionRecord::from(claim);
+            let leaf_index = claim.leaf_index;
+            let result = match claim.status() {
+                ClaimSubmissionStatus::Queued => self.submit(claim),
+                ClaimSubmissionStatus::Submitted => self.track(claim),
+                _ => Ok(()),
+            };
+            if let Err(e) = result {
+                error!("process claim fail. leaf index: {}, err: {:?}", leaf_index, e);
+            }
+        }
+
+        Ok(count)
+    }
+
+    fn submit(&self, mut claim: ClaimSubmissionRecord) -> Result<(), NodeError> {
+        let chain_config = self.chain_config.clone().unwrap();
+        let chain_service = self.chain_service.as_ref().unwrap();
+        let bridge_tx_repo = BridgeTxRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+        let claim_repo = ClaimSubmissionRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+
+        let row = match bridge_tx_repo.find_by_signature(&claim.signature)? {
+            Some(row) => row,
+            None => {
+                claim.fail("withdrawal not found".to_string(), chrono::Utc::now().naive_utc());
+                claim_repo.update(claim)?;
+                return Ok(());
+            }
+        };
+
+        // claimed by the recipient or an earlier attempt of ours
+        if chain_service.is_claimed(&chain_claim_service::leaf_hash(&row)?)? {
+            return self.confirm(claim);
+        }
+
+        let now = chrono::Utc::now().naive_utc();
+        claim.attempts += 1;
+        match chain_service.submit_claim(&row, claim.leaf_index, claim.priority_fee as u64) {
+            Ok(l1_signature) => {
+                info!("claim submitted. leaf index: {}, signature: {}, l1 signature: {}, priority fee: {}",
+                    claim.leaf_index, claim.signature, l1_signature, claim.priority_fee);
+                claim.status = ClaimSubmissionStatus::Submitted.as_str().to_string();
+                claim.l1_signature = l1_signature.to_string();
+                claim.submitted_on = Some(now);
+                claim.next_attempt_on = now + chrono::Duration::seconds(CONFIRM_TIMEOUT_SECONDS);
+            }
+            Err(e) => {
+                error!("submit claim fail. leaf index: {}, attempts: {}, err: {}", claim.leaf_index, claim.attempts, e.message);
+                claim.retry(e.message, chain_config.finalizer_max_attempts as i32, chain_config.finalizer_max_priority_fee as i64, now);
+            }
+        }
+        claim_repo.update(claim)?;
+
+        Ok(())
+    }
+
+    fn track(&self, mut claim: ClaimSubmissionRecord) -> Result<(), NodeError> {
+        let chain_config = self.chain_config.clone().unwrap();
+        let claim_repo = ClaimSubmissionRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+
+        let l1_signature = Signature::from_str(&claim.l1_signature).map_err(|e| {
+            NodeError::new(generate_uuid(), format!("invalid l1 signature: {}, err: {:?}", claim.l1_signature, e))
+        })?;
+
+        let now = chrono::Utc::now().naive_utc();
+        match self.chain_service.as_ref().unwrap().get_claim_status(&l1_signature) {
+            Some(true) => self.confirm(claim),
+            Some(false) => {
+                claim.retry(format!("claim transaction failed. l1 signature: {}", l1_signature),
+                    chain_config.finalizer_max_attempts as i32, chain_config.finalizer_max_priority_fee as i64, now);
+                claim_repo.update(claim)?;
+                Ok(())
+            }
+            None if now >= claim.next_attempt_on => {
+                claim.retry(format!("claim transaction not confirmed. l1 signature: {}", l1_signature),
+                    chain_config.finalizer_max_attempts as i32, chain_config.finalizer_max_priority_fee as i64, now);
+                claim_repo.update(claim)?;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn confirm(&self, mut claim: ClaimSubmissionRecord) -> Result<(), NodeError> {
+        let bridge_tx_repo = BridgeTxRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+        let claim_repo = ClaimSubmissionRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+
+        bridge_tx_repo.mark_claimed(&claim.signature, &claim.l1_signature)?;
+
+        info!("withdrawal claimed. leaf index: {}, signature: {}, l1 signature: {}", claim.leaf_index, claim.signature, claim.l1_signature);
+        claim.status = ClaimSubmissionStatus::Confirmed.as_str().to_string();
+        claim_repo.update(claim)?;
+
+        Ok(())
+    }
+}
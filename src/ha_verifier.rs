@@ -0,0 +1,131 @@
+use std::str::FromStr;
+
+use itertools::Itertools;
+use log::info;
+use solana_sdk::hash::Hash;
+use solana_sdk::signature::Signature;
+
+use crate::common::node_configs::{ChainConfiguration, StoreConfiguration};
+use crate::common::node_error::NodeError;
+use crate::models::account_audit_row::AccountAuditRow;
+use crate::repositories::account_audit_repo::AccountAuditRepo;
+use crate::services::chain_service::ChainService;
+use crate::utils::account_util::compute_slot_ha_steps;
+use crate::utils::store_util::{create_pool, PgConnectionPool};
+use crate::utils::uuid_util::generate_uuid;
+
+/// The first slot whose recomputed hash account differs from its on-chain brief.
+///
+/// Briefs only commit the hash account at the end of a slot, so the diverging transaction can't be
+/// told apart from the others of the slot. `first_slot_transaction` is the first transaction of the
+/// slot, where the inspection starts, and `steps` holds each transaction of the slot with the hash
+/// account after it.
+#[derive(Debug, Clone)]
+pub struct HaDivergence {
+    pub slot: u64,
+    pub first_slot_transaction: Option<Signature>,
+    pub local_hash_account: Hash,
+    pub chain_hash_account: String,
+    pub local_transaction_number: u32,
+    pub chain_transaction_number: u32,
+    pub steps: Vec<(Signature, Hash)>,
+}
+
+/// Recompute the hash account chain from `account_audit` and compare it with the on-chain briefs.
+pub struct HaVerifier {
+    client_pool: Option<PgConnectionPool>,
+    chain_service: Option<ChainService>,
+    store_config: Option<StoreConfiguration>,
+    chain_config: Option<ChainConfiguration>,
+}
+
+impl HaVerifier {
+    pub fn new() -> Self {
+        Self {
+            client_pool: None,
+            chain_service: None,
+            store_config: None,
+            chain_config: None,
+        }
+    }
+
+    pub fn load_store_config(mut self, store_config: &StoreConfiguration) -> Self {
+        self.store_config = Some(store_config.clone());
+        self
+    }
+
+    pub fn load_chain_config(mut self, chain_config: &ChainConfiguration) -> Self {
+        self.chain_config = Some(chain_config.clone());
+        self
+    }
+
+    pub fn connect_store(&mut self) -> Result<(), NodeError> {
+        let pool = create_pool(self.store_config.clone().unwrap(), 2);
+
+        self.client_pool = Some(pool);
+
+        Ok(())
+    }
+
+    pub fn connect_chain(&mut self) -> Result<(), NodeError> {
+        let chain_service = ChainService::new(&self.chain_config.clone().unwrap())?;
+
+        self.chain_service = Some(chain_service);
+
+        Ok(())
+    }
+
+    /// Verify the slots in `[from_slot, to_slot]` that have account changes, stop at the first divergence.
+    pub fn verify(&mut self, from_slot: i64, to_slot: i64) -> Result<Option<HaDivergence>, NodeError> {
+        if self.client_pool.is_none() {
+            self.connect_store()?;
+        }
+        if self.chain_service.is_none() {
+            self.connect_chain()?;
+        }
+
+        let repo = AccountAuditRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+        let mut old_ha = match repo.last_slot_before(from_slot)? {
+            Some(slot) => self.chain_hash_account(slot as u64)?,
+            None => Hash::default(),
+        };
+
+        let rows = repo.range(from_slot, to_slot)?;
+        for (slot, slot_rows) in &rows.into_iter().group_by(|row| row.slot) {
+            let slot_rows: Vec<AccountAuditRow> = slot_rows.collect();
+            let steps = compute_slot_ha_steps(&slot_rows, &old_ha)?;
+            let local_ha = steps.last().map(|(_, ha)| *ha).unwrap_or(old_ha);
+
+            let brief = self.chain_service.as_ref().unwrap().fetch_brief(slot as u64).ok_or_else(|| {
+                NodeError::new(generate_uuid(), format!("brief is not on chain. slot: {}", slot))
+            })?;
+
+            if brief.hash_account != local_ha.to_string() || brief.transaction_number != steps.len() as u32 {
+                return Ok(Some(HaDivergence {
+                    slot: slot as u64,
+                    first_slot_transaction: steps.first().map(|(tx_id, _)| *tx_id),
+                    local_hash_account: local_ha,
+                    chain_hash_account: brief.hash_account,
+                    local_transaction_number: steps.len() as u32,
+                    chain_transaction_number: brief.transaction_number,
+                    steps,
+                }));
+            }
+            info!("hash account verified. slot: {}, hash account: {}", slot, local_ha);
+
+            old_ha = local_ha;
+        }
+
+        Ok(None)
+    }
+
+    fn chain_hash_account(&self, slot: u64) -> Result<Hash, NodeError> {
+        let brief = self.chain_service.as_ref().unwrap().fetch_brief(slot).ok_or_else(|| {
+            NodeError::new(generate_uuid(), format!("brief is not on chain. slot: {}", slot))
+        })?;
+
+        Hash::from_str(&brief.hash_account).map_err(|e| {
+            NodeError::new(generate_uuid(), format!("invalid hash account. slot: {}, err: {:?}", slot, e))
+        })
+    }
+}
@@ -1,5 +1,13 @@
 pub mod filter;
 pub mod monitor;
+pub mod challenger;
+pub mod state_verifier;
+pub mod brief_producer;
+pub mod brief_collector;
+pub mod ha_verifier;
+pub mod finalizer;
+pub mod claim_reconciler;
+pub mod api;
 pub mod common;
 pub mod utils;
 pub mod entities;
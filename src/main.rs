@@ -1,13 +1,24 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
 use std::thread;
 
 use log::{error, info};
 use relayer::common::node_configs::NodeConfiguration;
 use relayer::monitor::Monitor;
+use relayer::challenger::Challenger;
+use relayer::state_verifier::StateVerifier;
+use relayer::smt::state_tree::StateTree;
+use relayer::brief_producer::BriefProducer;
+use relayer::brief_collector::BriefCollector;
 use relayer::filter::Filter;
+use relayer::ha_verifier::HaVerifier;
+use relayer::finalizer::Finalizer;
+use relayer::claim_reconciler::ClaimReconciler;
+use relayer::api::server::ApiServer;
 use relayer::utils;
 use relayer::utils::log_util::{init_logger, LogOutput};
 
-use clap::{App, Arg};
+use clap::{App, Arg, SubCommand};
 use relayer::utils::time_util::sleep_seconds;
 
 
@@ -25,11 +36,29 @@ fn main() {
         .takes_value(false) // 表示不接收值
         .help("Log mode: stream the validator log");
 
+    let verify_ha_command = SubCommand::with_name("verify-ha")
+        .about("Recompute the hash account chain from account_audit and compare it with the briefs on chain")
+        .arg(Arg::with_name("from_slot")
+            .long("from-slot")
+            .value_name("SLOT")
+            .takes_value(true)
+            .required(true)
+            .validator(is_slot)
+            .help("First L2 slot to verify"))
+        .arg(Arg::with_name("to_slot")
+            .long("to-slot")
+            .value_name("SLOT")
+            .takes_value(true)
+            .required(true)
+            .validator(is_slot)
+            .help("Last L2 slot to verify"));
+
     let matches = App::new("fraud-proof")
         .about("Fraud Proof")
         .version("0.1.0")
         .arg(config_file_path_arg)
         .arg(log_arg)
+        .subcommand(verify_ha_command)
         .get_matches();
 
     let output = if matches.is_present("log") {
@@ -51,6 +80,33 @@ fn main() {
         Err(err) => {
             error!("Load config error {:#?}", &err);
         }
+        Ok(cfg) if matches.subcommand_matches("verify-ha").is_some() => {
+            let verify_matches = matches.subcommand_matches("verify-ha").unwrap();
+            let from_slot = clap::value_t_or_exit!(verify_matches, "from_slot", i64);
+            let to_slot = clap::value_t_or_exit!(verify_matches, "to_slot", i64);
+
+            let mut ha_verifier = HaVerifier::new()
+                .load_chain_config(&cfg.chain)
+                .load_store_config(&cfg.store);
+
+            match ha_verifier.verify(from_slot, to_slot) {
+                Ok(None) => println!("hash account chain verified. slots: [{}, {}]", from_slot, to_slot),
+                Ok(Some(divergence)) => {
+                    println!("hash account chain diverged. slot: {}, first transaction of the slot: {:?}",
+                        divergence.slot, divergence.first_slot_transaction);
+                    println!("local hash account: {}, transactions: {}",
+                        divergence.local_hash_account, divergence.local_transaction_number);
+                    println!("chain hash account: {}, transactions: {}",
+                        divergence.chain_hash_account, divergence.chain_transaction_number);
+                    divergence.steps.iter().for_each(|(tx_id, ha)| println!("  {} -> {}", tx_id, ha));
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    error!("verify hash account fail. {:?}", e);
+                    std::process::exit(2);
+                }
+            }
+        }
         Ok(cfg) => {
             let store = cfg.store.clone();
             let chain = cfg.chain.clone();
@@ -68,6 +124,134 @@ fn main() {
                 let _ = monitor.start();
             });
 
+            if chain.enable_challenger {
+                let challenger_store = store.clone();
+                let challenger_chain = chain.clone();
+
+                let _ = thread::spawn(move || {
+                    let mut challenger = Challenger::new()
+                        .load_chain_config(&challenger_chain)
+                        .load_store_config(&challenger_store);
+
+                    if let Err(e) = challenger.start() {
+                        error!("challenger exit. {:?}", e);
+                    }
+                });
+            }
+
+            // the state db can be opened once, the brief producer, the state verifier and the api share it
+            let state_tree = match chain.enable_state_verifier || chain.enable_brief_producer {
+                true => match StateTree::open(Path::new("./relayer/verifier/smt")) {
+                    Ok(state_tree) => Some(Arc::new(Mutex::new(state_tree))),
+                    Err(e) => {
+                        error!("open state tree fail. {:?}", e);
+                        None
+                    }
+                },
+                false => None,
+            };
+
+            let mut account_prover = None;
+            if chain.enable_state_verifier {
+                let mut state_verifier = StateVerifier::new()
+                    .load_chain_config(&chain)
+                    .load_store_config(&store);
+                if let Some(state_tree) = &state_tree {
+                    state_verifier = state_verifier.load_state_tree(state_tree);
+                    account_prover = state_verifier.prover();
+                }
+
+                let _ = thread::spawn(move || {
+                    if let Err(e) = state_verifier.start() {
+                        error!("state verifier exit. {:?}", e);
+                    }
+                });
+            }
+
+            if chain.enable_brief_producer {
+                let producer_store = store.clone();
+                let producer_chain = chain.clone();
+                let producer_state_tree = state_tree.clone();
+
+                let _ = thread::spawn(move || {
+                    let mut brief_producer = BriefProducer::new()
+                        .load_chain_config(&producer_chain)
+                        .load_store_config(&producer_store);
+                    if let Some(state_tree) = &producer_state_tree {
+                        brief_producer = brief_producer.load_state_tree(state_tree);
+                    }
+
+                    if let Err(e) = brief_producer.start() {
+                        error!("brief producer exit. {:?}", e);
+                    }
+                });
+            }
+
+            if chain.enable_brief_collector {
+                let collector_store = store.clone();
+                let collector_chain = chain.clone();
+
+                let _ = thread::spawn(move || {
+                    let mut brief_collector = BriefCollector::new()
+                        .load_chain_config(&collector_chain)
+                        .load_store_config(&collector_store);
+
+                    if let Err(e) = brief_collector.start() {
+                        error!("brief collector exit. {:?}", e);
+                    }
+                });
+            }
+
+            if chain.enable_finalizer {
+                let finalizer_store = store.clone();
+                let finalizer_chain = chain.clone();
+
+                let _ = thread::spawn(move || {
+                    let mut finalizer = Finalizer::new()
+                        .load_chain_config(&finalizer_chain)
+                        .load_store_config(&finalizer_store);
+
+                    if let Err(e) = finalizer.start() {
+                        error!("finalizer exit. {:?}", e);
+                    }
+                });
+            }
+
+            if chain.enable_claim_reconciler {
+                let reconciler_store = store.clone();
+                let reconciler_chain = chain.clone();
+
+                let _ = thread::spawn(move || {
+                    let mut claim_reconciler = ClaimReconciler::new()
+                        .load_chain_config(&reconciler_chain)
+                        .load_store_config(&reconciler_store);
+
+                    if let Err(e) = claim_reconciler.start() {
+                        error!("claim reconciler exit. {:?}", e);
+                    }
+                });
+            }
+
+            if cfg.api.enable {
+                let api_store = store.clone();
+                let api_chain = chain.clone();
+                let api_config = cfg.api.clone();
+
+                let _ = thread::spawn(move || {
+                    let mut api_server = ApiServer::new()
+                        .load_api_config(&api_config)
+                        .load_chain_config(&api_chain)
+                        .load_store_config(&api_store);
+                    if let Some(account_prover) = &account_prover {
+                        api_server = api_server.load_account_prover(account_prover);
+                    }
+
+                    if let Err(e) = api_server.start() {
+                        error!("api server exit. {:?}", e);
+                    }
+                });
+            }
+
             let mut filter = Filter::new()
                 .store(&store)
                 .contract(&contract);
@@ -77,5 +261,8 @@ fn main() {
     }
 }
 
-
-
+fn is_slot(value: String) -> Result<(), String> {
+    value.parse::<i64>()
+        .map(|_| ())
+        .map_err(|_| format!("slot must be a number, got: {}", value))
+}
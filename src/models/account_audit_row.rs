@@ -1,7 +1,9 @@
 use diesel::Queryable;
 use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
+use crate::common::node_error::NodeError;
 use crate::smt::account_smt::SMTAccount;
+use crate::utils::uuid_util::generate_uuid;
 
 #[derive(Debug, Clone, Queryable, Serialize, Deserialize)]
 #[diesel(table_name = table_account_audit)]
@@ -53,14 +55,54 @@ pub struct AccountAuditRow {
 
 
 impl AccountAuditRow {
-    pub fn to_smt_account(&self) -> SMTAccount {
-        SMTAccount {
-            pubkey: Pubkey::try_from(self.pubkey.as_slice()).unwrap(),
+    /// The account written by the row, a row missing its owner or data is rejected.
+    pub fn to_smt_account(&self) -> Result<SMTAccount, NodeError> {
+        let invalid = |what: &str| NodeError::new(generate_uuid(),
+            format!("invalid account_audit row, {}. id: {}, slot: {}", what, self.id, self.slot));
+
+        Ok(SMTAccount {
+            pubkey: Pubkey::try_from(self.pubkey.as_slice()).map_err(|_| invalid("bad pubkey"))?,
             lamports: self.lamports,
-            owner: Pubkey::try_from(self.owner.clone().unwrap().as_slice()).unwrap(),
+            owner: Pubkey::try_from(self.owner.as_deref().ok_or_else(|| invalid("no owner"))?)
+                .map_err(|_| invalid("bad owner"))?,
             executable: self.executable,
             rent_epoch: self.rent_epoch,
-            data: self.data.clone().unwrap(),
-        }
+            data: self.data.clone().ok_or_else(|| invalid("no data"))?,
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use solana_sdk::pubkey::Pubkey;
+
+    use crate::models::account_audit_row::AccountAuditRow;
+
+    #[test]
+    fn test_to_smt_account() {
+        let mut row = AccountAuditRow {
+            id: 1,
+            pubkey: Pubkey::new_unique().to_bytes().to_vec(),
+            owner: Some(Pubkey::default().to_bytes().to_vec()),
+            lamports: 10,
+            slot: 5,
+            executable: false,
+            rent_epoch: 0,
+            data: Some(vec![1, 2, 3]),
+            write_version: 1,
+            txn_signature: None,
+            updated_on: chrono::NaiveDateTime::default(),
+        };
+        let account = row.to_smt_account().unwrap();
+        assert_eq!(account.lamports, 10);
+        assert_eq!(account.data, vec![1, 2, 3]);
+
+        row.data = None;
+        assert!(row.to_smt_account().is_err());
+        row.data = Some(vec![]);
+        row.owner = None;
+        assert!(row.to_smt_account().is_err());
+        row.owner = Some(vec![1, 2]);
+        assert!(row.to_smt_account().is_err());
     }
 }
@@ -1,11 +1,21 @@
 use crate::entities::bridge_transaction_entity::table_bridge_transaction;
+use crate::models::root_commitment_model::RootCommitmentRow;
 use crate::models::transaction_model::TransactionRow;
 use dd_merkle_tree::HashingAlgorithm;
+use diesel::pg::Pg;
+use diesel::sql_types::Bool;
 use diesel::Selectable;
-use diesel::{Insertable, Queryable, AsChangeset};
+use diesel::{BoxableExpression, ExpressionMethods, Insertable, Queryable, AsChangeset};
+use diesel_derive_enum::DbEnum;
 use lombok::{Getter, Setter};
 use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::common::node_error::NodeError;
+use crate::utils::uuid_util::generate_uuid;
 
 #[derive(Debug, Clone, Deserialize, Serialize, Setter, Getter)]
 #[serde(rename_all(serialize = "snake_case", deserialize = "snake_case"))]
@@ -23,6 +33,161 @@ pub struct BridgeTxData {
     pub proof: Option<String>,
 }
 
+/// The lifecycle of a bridge message, stored as the postgres enum `bridge_lifecycle`.
+///
+/// Detected -> Committed -> Proven -> ChallengeWindowElapsed -> Claimed is the way of a withdrawal,
+/// a message can be Rejected by a fraud proof or Orphaned by a rewritten L1 root on the way.
+/// The status only changes through `transition`, see `BridgeTxRepo::transition`, which also keeps
+/// `is_generated_proof` in line with it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Hash, DbEnum)]
+#[serde(rename_all = "snake_case")]
+#[DbValueStyle = "snake_case"]
+pub enum BridgeLifecycle {
+    // found on L2 by the filter
+    Detected,
+    // covered by an L1 commitment
+    Committed,
+    // merkle proof generated against the committed root
+    Proven,
+    // the challenge period of the root passed, the withdrawal can be claimed
+    ChallengeWindowElapsed,
+    // withdrawn on L1
+    Claimed,
+    // the root covering the message was overturned by a fraud proof
+    Rejected,
+    // the L1 root covering the message was rewritten, the message waits for a new commitment
+    Orphaned,
+}
+
+impl FromStr for BridgeLifecycle {
+    type Err = String;
+
+    fn from_str(status: &str) -> Result<Self, Self::Err> {
+        BridgeLifecycle::ALL.into_iter()
+            .find(|s| s.as_str() == status)
+            .ok_or_else(|| format!("unknown status: {}", status))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Error, Eq, PartialEq)]
+#[error("illegal bridge message transition {from:?} -> {to:?}")]
+pub struct IllegalTransition {
+    pub from: BridgeLifecycle,
+    pub to: BridgeLifecycle,
+}
+
+impl From<IllegalTransition> for NodeError {
+    fn from(error: IllegalTransition) -> Self {
+        NodeError::new(generate_uuid(), error.to_string())
+    }
+}
+
+impl BridgeLifecycle {
+    pub const ALL: [BridgeLifecycle; 7] = [
+        BridgeLifecycle::Detected,
+        BridgeLifecycle::Committed,
+        BridgeLifecycle::Proven,
+        BridgeLifecycle::ChallengeWindowElapsed,
+        BridgeLifecycle::Claimed,
+        BridgeLifecycle::Rejected,
+        BridgeLifecycle::Orphaned,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BridgeLifecycle::Detected => "detected",
+            BridgeLifecycle::Committed => "committed",
+            BridgeLifecycle::Proven => "proven",
+            BridgeLifecycle::ChallengeWindowElapsed => "challenge_window_elapsed",
+            BridgeLifecycle::Claimed => "claimed",
+            BridgeLifecycle::Rejected => "rejected",
+            BridgeLifecycle::Orphaned => "orphaned",
+        }
+    }
+
+    /// Whether a message in this status holds a merkle proof, the source of `is_generated_proof`.
+    pub fn has_proof(&self) -> bool {
+        matches!(self, BridgeLifecycle::Proven | BridgeLifecycle::ChallengeWindowElapsed | BridgeLifecycle::Claimed)
+    }
+
+    pub fn can_transition(&self, to: BridgeLifecycle) -> bool {
+        use BridgeLifecycle::*;
+
+        matches!((self, to),
+            (Detected, Committed)
+            | (Committed, Proven)
+            | (Proven, ChallengeWindowElapsed)
+            | (ChallengeWindowElapsed, Claimed)
+            | (Detected | Committed | Proven | ChallengeWindowElapsed, Rejected)
+            | (Committed | Proven | ChallengeWindowElapsed, Orphaned)
+            | (Orphaned, Detected))
+    }
+
+    pub fn transition(self, to: BridgeLifecycle) -> Result<BridgeLifecycle, IllegalTransition> {
+        if self.can_transition(to) {
+            Ok(to)
+        } else {
+            Err(IllegalTransition { from: self, to })
+        }
+    }
+
+    pub fn commit(self) -> Result<BridgeLifecycle, IllegalTransition> {
+        self.transition(BridgeLifecycle::Committed)
+    }
+
+    pub fn prove(self) -> Result<BridgeLifecycle, IllegalTransition> {
+        self.transition(BridgeLifecycle::Proven)
+    }
+
+    pub fn elapse_challenge_window(self) -> Result<BridgeLifecycle, IllegalTransition> {
+        self.transition(BridgeLifecycle::ChallengeWindowElapsed)
+    }
+
+    pub fn claim(self) -> Result<BridgeLifecycle, IllegalTransition> {
+        self.transition(BridgeLifecycle::Claimed)
+    }
+
+    pub fn reject(self) -> Result<BridgeLifecycle, IllegalTransition> {
+        self.transition(BridgeLifecycle::Rejected)
+    }
+
+    pub fn orphan(self) -> Result<BridgeLifecycle, IllegalTransition> {
+        self.transition(BridgeLifecycle::Orphaned)
+    }
+}
+
+/// Whether a withdrawal was claimed on L1, as observed on its claimed marker account.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+pub enum ClaimStatus {
+    Unclaimed,
+    Claimed,
+    // claimed, and further claim transactions hit the marker account
+    DoubleClaimed,
+}
+
+impl ClaimStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClaimStatus::Unclaimed => "unclaimed",
+            ClaimStatus::Claimed => "claimed",
+            ClaimStatus::DoubleClaimed => "double_claimed",
+        }
+    }
+}
+
+impl FromStr for ClaimStatus {
+    type Err = String;
+
+    fn from_str(status: &str) -> Result<Self, Self::Err> {
+        match status {
+            "unclaimed" => Ok(ClaimStatus::Unclaimed),
+            "claimed" => Ok(ClaimStatus::Claimed),
+            "double_claimed" => Ok(ClaimStatus::DoubleClaimed),
+            _ => Err(format!("unknown claim status: {}", status)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
 #[diesel(table_name = table_bridge_transaction)]
 pub struct BridgeTxRow {
@@ -58,6 +223,72 @@ pub struct BridgeTxRow {
     #[diesel(column_name = column_root_program_slot)]
     pub root_program_slot: i64,
 
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_sender)]
+    pub sender: String,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_recipient)]
+    pub recipient: String,
+
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_amount)]
+    pub amount: i64,
+
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_bridge_tx_index)]
+    pub bridge_tx_index: i64,
+
+    #[diesel(sql_type = BridgeLifecycleMapping)]
+    #[diesel(column_name = column_status)]
+    pub status: BridgeLifecycle,
+
+    // the L1 claim transaction, empty until the withdrawal is claimed
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_l1_signature)]
+    pub l1_signature: String,
+
+    #[diesel(sql_type = Nullable<Timestamp>)]
+    #[diesel(column_name = column_proven_on)]
+    pub proven_on: Option<chrono::NaiveDateTime>,
+
+    // kept up to date by the claim reconciler from the claimed marker account on L1
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_claim_status)]
+    pub claim_status: String,
+
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_claim_slot)]
+    pub claim_slot: i64,
+
+    // transactions seen against the claimed marker account, more than one flags a double claim
+    #[diesel(sql_type = Int4)]
+    #[diesel(column_name = column_claim_attempts)]
+    pub claim_attempts: i32,
+
+    // the L1 slot and time the root covering the message was committed at
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_l1_commit_slot)]
+    pub l1_commit_slot: i64,
+
+    #[diesel(sql_type = Nullable<Timestamp>)]
+    #[diesel(column_name = column_committed_on)]
+    pub committed_on: Option<chrono::NaiveDateTime>,
+
+    // the challenge window of the root ends here
+    #[diesel(sql_type = Nullable<Timestamp>)]
+    #[diesel(column_name = column_claimable_on)]
+    pub claimable_on: Option<chrono::NaiveDateTime>,
+
+    // the root of the proof is finalized on L1, the proof is provisional until then
+    #[diesel(sql_type = Bool)]
+    #[diesel(column_name = column_finalized)]
+    pub finalized: bool,
+
+    #[diesel(sql_type = Timestamp)]
+    #[diesel(column_name = column_created_on)]
+    pub created_on: chrono::NaiveDateTime,
+
     #[diesel(sql_type = Timestamp)]
     #[diesel(column_name = column_updated_on)]
     pub updated_on: chrono::NaiveDateTime,
@@ -93,6 +324,45 @@ pub struct BridgeTxRecord {
     #[diesel(sql_type = Bytea)]
     #[diesel(column_name = column_current_mt_root)]
     pub current_mt_root: Vec<u8>,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_sender)]
+    pub sender: String,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_recipient)]
+    pub recipient: String,
+
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_amount)]
+    pub amount: i64,
+
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_bridge_tx_index)]
+    pub bridge_tx_index: i64,
+
+    // the L1 claim transaction, empty until the withdrawal is claimed
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_l1_signature)]
+    pub l1_signature: String,
+
+    #[diesel(sql_type = Nullable<Timestamp>)]
+    #[diesel(column_name = column_proven_on)]
+    pub proven_on: Option<chrono::NaiveDateTime>,
+
+    // the L1 slot and time the root covering the message was committed at
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_l1_commit_slot)]
+    pub l1_commit_slot: i64,
+
+    #[diesel(sql_type = Nullable<Timestamp>)]
+    #[diesel(column_name = column_committed_on)]
+    pub committed_on: Option<chrono::NaiveDateTime>,
+
+    // the challenge window of the root ends here
+    #[diesel(sql_type = Nullable<Timestamp>)]
+    #[diesel(column_name = column_claimable_on)]
+    pub claimable_on: Option<chrono::NaiveDateTime>,
 }
 
 // impl From<&TransactionRow> for BridgeTxRecord {
@@ -117,12 +387,215 @@ impl From<BridgeTxRow> for BridgeTxRecord {
             proof: btr.proof,
             is_generated_proof: btr.is_generated_proof,
             root_program_slot: 0 as i64,
-            current_mt_root: btr.current_mt_root
+            current_mt_root: btr.current_mt_root,
+            sender: btr.sender,
+            recipient: btr.recipient,
+            amount: btr.amount,
+            bridge_tx_index: btr.bridge_tx_index,
+            l1_signature: btr.l1_signature,
+            proven_on: btr.proven_on,
+            l1_commit_slot: btr.l1_commit_slot,
+            committed_on: btr.committed_on,
+            claimable_on: btr.claimable_on,
         }
     }
     
 }
 
+/// The L2 slots whose bridge messages one root commitment adds to the tree, `(after_slot, to_slot]`.
+///
+/// The root manager commits the messages after the slot of the previous root through the slot of
+/// its own, so the windows of consecutive commitments never share a message. The first window is
+/// after slot 0, which carries none.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommitmentWindow {
+    pub after_slot: i64,
+    pub to_slot: i64,
+}
+
+impl CommitmentWindow {
+    pub fn new(after_slot: i64, to_slot: i64) -> Self {
+        CommitmentWindow { after_slot, to_slot }
+    }
+
+    /// Every message up to and including `to_slot`, as loaded into the tree on a restart.
+    pub fn through(to_slot: i64) -> Self {
+        CommitmentWindow::new(0, to_slot)
+    }
+
+    /// Every message a bridge tree can hold, the leaves of the latest tree and the ones after it.
+    pub fn all() -> Self {
+        CommitmentWindow::through(i64::MAX)
+    }
+
+    /// The window of the next commitment, at `to_slot`.
+    pub fn next(&self, to_slot: i64) -> Self {
+        CommitmentWindow::new(self.to_slot, to_slot)
+    }
+
+    /// The slots of the window, `after_slot` excluded.
+    pub fn slots(&self) -> RangeInclusive<i64> {
+        self.after_slot + 1..=self.to_slot
+    }
+
+    pub fn contains(&self, slot: i64) -> bool {
+        self.slots().contains(&slot)
+    }
+
+    /// The window as a filter on the slot of bridge messages, with the same bounds as `contains`.
+    pub fn filter(&self) -> Box<dyn BoxableExpression<table_bridge_transaction::table, Pg, SqlType = Bool>> {
+        let slots = self.slots();
+        Box::new(table_bridge_transaction::column_slot.between(*slots.start(), *slots.end()))
+    }
+}
+
+/// A withdrawal and its merkle proof as served by the proof api, hashes are hex encoded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WithdrawalProof {
+    pub signature: String,
+    pub slot: i64,
+    pub leaf_index: i64,
+    pub leaf_hash: String,
+    pub status: BridgeLifecycle,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root_program_slot: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub committed_on: Option<chrono::NaiveDateTime>,
+    // when the challenge window of the root ends, for countdowns
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claimable_on: Option<chrono::NaiveDateTime>,
+    // false while the root of the proof may still be rolled back on L1
+    pub finalized: bool,
+}
+
+impl WithdrawalProof {
+    pub fn new(row: &BridgeTxRow, leaf_index: i64) -> Self {
+        let proven = row.is_generated_proof;
+        WithdrawalProof {
+            signature: row.signature.clone(),
+            slot: row.slot,
+            leaf_index,
+            leaf_hash: hex::encode(&row.tx_info_hash),
+            status: row.status,
+            proof: proven.then(|| split_proof(&row.proof)),
+            root: proven.then(|| hex::encode(&row.current_mt_root)),
+            root_program_slot: proven.then_some(row.root_program_slot),
+            committed_on: row.committed_on,
+            claimable_on: row.claimable_on,
+            finalized: row.finalized,
+        }
+    }
+
+    /// The withdrawal proven against the root of `commitment` instead of the root it was proven against.
+    pub fn against(row: &BridgeTxRow, leaf_index: i64, commitment: &RootCommitmentRow, proof: &str) -> Self {
+        WithdrawalProof {
+            proof: Some(split_proof(proof)),
+            root: Some(hex::encode(&commitment.merkle_tree_root)),
+            root_program_slot: Some(commitment.slot),
+            committed_on: Some(commitment.committed_on),
+            claimable_on: Some(commitment.claimable_on),
+            finalized: commitment.finalized,
+            ..WithdrawalProof::new(row, leaf_index)
+        }
+    }
+}
+
+// proofs are stored as the hex of the pairing hashes concatenated, 32 bytes each
+fn split_proof(proof: &str) -> Vec<String> {
+    proof.as_bytes().chunks(64)
+        .map(|h| String::from_utf8_lossy(h).to_string())
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+pub enum AddressRole {
+    Sender,
+    Recipient,
+    Any,
+}
+
+/// A page of the withdrawals of one address, newest first.
+#[derive(Debug, Clone)]
+pub struct WithdrawalQuery {
+    pub address: String,
+    pub role: AddressRole,
+    // empty matches every status
+    pub statuses: Vec<BridgeLifecycle>,
+    pub from_slot: Option<i64>,
+    pub to_slot: Option<i64>,
+    pub from_time: Option<chrono::NaiveDateTime>,
+    pub to_time: Option<chrono::NaiveDateTime>,
+    // `(slot, id)` of the last row of the previous page
+    pub cursor: Option<(i64, i64)>,
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WithdrawalSummary {
+    pub signature: String,
+    pub slot: i64,
+    pub sender: String,
+    pub recipient: String,
+    pub amount: i64,
+    pub status: BridgeLifecycle,
+    pub leaf_hash: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root_program_slot: Option<i64>,
+    pub claim_status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claim_signature: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claim_slot: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claimable_on: Option<chrono::NaiveDateTime>,
+    pub finalized: bool,
+    pub created_on: chrono::NaiveDateTime,
+}
+
+impl From<&BridgeTxRow> for WithdrawalSummary {
+    fn from(row: &BridgeTxRow) -> Self {
+        WithdrawalSummary {
+            signature: row.signature.clone(),
+            slot: row.slot,
+            sender: row.sender.clone(),
+            recipient: row.recipient.clone(),
+            amount: row.amount,
+            status: row.status,
+            leaf_hash: hex::encode(&row.tx_info_hash),
+            root_program_slot: (row.root_program_slot > 0).then_some(row.root_program_slot),
+            claim_status: row.claim_status.clone(),
+            claim_signature: (!row.l1_signature.is_empty()).then(|| row.l1_signature.clone()),
+            claim_slot: (row.claim_slot > 0).then_some(row.claim_slot),
+            claimable_on: row.claimable_on,
+            finalized: row.finalized,
+            created_on: row.created_on,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WithdrawalPage {
+    pub items: Vec<WithdrawalSummary>,
+    // pass as `cursor` to fetch the next page, absent on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// Proven withdrawals in one claim status, oldest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimBacklog {
+    pub claim_status: String,
+    // every withdrawal in the status, not just this page
+    pub total: i64,
+    pub items: Vec<WithdrawalSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
 #[derive(Debug)]
 pub struct BridgeTxInfo {
     pub from: Pubkey,
@@ -181,4 +654,165 @@ impl MessageType {
     pub fn from_bytes(bytes: &[u8]) -> Self {
         bincode::deserialize(bytes).unwrap()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+pub mod test {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use std::str::FromStr;
+
+    use diesel::pg::Pg;
+    use diesel::prelude::*;
+
+    use crate::entities::bridge_transaction_entity::table_bridge_transaction;
+    use crate::models::bridge_transaction_model::{BridgeLifecycle, BridgeTxRow, CommitmentWindow, WithdrawalProof};
+
+    fn prepare_row(is_generated_proof: bool) -> BridgeTxRow {
+        BridgeTxRow {
+            id: 1,
+            slot: 10,
+            signature: "sig".to_string(),
+            tx_info_hash: vec![1u8; 32],
+            proof: hex::encode([[2u8; 32], [3u8; 32]].concat()),
+            is_generated_proof,
+            current_mt_root: vec![4u8; 32],
+            root_program_slot: 12,
+            sender: "sender".to_string(),
+            recipient: "sender".to_string(),
+            amount: 5,
+            bridge_tx_index: 0,
+            status: if is_generated_proof { BridgeLifecycle::Proven } else { BridgeLifecycle::Detected },
+            l1_signature: String::new(),
+            proven_on: None,
+            claim_status: "unclaimed".to_string(),
+            claim_slot: 0,
+            claim_attempts: 0,
+            l1_commit_slot: 0,
+            committed_on: None,
+            claimable_on: None,
+            finalized: false,
+            created_on: chrono::NaiveDateTime::default(),
+            updated_on: chrono::NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn test_withdrawal_proof() {
+        let pending = WithdrawalProof::new(&prepare_row(false), 3);
+        assert!(pending.proof.is_none() && pending.root.is_none() && pending.root_program_slot.is_none());
+
+        let proven = WithdrawalProof::new(&prepare_row(true), 3);
+        assert_eq!(proven.proof.unwrap(), vec![hex::encode([2u8; 32]), hex::encode([3u8; 32])]);
+        assert_eq!(proven.root.unwrap(), hex::encode([4u8; 32]));
+        assert_eq!(proven.root_program_slot, Some(12));
+    }
+
+    #[test]
+    fn test_withdrawal_proof_status() {
+        assert_eq!(WithdrawalProof::new(&prepare_row(false), 3).status, BridgeLifecycle::Detected);
+        assert_eq!(WithdrawalProof::new(&prepare_row(true), 3).status, BridgeLifecycle::Proven);
+        assert!(BridgeLifecycle::from_str("pending").is_err());
+    }
+
+    #[test]
+    fn test_lifecycle_transition() {
+        let status = BridgeLifecycle::Detected.commit().unwrap().prove().unwrap();
+        assert_eq!(status, BridgeLifecycle::Proven);
+        assert_eq!(status.elapse_challenge_window().unwrap().claim().unwrap(), BridgeLifecycle::Claimed);
+
+        // no skipping the challenge window, and claimed is final
+        assert!(BridgeLifecycle::Proven.claim().is_err());
+        assert!(BridgeLifecycle::Detected.prove().is_err());
+        assert!(BridgeLifecycle::Claimed.orphan().is_err());
+        assert!(BridgeLifecycle::Claimed.transition(BridgeLifecycle::Detected).is_err());
+
+        // a fraud proof rejects a message until it is claimed, and rejected is final
+        for status in [BridgeLifecycle::Detected, BridgeLifecycle::Committed, BridgeLifecycle::Proven, BridgeLifecycle::ChallengeWindowElapsed] {
+            assert_eq!(status.reject(), Ok(BridgeLifecycle::Rejected));
+        }
+        assert!(BridgeLifecycle::Claimed.reject().is_err());
+        assert!(BridgeLifecycle::Orphaned.reject().is_err());
+        assert!(BridgeLifecycle::Rejected.transition(BridgeLifecycle::Detected).is_err());
+        assert!(BridgeLifecycle::Rejected.orphan().is_err());
+        assert!(!BridgeLifecycle::Rejected.has_proof());
+
+        assert_eq!(BridgeLifecycle::Proven.orphan().unwrap().transition(BridgeLifecycle::Detected).unwrap(), BridgeLifecycle::Detected);
+
+        assert!(BridgeLifecycle::Proven.has_proof() && BridgeLifecycle::Claimed.has_proof());
+        assert!(!BridgeLifecycle::Committed.has_proof() && !BridgeLifecycle::Orphaned.has_proof());
+
+        for status in BridgeLifecycle::ALL {
+            assert_eq!(BridgeLifecycle::from_str(status.as_str()), Ok(status));
+            assert_eq!(serde_json::to_value(status).unwrap(), status.as_str());
+        }
+    }
+
+    #[test]
+    fn test_commitment_window() {
+        let window = CommitmentWindow::new(10, 20);
+        assert!(!window.contains(10));
+        assert!(window.contains(11) && window.contains(20));
+        assert!(!window.contains(21));
+        assert_eq!(window.next(30), CommitmentWindow::new(20, 30));
+        assert!(!CommitmentWindow::through(5).contains(0));
+    }
+
+    // the query loads the same slots `contains` accepts
+    #[test]
+    fn test_commitment_window_filter() {
+        let query = table_bridge_transaction::table
+            .select(table_bridge_transaction::column_id)
+            .filter(CommitmentWindow::new(10, 20).filter());
+        let sql = diesel::debug_query::<Pg, _>(&query).to_string();
+        assert!(sql.contains("\"bridge_transaction\".\"slot\" BETWEEN $1 AND $2"), "{}", sql);
+        assert!(sql.ends_with("-- binds: [11, 20]"), "{}", sql);
+
+        let sql = diesel::debug_query::<Pg, _>(&table_bridge_transaction::table.filter(CommitmentWindow::through(5).filter())).to_string();
+        assert!(sql.ends_with("-- binds: [1, 5]"), "{}", sql);
+    }
+
+    // consecutive windows, whether walked from the start or resumed after a commitment, hold every leaf once
+    #[test]
+    fn test_commitment_windows_partition_leaves() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..500 {
+            // several messages can share a slot, and a commitment can land on a slot with messages
+            let mut leaves: Vec<i64> = (0..rng.gen_range(0..64)).map(|_| rng.gen_range(1..200)).collect();
+            leaves.sort();
+            let mut commitments: Vec<i64> = (0..rng.gen_range(1..16)).map(|_| rng.gen_range(1..200)).collect();
+            commitments.push(200);
+            commitments.sort();
+            commitments.dedup();
+
+            let mut window = CommitmentWindow::through(commitments[0]);
+            let mut counts = vec![0; leaves.len()];
+            for (i, &slot) in commitments.iter().enumerate() {
+                if i > 0 {
+                    window = window.next(slot);
+                }
+                for (leaf, &leaf_slot) in leaves.iter().enumerate() {
+                    if window.contains(leaf_slot) {
+                        counts[leaf] += 1;
+                    }
+                }
+            }
+            assert!(counts.iter().all(|&count| count == 1), "leaves: {:?}, commitments: {:?}", leaves, commitments);
+
+            // a restart loads the tree through a commitment and continues with the window after it
+            let resume_at = rng.gen_range(0..commitments.len());
+            let loaded = CommitmentWindow::through(commitments[resume_at]);
+            let mut window = loaded;
+            let mut counts: Vec<i32> = leaves.iter().map(|&leaf_slot| loaded.contains(leaf_slot) as i32).collect();
+            for &slot in commitments[resume_at + 1..].iter() {
+                window = window.next(slot);
+                for (leaf, &leaf_slot) in leaves.iter().enumerate() {
+                    if window.contains(leaf_slot) {
+                        counts[leaf] += 1;
+                    }
+                }
+            }
+            assert!(counts.iter().all(|&count| count == 1), "leaves: {:?}, commitments: {:?}, resume at: {}", leaves, commitments, resume_at);
+        }
+    }
+}
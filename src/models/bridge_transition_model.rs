@@ -0,0 +1,46 @@
+use crate::entities::bridge_transition_entity::table_bridge_transition;
+use crate::models::bridge_transaction_model::{BridgeLifecycle, BridgeLifecycleMapping};
+use diesel::Selectable;
+use diesel::{Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+
+/// One status change of a bridge message, the history of its lifecycle.
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = table_bridge_transition)]
+pub struct BridgeTransitionRow {
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_id)]
+    pub id: i64,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_signature)]
+    pub signature: String,
+
+    #[diesel(sql_type = BridgeLifecycleMapping)]
+    #[diesel(column_name = column_from_status)]
+    pub from_status: BridgeLifecycle,
+
+    #[diesel(sql_type = BridgeLifecycleMapping)]
+    #[diesel(column_name = column_to_status)]
+    pub to_status: BridgeLifecycle,
+
+    #[diesel(sql_type = Timestamp)]
+    #[diesel(column_name = column_created_on)]
+    pub created_on: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = table_bridge_transition)]
+pub struct BridgeTransitionRecord {
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_signature)]
+    pub signature: String,
+
+    #[diesel(sql_type = BridgeLifecycleMapping)]
+    #[diesel(column_name = column_from_status)]
+    pub from_status: BridgeLifecycle,
+
+    #[diesel(sql_type = BridgeLifecycleMapping)]
+    #[diesel(column_name = column_to_status)]
+    pub to_status: BridgeLifecycle,
+}
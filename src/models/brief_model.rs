@@ -44,6 +44,11 @@ pub struct BriefRow {
     #[diesel(column_name = column_transaction_number)]
     pub transaction_number: i32,
 
+    // a challenge account was found on chain for the slot, the brief account is never closed
+    #[diesel(sql_type = Bool)]
+    #[diesel(column_name = column_challenged)]
+    pub challenged: bool,
+
     #[diesel(sql_type = Timestamp)]
     #[diesel(column_name = column_updated_on)]
     pub updated_on: chrono::NaiveDateTime,
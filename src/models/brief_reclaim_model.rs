@@ -0,0 +1,37 @@
+use crate::entities::brief_reclaim_entity::table_brief_reclaim;
+use diesel::Selectable;
+use diesel::{Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = table_brief_reclaim)]
+pub struct BriefReclaimRow {
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_id)]
+    pub id: i64,
+
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_slot)]
+    pub slot: i64,
+
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_lamports)]
+    pub lamports: i64,
+
+    #[diesel(sql_type = Timestamp)]
+    #[diesel(column_name = column_updated_on)]
+    pub updated_on: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = table_brief_reclaim)]
+pub struct BriefReclaimRecord {
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_slot)]
+    pub slot: i64,
+
+    // rent returned to the payer when the brief account was closed
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_lamports)]
+    pub lamports: i64,
+}
@@ -0,0 +1,227 @@
+use crate::entities::challenge_entity::table_challenge;
+use crate::models::brief_model::BriefRow;
+use diesel::Selectable;
+use diesel::{AsChangeset, Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+pub enum ChallengeStatus {
+    // recorded by the monitor, not sent yet
+    Pending,
+    // challenge transaction sent to the fraud proof program
+    Submitted,
+    // challenge transaction executed successfully
+    Accepted,
+    // challenge transaction failed on chain
+    Rejected,
+    // the stored challenge is malformed and can't be sent
+    Failed,
+}
+
+impl ChallengeStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChallengeStatus::Pending => "pending",
+            ChallengeStatus::Submitted => "submitted",
+            ChallengeStatus::Accepted => "accepted",
+            ChallengeStatus::Rejected => "rejected",
+            ChallengeStatus::Failed => "failed",
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        matches!(self, ChallengeStatus::Pending | ChallengeStatus::Submitted)
+    }
+}
+
+/// The root a challenge disputes.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+pub enum ChallengeKind {
+    // the bridge message merkle root
+    MerkleRoot,
+    // the world state root of the account tree
+    WorldState,
+}
+
+impl ChallengeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChallengeKind::MerkleRoot => "merkle_root",
+            ChallengeKind::WorldState => "world_state",
+        }
+    }
+}
+
+impl FromStr for ChallengeKind {
+    type Err = String;
+
+    fn from_str(kind: &str) -> Result<Self, Self::Err> {
+        match kind {
+            "merkle_root" => Ok(ChallengeKind::MerkleRoot),
+            "world_state" => Ok(ChallengeKind::WorldState),
+            _ => Err(format!("unknown challenge kind: {}", kind)),
+        }
+    }
+}
+
+impl FromStr for ChallengeStatus {
+    type Err = String;
+
+    fn from_str(status: &str) -> Result<Self, Self::Err> {
+        match status {
+            "pending" => Ok(ChallengeStatus::Pending),
+            "submitted" => Ok(ChallengeStatus::Submitted),
+            "accepted" => Ok(ChallengeStatus::Accepted),
+            "rejected" => Ok(ChallengeStatus::Rejected),
+            "failed" => Ok(ChallengeStatus::Failed),
+            _ => Err(format!("unknown challenge status: {}", status)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = table_challenge)]
+pub struct ChallengeRow {
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_id)]
+    pub id: i64,
+
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_slot)]
+    pub slot: i64,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_kind)]
+    pub kind: String,
+
+    #[diesel(sql_type = Bytea)]
+    #[diesel(column_name = column_local_root)]
+    pub local_root: Vec<u8>,
+
+    #[diesel(sql_type = Bytea)]
+    #[diesel(column_name = column_chain_root)]
+    pub chain_root: Vec<u8>,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_hash_account)]
+    pub hash_account: String,
+
+    #[diesel(sql_type = Int4)]
+    #[diesel(column_name = column_transaction_number)]
+    pub transaction_number: i32,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_signature)]
+    pub signature: String,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_status)]
+    pub status: String,
+
+    #[diesel(sql_type = Timestamp)]
+    #[diesel(column_name = column_updated_on)]
+    pub updated_on: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Insertable, AsChangeset, Serialize, Deserialize)]
+#[diesel(table_name = table_challenge)]
+pub struct ChallengeRecord {
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_slot)]
+    pub slot: i64,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_kind)]
+    pub kind: String,
+
+    #[diesel(sql_type = Bytea)]
+    #[diesel(column_name = column_local_root)]
+    pub local_root: Vec<u8>,
+
+    #[diesel(sql_type = Bytea)]
+    #[diesel(column_name = column_chain_root)]
+    pub chain_root: Vec<u8>,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_hash_account)]
+    pub hash_account: String,
+
+    #[diesel(sql_type = Int4)]
+    #[diesel(column_name = column_transaction_number)]
+    pub transaction_number: i32,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_signature)]
+    pub signature: String,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_status)]
+    pub status: String,
+}
+
+impl From<ChallengeRow> for ChallengeRecord {
+    fn from(cr: ChallengeRow) -> Self {
+        ChallengeRecord {
+            slot: cr.slot,
+            kind: cr.kind,
+            local_root: cr.local_root,
+            chain_root: cr.chain_root,
+            hash_account: cr.hash_account,
+            transaction_number: cr.transaction_number,
+            signature: cr.signature,
+            status: cr.status,
+        }
+    }
+}
+
+impl ChallengeRecord {
+    pub fn pending(slot: i64, kind: ChallengeKind, local_root: Vec<u8>, chain_root: Vec<u8>, brief: Option<BriefRow>) -> Self {
+        let (hash_account, transaction_number) = match brief {
+            Some(brief) => (brief.hash_account, brief.transaction_number),
+            None => (String::new(), 0),
+        };
+
+        ChallengeRecord {
+            slot,
+            kind: kind.as_str().to_string(),
+            local_root,
+            chain_root,
+            hash_account,
+            transaction_number,
+            signature: String::new(),
+            status: ChallengeStatus::Pending.as_str().to_string(),
+        }
+    }
+
+    pub fn status(&self) -> Result<ChallengeStatus, String> {
+        ChallengeStatus::from_str(&self.status)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use std::str::FromStr;
+
+    use crate::models::challenge_model::{ChallengeKind, ChallengeRecord, ChallengeStatus};
+
+    #[test]
+    fn test_challenge_record() {
+        let record = ChallengeRecord::pending(7, ChallengeKind::WorldState, vec![1u8; 32], vec![2u8; 32], None);
+        assert_eq!(ChallengeKind::from_str(&record.kind), Ok(ChallengeKind::WorldState));
+        assert_eq!(record.status(), Ok(ChallengeStatus::Pending));
+        assert!(record.hash_account.is_empty() && record.signature.is_empty());
+
+        for kind in [ChallengeKind::MerkleRoot, ChallengeKind::WorldState] {
+            assert_eq!(ChallengeKind::from_str(kind.as_str()), Ok(kind));
+        }
+        for status in [ChallengeStatus::Pending, ChallengeStatus::Submitted, ChallengeStatus::Accepted, ChallengeStatus::Rejected, ChallengeStatus::Failed] {
+            assert_eq!(ChallengeStatus::from_str(status.as_str()), Ok(status));
+        }
+        assert!(ChallengeStatus::from_str("open").is_err());
+
+        // a corrupt status is not taken for a pending challenge
+        let record = ChallengeRecord { status: "open".to_string(), ..record };
+        assert!(record.status().is_err());
+    }
+}
@@ -0,0 +1,239 @@
+use crate::entities::claim_submission_entity::table_claim_submission;
+use diesel::Selectable;
+use diesel::{AsChangeset, Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+// a failed attempt waits this long, doubled for every further attempt
+const RETRY_BASE_SECONDS: i64 = 10;
+
+const RETRY_MAX_SECONDS: i64 = 600;
+
+// a failed claim is queued again after this long, with its attempts reset
+const REQUEUE_FAILED_SECONDS: i64 = 3600;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+pub enum ClaimSubmissionStatus {
+    // waiting for the next attempt
+    Queued,
+    // claim transaction sent, waiting for the cluster
+    Submitted,
+    // the withdrawal is claimed on L1
+    Confirmed,
+    // gave up after the configured attempts, queued again at `next_attempt_on`
+    Failed,
+}
+
+impl ClaimSubmissionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClaimSubmissionStatus::Queued => "queued",
+            ClaimSubmissionStatus::Submitted => "submitted",
+            ClaimSubmissionStatus::Confirmed => "confirmed",
+            ClaimSubmissionStatus::Failed => "failed",
+        }
+    }
+
+}
+
+impl FromStr for ClaimSubmissionStatus {
+    type Err = String;
+
+    fn from_str(status: &str) -> Result<Self, Self::Err> {
+        match status {
+            "queued" => Ok(ClaimSubmissionStatus::Queued),
+            "submitted" => Ok(ClaimSubmissionStatus::Submitted),
+            "confirmed" => Ok(ClaimSubmissionStatus::Confirmed),
+            "failed" => Ok(ClaimSubmissionStatus::Failed),
+            _ => Err(format!("unknown claim submission status: {}", status)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = table_claim_submission)]
+pub struct ClaimSubmissionRow {
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_id)]
+    pub id: i64,
+
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_leaf_index)]
+    pub leaf_index: i64,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_signature)]
+    pub signature: String,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_status)]
+    pub status: String,
+
+    #[diesel(sql_type = Int4)]
+    #[diesel(column_name = column_attempts)]
+    pub attempts: i32,
+
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_priority_fee)]
+    pub priority_fee: i64,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_l1_signature)]
+    pub l1_signature: String,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_last_error)]
+    pub last_error: String,
+
+    #[diesel(sql_type = Timestamp)]
+    #[diesel(column_name = column_next_attempt_on)]
+    pub next_attempt_on: chrono::NaiveDateTime,
+
+    #[diesel(sql_type = Nullable<Timestamp>)]
+    #[diesel(column_name = column_submitted_on)]
+    pub submitted_on: Option<chrono::NaiveDateTime>,
+
+    #[diesel(sql_type = Timestamp)]
+    #[diesel(column_name = column_updated_on)]
+    pub updated_on: chrono::NaiveDateTime,
+}
+
+/// One L1 claim of a proven withdrawal, the leaf index makes enqueueing idempotent.
+#[derive(Debug, Clone, Insertable, AsChangeset, Serialize, Deserialize)]
+#[diesel(table_name = table_claim_submission)]
+pub struct ClaimSubmissionRecord {
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_leaf_index)]
+    pub leaf_index: i64,
+
+    // the L2 withdrawal transaction
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_signature)]
+    pub signature: String,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_status)]
+    pub status: String,
+
+    #[diesel(sql_type = Int4)]
+    #[diesel(column_name = column_attempts)]
+    pub attempts: i32,
+
+    // micro-lamports per compute unit
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_priority_fee)]
+    pub priority_fee: i64,
+
+    // the last claim transaction sent
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_l1_signature)]
+    pub l1_signature: String,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_last_error)]
+    pub last_error: String,
+
+    #[diesel(sql_type = Timestamp)]
+    #[diesel(column_name = column_next_attempt_on)]
+    pub next_attempt_on: chrono::NaiveDateTime,
+
+    #[diesel(sql_type = Nullable<Timestamp>)]
+    #[diesel(column_name = column_submitted_on)]
+    pub submitted_on: Option<chrono::NaiveDateTime>,
+}
+
+impl From<ClaimSubmissionRow> for ClaimSubmissionRecord {
+    fn from(row: ClaimSubmissionRow) -> Self {
+        ClaimSubmissionRecord {
+            leaf_index: row.leaf_index,
+            signature: row.signature,
+            status: row.status,
+            attempts: row.attempts,
+            priority_fee: row.priority_fee,
+            l1_signature: row.l1_signature,
+            last_error: row.last_error,
+            next_attempt_on: row.next_attempt_on,
+            submitted_on: row.submitted_on,
+        }
+    }
+}
+
+impl ClaimSubmissionRecord {
+    pub fn queued(leaf_index: i64, signature: String, priority_fee: i64, now: chrono::NaiveDateTime) -> Self {
+        ClaimSubmissionRecord {
+            leaf_index,
+            signature,
+            status: ClaimSubmissionStatus::Queued.as_str().to_string(),
+            attempts: 0,
+            priority_fee,
+            l1_signature: String::new(),
+            last_error: String::new(),
+            next_attempt_on: now,
+            submitted_on: None,
+        }
+    }
+
+    pub fn status(&self) -> ClaimSubmissionStatus {
+        self.status.parse().unwrap_or(ClaimSubmissionStatus::Queued)
+    }
+
+    /// Give up for now, the claim is queued again with its attempts reset after a long backoff, the
+    /// cause, a drained fee payer or a congested cluster, may be gone by then.
+    pub fn fail(&mut self, error: String, now: chrono::NaiveDateTime) {
+        self.status = ClaimSubmissionStatus::Failed.as_str().to_string();
+        self.last_error = error;
+        self.next_attempt_on = now + chrono::Duration::seconds(REQUEUE_FAILED_SECONDS);
+    }
+
+    /// Queue another attempt after a backoff, with the priority fee doubled up to `max_priority_fee`.
+    /// Gives up once `max_attempts` were made.
+    pub fn retry(&mut self, error: String, max_attempts: i32, max_priority_fee: i64, now: chrono::NaiveDateTime) {
+        if self.attempts >= max_attempts {
+            self.fail(error, now);
+            return;
+        }
+
+        self.last_error = error;
+
+        let backoff = (RETRY_BASE_SECONDS << (self.attempts.max(1) - 1).min(16)).min(RETRY_MAX_SECONDS);
+        self.status = ClaimSubmissionStatus::Queued.as_str().to_string();
+        self.priority_fee = (self.priority_fee.max(1) * 2).min(max_priority_fee);
+        self.next_attempt_on = now + chrono::Duration::seconds(backoff);
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::models::claim_submission_model::{ClaimSubmissionRecord, ClaimSubmissionStatus};
+
+    #[test]
+    fn test_retry() {
+        let now = chrono::NaiveDateTime::default();
+        let mut record = ClaimSubmissionRecord::queued(3, "sig".to_string(), 0, now);
+
+        record.attempts = 1;
+        record.retry("blockhash expired".to_string(), 3, 5, now);
+        assert_eq!(record.status(), ClaimSubmissionStatus::Queued);
+        assert_eq!(record.priority_fee, 2);
+        assert_eq!(record.next_attempt_on, now + chrono::Duration::seconds(10));
+
+        record.attempts = 2;
+        record.retry("blockhash expired".to_string(), 3, 5, now);
+        assert_eq!(record.priority_fee, 4);
+        assert_eq!(record.next_attempt_on, now + chrono::Duration::seconds(20));
+
+        record.attempts = 3;
+        record.retry("insufficient funds".to_string(), 3, 5, now);
+        assert_eq!(record.status(), ClaimSubmissionStatus::Failed);
+        assert_eq!(record.last_error, "insufficient funds");
+        assert_eq!(record.next_attempt_on, now + chrono::Duration::seconds(3600));
+    }
+
+    #[test]
+    fn test_status_from_str() {
+        for status in [ClaimSubmissionStatus::Queued, ClaimSubmissionStatus::Submitted, ClaimSubmissionStatus::Confirmed, ClaimSubmissionStatus::Failed] {
+            assert_eq!(status.as_str().parse::<ClaimSubmissionStatus>(), Ok(status));
+        }
+        assert!("pending".parse::<ClaimSubmissionStatus>().is_err());
+    }
+}
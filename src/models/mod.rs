@@ -3,3 +3,11 @@ pub mod brief_model;
 pub mod transaction_model;
 pub mod block_model;
 pub mod bridge_transaction_model;
+pub mod challenge_model;
+pub mod brief_reclaim_model;
+pub mod proof_event_model;
+pub mod claim_submission_model;
+pub mod bridge_transition_model;
+pub mod root_commitment_model;
+pub mod root_observation_model;
+pub mod scan_checkpoint_model;
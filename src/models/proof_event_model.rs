@@ -0,0 +1,65 @@
+use crate::entities::proof_event_entity::table_proof_event;
+use crate::models::bridge_transaction_model::BridgeTxRecord;
+use diesel::Selectable;
+use diesel::{Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+
+/// A proof persisted by the monitor, the ids order the events for subscribers.
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = table_proof_event)]
+pub struct ProofEventRow {
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_id)]
+    pub id: i64,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_signature)]
+    pub signature: String,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_sender)]
+    pub sender: String,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_recipient)]
+    pub recipient: String,
+
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_root_program_slot)]
+    pub root_program_slot: i64,
+
+    #[diesel(sql_type = Timestamp)]
+    #[diesel(column_name = column_created_on)]
+    pub created_on: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = table_proof_event)]
+pub struct ProofEventRecord {
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_signature)]
+    pub signature: String,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_sender)]
+    pub sender: String,
+
+    #[diesel(sql_type = Varchar)]
+    #[diesel(column_name = column_recipient)]
+    pub recipient: String,
+
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_root_program_slot)]
+    pub root_program_slot: i64,
+}
+
+impl From<&BridgeTxRecord> for ProofEventRecord {
+    fn from(record: &BridgeTxRecord) -> Self {
+        ProofEventRecord {
+            signature: record.signature.clone(),
+            sender: record.sender.clone(),
+            recipient: record.recipient.clone(),
+            root_program_slot: record.root_program_slot,
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::entities::root_commitment_entity::table_root_commitment;
+use diesel::Selectable;
+use diesel::{Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+
+/// A root of the root manager program and when it was committed on L1.
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = table_root_commitment)]
+pub struct RootCommitmentRow {
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_id)]
+    pub id: i64,
+
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_slot)]
+    pub slot: i64,
+
+    #[diesel(sql_type = Bytea)]
+    #[diesel(column_name = column_merkle_tree_root)]
+    pub merkle_tree_root: Vec<u8>,
+
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_l1_slot)]
+    pub l1_slot: i64,
+
+    #[diesel(sql_type = Timestamp)]
+    #[diesel(column_name = column_committed_on)]
+    pub committed_on: chrono::NaiveDateTime,
+
+    #[diesel(sql_type = Timestamp)]
+    #[diesel(column_name = column_claimable_on)]
+    pub claimable_on: chrono::NaiveDateTime,
+
+    #[diesel(sql_type = Bool)]
+    #[diesel(column_name = column_finalized)]
+    pub finalized: bool,
+
+    #[diesel(sql_type = Timestamp)]
+    #[diesel(column_name = column_created_on)]
+    pub created_on: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = table_root_commitment)]
+pub struct RootCommitmentRecord {
+    // the slot the root is stored under in the root manager program
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_slot)]
+    pub slot: i64,
+
+    #[diesel(sql_type = Bytea)]
+    #[diesel(column_name = column_merkle_tree_root)]
+    pub merkle_tree_root: Vec<u8>,
+
+    // the L1 slot of the transaction that created the roots account
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_l1_slot)]
+    pub l1_slot: i64,
+
+    #[diesel(sql_type = Timestamp)]
+    #[diesel(column_name = column_committed_on)]
+    pub committed_on: chrono::NaiveDateTime,
+
+    #[diesel(sql_type = Timestamp)]
+    #[diesel(column_name = column_claimable_on)]
+    pub claimable_on: chrono::NaiveDateTime,
+
+    // the root is finalized on L1, proofs against it are provisional until then
+    #[diesel(sql_type = Bool)]
+    #[diesel(column_name = column_finalized)]
+    pub finalized: bool,
+}
+
+impl From<RootCommitmentRow> for RootCommitmentRecord {
+    fn from(row: RootCommitmentRow) -> Self {
+        RootCommitmentRecord {
+            slot: row.slot,
+            merkle_tree_root: row.merkle_tree_root,
+            l1_slot: row.l1_slot,
+            committed_on: row.committed_on,
+            claimable_on: row.claimable_on,
+            finalized: row.finalized,
+        }
+    }
+}
+
+impl RootCommitmentRecord {
+    pub fn new(slot: i64, merkle_tree_root: Vec<u8>, l1_slot: i64, committed_on: chrono::NaiveDateTime, challenge_period_seconds: u64) -> Self {
+        RootCommitmentRecord {
+            slot,
+            merkle_tree_root,
+            l1_slot,
+            committed_on,
+            claimable_on: committed_on + chrono::Duration::seconds(challenge_period_seconds as i64),
+            finalized: false,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::models::root_commitment_model::RootCommitmentRecord;
+
+    #[test]
+    fn test_claimable_on() {
+        let committed_on = chrono::DateTime::from_timestamp(1_000, 0).unwrap().naive_utc();
+        let record = RootCommitmentRecord::new(7, vec![1u8; 32], 90, committed_on, 600);
+        assert_eq!(record.claimable_on.and_utc().timestamp(), 1_600);
+    }
+}
@@ -0,0 +1,41 @@
+use crate::entities::root_observation_entity::table_root_observation;
+use diesel::Selectable;
+use diesel::{Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+
+/// A root read from the root manager program for a slot, a rewritten root adds another row.
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = table_root_observation)]
+pub struct RootObservationRow {
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_id)]
+    pub id: i64,
+
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_slot)]
+    pub slot: i64,
+
+    #[diesel(sql_type = Bytea)]
+    #[diesel(column_name = column_merkle_tree_root)]
+    pub merkle_tree_root: Vec<u8>,
+
+    #[diesel(sql_type = Timestamp)]
+    #[diesel(column_name = column_first_seen_on)]
+    pub first_seen_on: chrono::NaiveDateTime,
+
+    #[diesel(sql_type = Timestamp)]
+    #[diesel(column_name = column_last_seen_on)]
+    pub last_seen_on: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = table_root_observation)]
+pub struct RootObservationRecord {
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_slot)]
+    pub slot: i64,
+
+    #[diesel(sql_type = Bytea)]
+    #[diesel(column_name = column_merkle_tree_root)]
+    pub merkle_tree_root: Vec<u8>,
+}
@@ -0,0 +1,48 @@
+use crate::entities::scan_checkpoint_entity::table_scan_checkpoint;
+use diesel::Selectable;
+use diesel::{AsChangeset, Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+
+// the highest L2 slot the filter scanned for bridge messages
+pub const FILTER_CHECKPOINT: &str = "filter";
+
+// the L2 slot of the L1 commitment the monitor works on
+pub const MONITOR_TARGET: &str = "monitor_target";
+
+// the L2 slot of the last withdrawal the finalizer queued a claim for
+pub const FINALIZER_CURSOR: &str = "finalizer_cursor";
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable, AsChangeset, Serialize, Deserialize)]
+#[diesel(table_name = table_scan_checkpoint)]
+pub struct ScanCheckpointRow {
+    #[diesel(sql_type = VarChar)]
+    #[diesel(column_name = column_name)]
+    pub name: String,
+
+    #[diesel(sql_type = Int8)]
+    #[diesel(column_name = column_slot)]
+    pub slot: i64,
+
+    #[diesel(sql_type = Timestamp)]
+    #[diesel(column_name = column_updated_on)]
+    pub updated_on: chrono::NaiveDateTime,
+}
+
+/// How far the filter scanned, against the L2 head and the commitment the monitor waits for.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScanProgress {
+    pub filter_slot: i64,
+    pub monitor_target_slot: i64,
+    pub head_slot: i64,
+}
+
+impl ScanProgress {
+    pub fn filter_lag(&self) -> i64 {
+        (self.head_slot - self.filter_slot).max(0)
+    }
+
+    /// Slots the filter still has to scan before the monitor can verify its commitment.
+    pub fn commitment_lag(&self) -> i64 {
+        (self.monitor_target_slot - self.filter_slot).max(0)
+    }
+}
@@ -1,6 +1,20 @@
-use log::{error, info};
+use std::time::{Duration, Instant};
+
+use log::{error, info, warn};
 use dd_merkle_tree::{MerkleTree, HashingAlgorithm};
-use crate::{common::{node_configs::{ChainConfiguration, ContractConfiguration, StoreConfiguration}, node_error::NodeError}, services::{chain_service::ChainService, execute_service::ExecuteService}, utils::{time_util, uuid_util::generate_uuid}};
+use crate::models::bridge_transaction_model::CommitmentWindow;
+use crate::models::root_commitment_model::RootCommitmentRecord;
+use crate::models::scan_checkpoint_model::{FILTER_CHECKPOINT, MONITOR_TARGET};
+use crate::services::chain_root_mgr_service::RootMgrError;
+use crate::{common::{node_configs::{ChainConfiguration, ContractConfiguration, StoreConfiguration}, node_error::NodeError}, services::{chain_service::ChainService, chain_slots_feed::ChainSlotsFeed, execute_service::ExecuteService}, utils::time_util};
+
+// the most provisional roots checked for finality in one round
+const PROMOTE_ROOTS: i64 = 32;
+
+// the longest the monitor waits for the filter to reach a commitment before retrying next round
+const FILTER_WAIT_SECONDS: u64 = 120;
+
+const FILTER_LAG_LOG_SECONDS: u64 = 10;
 
 pub struct Monitor {
     execute_service: Option<ExecuteService>,
@@ -64,6 +78,9 @@ impl Monitor {
             error!("{:?}", e);
         }
 
+        let challenge_period_seconds = self.challenge_period_seconds();
+        info!("challenge period seconds: {}", challenge_period_seconds);
+
         self.local_tree = Some(MerkleTree::new(HashingAlgorithm::Sha256d, 32));
         let chain_service = self.chain_service.as_mut().unwrap();
         let execute_service = self.execute_service.as_mut().unwrap();
@@ -77,84 +94,342 @@ impl Monitor {
         }
         info!("dong: earliest no proof tx slot: {}", earliest_no_proof_tx_slot);
 
+        // the proofs of a commitment are persisted with it, the next slot after the latest one is the
+        // first uncommitted. proofs persisted before commitments were recorded fall back to the last proven tx.
         let mut max_has_proof_tx_slot: i64 = 0;
-        if let Ok(last_has_proof_tx) = execute_service.get_last_has_proof_bridge_tx_from_pg_for_monitor() {
+        if let Some(commitment) = execute_service.recent_root_commitments(1)?.first() {
+            max_has_proof_tx_slot = commitment.slot;
+        } else if let Ok(last_has_proof_tx) = execute_service.get_last_has_proof_bridge_tx_from_pg_for_monitor() {
             max_has_proof_tx_slot = last_has_proof_tx.slot;
         }
         //let last_has_proof_tx_slot = execute_service.get_last_slot_from_rkdb_for_monitor().unwrap();
         info!("dong: local_last_slot: {}", max_has_proof_tx_slot);
         if max_has_proof_tx_slot > 0 {
-            let old_hashes = execute_service.brige_txs_hashes(0, max_has_proof_tx_slot).unwrap();
-            if old_hashes.len() != 0 {
-                local_tree_leaf_num = old_hashes.len() - 1;
-            }
-            info!("dong: old_hashes {:?}", old_hashes);
-            let _ = local_tree.add_hashes(old_hashes);
-            
+            local_tree_leaf_num = load_tree(execute_service, local_tree, max_has_proof_tx_slot);
         }
+        // a slot left unproven is taken up again from the slots on chain next round
+        let mut retry = false;
         
+        let root_recheck_count = self.chain_config.as_ref().unwrap().root_recheck_count as i64;
+        // the first round hands out every slot on chain, later rounds only the appended ones
+        let mut slots_feed = ChainSlotsFeed::new(self.chain_config.as_ref().unwrap());
         loop {
-            // check rootmgr latest slot
-            
-            let chain_all_slots = chain_service.get_all_slots_from_chain().unwrap_or_default();
-            //let chain_all_slots = vec![52833];
-            if chain_all_slots.len() == 0 as usize {
-                info!("there is no slots info on chain, waitting...");
-                time_util::sleep_seconds(1);
+            // check rootmgr new slots
+            let update = slots_feed.next(chain_service);
+            for slot in update.out_of_order.iter() {
+                error!("slot out of order on chain, skipped. slot: {}, local last slot: {}", slot, max_has_proof_tx_slot);
+            }
+            if let Some(slot) = update.rewritten_from {
+                warn!("slots rewritten on chain. from slot: {}", slot);
+            }
+
+            // roots of the latest commitments can also be overwritten in place
+            let root_rewritten_from = match recheck_roots(chain_service, execute_service, root_recheck_count) {
+                Ok(slot) => slot,
+                Err(e) => {
+                    error!("recheck roots fail. {:?}", e);
+                    None
+                }
+            };
+            if let Err(e) = promote_roots(chain_service, execute_service) {
+                error!("promote provisional roots fail. {:?}", e);
+            }
+
+            // a rewrite of slots not processed yet voids nothing
+            let rewritten_from = [update.rewritten_from.filter(|&slot| slot as i64 <= max_has_proof_tx_slot), root_rewritten_from]
+                .into_iter()
+                .flatten()
+                .min();
+
+            let chain_new_slots = match rewritten_from {
+                Some(rewritten_from) => {
+                    let (invalidated, kept) = execute_service.invalidate_roots_from(rewritten_from as i64)?;
+                    warn!("proofs against rewritten roots invalidated. from slot: {}, invalidated: {}, kept: {}",
+                        rewritten_from, invalidated.len(), kept.len());
+                    for row in kept.iter() {
+                        error!("withdrawal claimed or being claimed against a rewritten root. signature: {}, root slot: {}", row.signature, row.root_program_slot);
+                    }
+
+                    max_has_proof_tx_slot = execute_service.last_root_commitment_before(rewritten_from as i64)?.map_or(0, |commitment| commitment.slot);
+                    local_tree_leaf_num = load_tree(execute_service, local_tree, max_has_proof_tx_slot);
+
+                    // regenerate against every slot on chain after the last commitment left, not only the recent ones
+                    match chain_service.get_all_slots_from_chain() {
+                        Some(slots) => {
+                            retry = false;
+                            slots
+                        }
+                        None => {
+                            retry = true;
+                            continue;
+                        }
+                    }
+                }
+                None if retry => match chain_service.get_all_slots_from_chain() {
+                    Some(slots) => {
+                        retry = false;
+                        slots
+                    }
+                    None => continue,
+                },
+                None => update.slots,
+            };
+            if chain_new_slots.is_empty() {
                 continue;
             }
-            info!("dong: chain_all_slots: {:?}", chain_all_slots);
-            let chain_last_slot = chain_all_slots[chain_all_slots.len() - 1];
-            info!("dong: local_last_slot: {}, chain_last_slot: {}", max_has_proof_tx_slot, chain_last_slot);
+            info!("dong: chain_new_slots: {:?}", chain_new_slots);
 
-            if !(chain_last_slot > max_has_proof_tx_slot as u64) {
-                info!("there is no slot update on chain. local last slot: {:?}, chain last slot: {:?}", max_has_proof_tx_slot.clone(),chain_last_slot.clone());
-                time_util::sleep_seconds(1);
+            let mut chain_sub_slots: Vec<u64> = vec![];
+            for slot in chain_new_slots {
+                if slot > chain_sub_slots.last().copied().unwrap_or(max_has_proof_tx_slot as u64) {
+                    chain_sub_slots.push(slot);
+                }
+            }
+            if chain_sub_slots.is_empty() {
+                info!("there is no slot update on chain. local last slot: {:?}", max_has_proof_tx_slot);
                 continue;
             }
-            
-            let chain_sub_slots: Vec<u64> = chain_all_slots.iter().filter(|&&s| s > max_has_proof_tx_slot as u64).cloned().collect();
-            let mut tmp_start_slot = max_has_proof_tx_slot;
+            let mut window = CommitmentWindow::through(max_has_proof_tx_slot);
             for tmp_slot in chain_sub_slots {
-                let mut bridge_txs = execute_service.bridge_tx_range(tmp_start_slot, tmp_slot as i64).unwrap();
+                // the tree holds the leaves through the previous commitment, nothing to reload
+                match wait_for_filter(execute_service, tmp_slot as i64) {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        retry = true;
+                        break;
+                    }
+                    Err(e) => {
+                        error!("wait for filter fail, retry. slot: {}, err: {:?}", tmp_slot, e);
+                        retry = true;
+                        break;
+                    }
+                }
+
+                // the messages after the previous commitment through this one
+                window = window.next(tmp_slot as i64);
+                let mut bridge_txs = match execute_service.bridge_tx_range(window) {
+                    Ok(bridge_txs) => bridge_txs,
+                    Err(e) => {
+                        error!("get bridge txs fail, retry. slot: {}, err: {:?}", tmp_slot, e);
+                        local_tree_leaf_num = load_tree(execute_service, local_tree, max_has_proof_tx_slot);
+                        retry = true;
+                        break;
+                    }
+                };
                 let bridge_txs_hashes: Vec<Vec<u8>>= bridge_txs.clone().into_iter().map(|bt| {bt.tx_info_hash}).collect();
                 info!("dong: bridge_txs_hashes {:?}", bridge_txs_hashes);
-                let _ = local_tree.add_hashes(bridge_txs_hashes).unwrap();
-                
-                let _ = local_tree.merklize().unwrap();
+                if let Err(e) = local_tree.add_hashes(bridge_txs_hashes) {
+                    error!("add hashes to local tree fail, retry. slot: {}, err: {:?}", tmp_slot, e);
+                    local_tree_leaf_num = load_tree(execute_service, local_tree, max_has_proof_tx_slot);
+                    retry = true;
+                    break;
+                }
+
+                if let Err(e) = local_tree.merklize() {
+                    error!("merklize local tree fail, retry. slot: {}, err: {:?}", tmp_slot, e);
+                    local_tree_leaf_num = load_tree(execute_service, local_tree, max_has_proof_tx_slot);
+                    retry = true;
+                    break;
+                }
     
                 let local_mt_root = local_tree.get_merkle_root().unwrap();
-                let chain_roots_info = chain_service.get_roots_info_by_slot(tmp_slot).unwrap();
+                let chain_roots_info = match chain_service.get_roots_info_by_slot(tmp_slot) {
+                    Ok(chain_roots_info) => chain_roots_info,
+                    Err(e) => {
+                        error!("get roots info fail, retry. slot: {}, err: {:?}", tmp_slot, e);
+                        local_tree_leaf_num = load_tree(execute_service, local_tree, max_has_proof_tx_slot);
+                        retry = true;
+                        break;
+                    }
+                };
+                if let Err(e) = execute_service.observe_root(tmp_slot as i64, chain_roots_info.merkle_tree_root.to_vec()) {
+                    error!("record root observation fail. slot: {}, err: {:?}", tmp_slot, e);
+                }
                 // todo tmp del
                 if chain_roots_info.merkle_tree_root.to_vec() != local_mt_root {
                     error!("local merkle tree is different to the tree on chain, chain merkle tree root: {:?}, local root: {:?}", chain_roots_info.merkle_tree_root.to_vec(), local_mt_root);
-                    return  Err(NodeError::new(
-                        generate_uuid(), 
-                        "local merkle tree is different to the tree on chain".to_string()
-                    ));
+                    if self.chain_config.as_ref().unwrap().enable_challenger {
+                        match execute_service.record_challenge(tmp_slot as i64, local_mt_root.clone(), chain_roots_info.merkle_tree_root.to_vec()) {
+                            Ok(_) => info!("challenge recorded. slot: {}", tmp_slot),
+                            Err(e) => error!("record challenge fail. slot: {}, err: {:?}", tmp_slot, e),
+                        }
+                    }
+                    // nothing is proven against a disputed root, check it again until it is replaced
+                    local_tree_leaf_num = load_tree(execute_service, local_tree, max_has_proof_tx_slot);
+                    retry = true;
+                    break;
                 }
     
+                // the l1 time is unknown when the rpc node pruned the commit transaction, use when it was observed
+                let (l1_slot, block_time) = match chain_service.get_roots_commit(tmp_slot) {
+                    Ok(Some(commit)) => commit,
+                    Ok(None) => (0, None),
+                    Err(e) => {
+                        // the claimable time of every proof hangs on it, commit the slot on a later tick
+                        error!("get roots commit fail, retry. slot: {}, err: {:?}", tmp_slot, e);
+                        local_tree_leaf_num = load_tree(execute_service, local_tree, max_has_proof_tx_slot);
+                        retry = true;
+                        break;
+                    }
+                };
+                let committed_on = block_time
+                    .and_then(|block_time| chrono::DateTime::from_timestamp(block_time, 0))
+                    .unwrap_or_else(chrono::Utc::now)
+                    .naive_utc();
+                let mut commitment = RootCommitmentRecord::new(
+                    tmp_slot as i64, chain_roots_info.merkle_tree_root.to_vec(), l1_slot as i64, committed_on, challenge_period_seconds);
+                // a root read at `finalized` is final right away, otherwise `promote_roots` picks it up
+                commitment.finalized = chain_service.reads_finalized();
+
                 info!("dong monitor: 11 bridge_txs: {:?}", bridge_txs);
                 let _ = bridge_txs.iter_mut().for_each(| bt| {
                     //let proof = local_tree.merkle_proof_hash(bt.clone().tx_info_hash).unwrap();
                     let proof = local_tree.merkle_proof_index(local_tree_leaf_num).unwrap();
                     bt.proof = hex::encode(proof.get_pairing_hashes());
-                    bt.is_generated_proof = true;
                     bt.current_mt_root = local_mt_root.clone();
                     bt.root_program_slot = tmp_slot as i64;
+                    bt.proven_on = Some(chrono::Utc::now().naive_utc());
                     local_tree_leaf_num += 1;
                 });
     
                 info!("dong monitor: 22 bridge_txs: {:?}", bridge_txs);
-                
-                let _ = bridge_txs.iter().for_each(|bt| {
-                    info!("updata tx: {:?}", bt.clone());
-                    execute_service.bridge_tx_update(bt.clone()).unwrap();
-                });
+
+                // all or nothing, a failed commit leaves no proof of the slot behind and it is proven again
+                let commitment = match execute_service.commit_proofs(&commitment, &bridge_txs) {
+                    Ok(commitment) => commitment,
+                    Err(e) => {
+                        error!("commit proofs fail, retry. slot: {}, err: {:?}", tmp_slot, e);
+                        local_tree_leaf_num = load_tree(execute_service, local_tree, max_has_proof_tx_slot);
+                        retry = true;
+                        break;
+                    }
+                };
+                info!("root committed. slot: {}, l1 slot: {}, committed on: {}, claimable on: {}, proofs: {}",
+                    tmp_slot, commitment.l1_slot, commitment.committed_on, commitment.claimable_on, bridge_txs.len());
+
                 max_has_proof_tx_slot = tmp_slot as i64;
-                tmp_start_slot = tmp_slot as i64;
             }
             //execute_service.update_last_slot_to_rkdb_for_monitor(chain_last_slot as i64);
         }
     }
-}
\ No newline at end of file
+
+    /// The challenge period of the fraud proof program, or the configured one when it is not read from L1.
+    fn challenge_period_seconds(&self) -> u64 {
+        let chain_config = self.chain_config.as_ref().unwrap();
+        if !chain_config.challenge_period_from_state {
+            return chain_config.challenge_period_seconds;
+        }
+
+        match self.chain_service.as_ref().and_then(|chain_service| chain_service.get_challenge_period()) {
+            Some(challenge_period) => challenge_period,
+            None => {
+                error!("challenge period not found in state account, use the configured one. seconds: {}", chain_config.challenge_period_seconds);
+                chain_config.challenge_period_seconds
+            }
+        }
+    }
+}
+
+/// Rebuild `local_tree` from the leaves up to `slot`, returning the leaf number to continue from.
+fn load_tree(execute_service: &ExecuteService, local_tree: &mut MerkleTree, slot: i64) -> usize {
+    *local_tree = MerkleTree::new(HashingAlgorithm::Sha256d, 32);
+    let mut local_tree_leaf_num = 0;
+    let old_hashes = execute_service.brige_txs_hashes(CommitmentWindow::through(slot)).unwrap();
+    if old_hashes.len() != 0 {
+        local_tree_leaf_num = old_hashes.len();
+    }
+    info!("dong: old_hashes {:?}", old_hashes);
+    let _ = local_tree.add_hashes(old_hashes);
+
+    local_tree_leaf_num
+}
+
+/// Read the roots of the latest `count` commitments and of every one not promoted yet again,
+/// returning the lowest slot whose root changed or disappeared on L1.
+fn recheck_roots(chain_service: &mut ChainService, execute_service: &ExecuteService, count: i64) -> Result<Option<u64>, NodeError> {
+    let mut commitments = execute_service.recent_root_commitments(count)?;
+    if let Some(oldest) = execute_service.provisional_root_commitments(1)?.first() {
+        if commitments.last().map_or(true, |commitment| oldest.slot < commitment.slot) {
+            commitments = execute_service.root_commitments_since(oldest.slot)?;
+        }
+    }
+
+    let mut rewritten_from = None;
+    // highest slot first, so the lowest rewritten one is kept
+    for commitment in commitments {
+        let slot = commitment.slot as u64;
+        let chain_root = match chain_service.get_roots_info_by_slot(slot) {
+            Ok(roots_info) => roots_info.merkle_tree_root.to_vec(),
+            Err(RootMgrError::AccountNotFound(address)) => {
+                warn!("roots account removed on chain. slot: {}, address: {}", slot, address);
+                rewritten_from = Some(slot);
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        execute_service.observe_root(commitment.slot, chain_root.clone())?;
+
+        if chain_root != commitment.merkle_tree_root {
+            let observed: Vec<String> = execute_service.root_observations(commitment.slot)?
+                .iter()
+                .map(|observation| format!("{}@{}", hex::encode(&observation.merkle_tree_root), observation.first_seen_on))
+                .collect();
+            warn!("root rewritten on chain. slot: {}, committed root: {}, chain root: {}, observed: {:?}",
+                slot, hex::encode(&commitment.merkle_tree_root), hex::encode(&chain_root), observed);
+            rewritten_from = Some(slot);
+        }
+    }
+
+    Ok(rewritten_from)
+}
+
+/// Promote the provisional roots that L1 finalized unchanged, lowest slot first.
+fn promote_roots(chain_service: &ChainService, execute_service: &ExecuteService) -> Result<usize, NodeError> {
+    let mut promoted = 0;
+    for commitment in execute_service.provisional_root_commitments(PROMOTE_ROOTS)? {
+        let finalized_root = match chain_service.get_finalized_roots_info_by_slot(commitment.slot as u64) {
+            Ok(roots_info) => roots_info.merkle_tree_root.to_vec(),
+            // not finalized yet, and neither are the later ones
+            Err(RootMgrError::AccountNotFound(_)) => break,
+            Err(e) => return Err(e.into()),
+        };
+        if finalized_root != commitment.merkle_tree_root {
+            // left to `recheck_roots`, which reads every unpromoted commitment again
+            warn!("finalized root differs from the provisional one. slot: {}, provisional root: {}, finalized root: {}",
+                commitment.slot, hex::encode(&commitment.merkle_tree_root), hex::encode(&finalized_root));
+            break;
+        }
+
+        let count = execute_service.finalize_root(commitment.slot)?;
+        info!("root finalized. slot: {}, proofs: {}", commitment.slot, count);
+        promoted += 1;
+    }
+
+    Ok(promoted)
+}
+
+/// Wait until the filter scanned every L2 slot up to the commitment `slot`, so the local tree
+/// holds all of its leaves. Gives up after `FILTER_WAIT_SECONDS`, returning whether it caught up.
+///
+/// The lag is also exported as `relayer_filter_commitment_lag_slots`.
+fn wait_for_filter(execute_service: &ExecuteService, slot: i64) -> Result<bool, NodeError> {
+    execute_service.update_scan_checkpoint(MONITOR_TARGET, slot)?;
+
+    let started = Instant::now();
+    let mut last_log: Option<Instant> = None;
+    loop {
+        let filter_slot = execute_service.get_scan_checkpoint(FILTER_CHECKPOINT)?;
+        if filter_slot >= slot {
+            return Ok(true);
+        }
+        if started.elapsed() >= Duration::from_secs(FILTER_WAIT_SECONDS) {
+            warn!("filter still behind, commitment put off. commitment slot: {}, filter slot: {}, lag: {}", slot, filter_slot, slot - filter_slot);
+            return Ok(false);
+        }
+        if last_log.map_or(true, |last_log| last_log.elapsed() >= Duration::from_secs(FILTER_LAG_LOG_SECONDS)) {
+            warn!("waiting for filter. commitment slot: {}, filter slot: {}, lag: {}", slot, filter_slot, slot - filter_slot);
+            last_log = Some(Instant::now());
+        }
+        time_util::sleep_seconds(1);
+    }
+}
@@ -47,4 +47,21 @@ impl AccountAuditRepo {
 
         Ok(row)
     }
+
+    /// The last slot before `slot` with account changes.
+    pub fn last_slot_before(&self, slot: i64) -> Result<Option<i64>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let results = table_account_audit
+            .select(column_slot)
+            .filter(column_slot.lt(slot))
+            .order(column_slot.desc())
+            .limit(1)
+            .load::<i64>(conn)
+            .map_err(|e| {
+                NodeError::new(generate_uuid(), format!("Error loading account_audit: {:?}", e))
+            })?;
+
+        Ok(results.first().cloned())
+    }
 }
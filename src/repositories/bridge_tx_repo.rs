@@ -1,14 +1,33 @@
 use crate::common::node_error::NodeError;
 use crate::entities::account_audit_entity::table_account_audit::column_write_version;
-use crate::entities::bridge_transaction_entity::table_bridge_transaction::{column_is_generated_proof, column_signature, column_slot};
+use crate::entities::bridge_transaction_entity::table_bridge_transaction::{column_claim_attempts, column_claim_slot, column_claim_status, column_claimable_on, column_committed_on, column_created_on, column_current_mt_root, column_finalized, column_id, column_is_generated_proof, column_l1_commit_slot, column_l1_signature, column_proof, column_proven_on, column_recipient, column_root_program_slot, column_sender, column_signature, column_slot, column_status, column_tx_info_hash};
 use crate::entities::bridge_transaction_entity::table_bridge_transaction::dsl::table_bridge_transaction;
-use crate::models::bridge_transaction_model::{BridgeTxRecord, BridgeTxRow};
+use crate::entities::bridge_transition_entity::table_bridge_transition::{column_id as transition_id, column_signature as transition_signature};
+use crate::entities::bridge_transition_entity::table_bridge_transition::dsl::table_bridge_transition;
+use crate::entities::claim_submission_entity::table_claim_submission::{column_signature as claim_signature, column_status as claim_status};
+use crate::entities::claim_submission_entity::table_claim_submission::dsl::table_claim_submission;
+use crate::entities::proof_event_entity::table_proof_event::dsl::table_proof_event;
+use crate::entities::root_commitment_entity::table_root_commitment::{column_finalized as commitment_finalized, column_slot as commitment_slot};
+use crate::entities::root_commitment_entity::table_root_commitment::dsl::table_root_commitment;
+use crate::entities::scan_checkpoint_entity::table_scan_checkpoint::{column_name as checkpoint_name, column_slot as checkpoint_slot};
+use crate::entities::scan_checkpoint_entity::table_scan_checkpoint::dsl::table_scan_checkpoint;
+use crate::models::bridge_transaction_model::{AddressRole, BridgeLifecycle, BridgeTxRecord, ClaimStatus, BridgeTxRow, CommitmentWindow, WithdrawalQuery};
+use crate::models::bridge_transition_model::{BridgeTransitionRecord, BridgeTransitionRow};
+use crate::models::claim_submission_model::ClaimSubmissionStatus;
+use crate::models::proof_event_model::ProofEventRecord;
+use crate::models::scan_checkpoint_model::FINALIZER_CURSOR;
+use crate::models::root_commitment_model::{RootCommitmentRecord, RootCommitmentRow};
+use crate::services::chain_claim_service::ClaimMarker;
 use crate::utils::store_util::{PgConnectionPool, PooledPgConnection};
 use crate::utils::uuid_util::generate_uuid;
+use diesel::dsl::count_star;
+use diesel::pg::Pg;
 use diesel::prelude::*;
 use diesel::RunQueryDsl;
 use log::error;
 
+type BoxedBridgeTxQuery = crate::entities::bridge_transaction_entity::table_bridge_transaction::BoxedQuery<'static, Pg>;
+
 pub struct BridgeTxRepo {
     pub pool: Box<PgConnectionPool>,
 }
@@ -114,22 +133,484 @@ impl BridgeTxRepo {
         Ok(updated_row)
     }
     
-    pub fn range(&self, from_slot: i64, to_slot: i64) -> Result<Vec<BridgeTxRow>, NodeError> {
+    /// The messages of `window`, in tree order.
+    pub fn range(&self, window: CommitmentWindow) -> Result<Vec<BridgeTxRow>, NodeError> {
         let conn: &mut PooledPgConnection = &mut self.pool.get()?;
 
         let rows = table_bridge_transaction
-            .filter(column_slot.ge(from_slot).and(column_slot.le(to_slot)))
-            .order(column_slot.asc())
+            .filter(window.filter())
+            .order((column_slot.asc(), column_id.asc()))
             .load::<BridgeTxRow>(conn)
-            .expect("Error loading bridge_tx");
+            .map_err(|e| {
+                error!("Error loading bridge tx: {:?}", e);
+                NodeError::new(generate_uuid(), format!("Error loading bridge tx: {:?}", e))
+            })?;
 
         Ok(rows)
     }
 
-    pub fn bridge_tx_hashes(&self, from_slot: i64, to_slot: i64) -> Result<Vec<Vec<u8>>, NodeError> {
-        let bridge_txs = self.range(from_slot, to_slot).unwrap();
+    pub fn bridge_tx_hashes(&self, window: CommitmentWindow) -> Result<Vec<Vec<u8>>, NodeError> {
+        let bridge_txs = self.range(window)?;
         let hashes = bridge_txs.into_iter().map(|t| {t.tx_info_hash}).collect();
         
         Ok(hashes)
     }
-}
\ No newline at end of file
+
+    pub fn find_by_signature(&self, signature: &str) -> Result<Option<BridgeTxRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let row = table_bridge_transaction
+            .filter(column_signature.eq(signature))
+            .first::<BridgeTxRow>(conn)
+            .optional()?;
+
+        Ok(row)
+    }
+
+    pub fn find_by_tx_info_hash(&self, tx_info_hash: &[u8]) -> Result<Option<BridgeTxRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let row = table_bridge_transaction
+            .filter(column_tx_info_hash.eq(tx_info_hash))
+            .order(column_id.asc())
+            .first::<BridgeTxRow>(conn)
+            .optional()?;
+
+        Ok(row)
+    }
+
+    /// Leaves of the bridge merkle tree are ordered by slot, then by insertion.
+    pub fn find_by_leaf_index(&self, leaf_index: i64) -> Result<Option<BridgeTxRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let row = leaf_at(leaf_index)
+            .first::<BridgeTxRow>(conn)
+            .optional()?;
+
+        Ok(row)
+    }
+
+    /// The index of the leaf of `row` in every bridge tree that holds it.
+    pub fn leaf_index(&self, row: &BridgeTxRow) -> Result<i64, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let index = leaves_before(row)?
+            .count()
+            .get_result::<i64>(conn)?;
+
+        Ok(index)
+    }
+
+    /// One page of the withdrawals of an address, ordered by `(slot, id)` descending.
+    pub fn history(&self, query: &WithdrawalQuery) -> Result<Vec<BridgeTxRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let mut statement = table_bridge_transaction.into_boxed();
+        statement = match query.role {
+            AddressRole::Sender => statement.filter(column_sender.eq(&query.address)),
+            AddressRole::Recipient => statement.filter(column_recipient.eq(&query.address)),
+            AddressRole::Any => statement.filter(column_sender.eq(&query.address).or(column_recipient.eq(&query.address))),
+        };
+        if !query.statuses.is_empty() {
+            statement = statement.filter(column_status.eq_any(query.statuses.clone()));
+        }
+        if let Some(from_slot) = query.from_slot {
+            statement = statement.filter(column_slot.ge(from_slot));
+        }
+        if let Some(to_slot) = query.to_slot {
+            statement = statement.filter(column_slot.le(to_slot));
+        }
+        if let Some(from_time) = query.from_time {
+            statement = statement.filter(column_created_on.ge(from_time));
+        }
+        if let Some(to_time) = query.to_time {
+            statement = statement.filter(column_created_on.le(to_time));
+        }
+        if let Some((slot, id)) = query.cursor {
+            statement = statement.filter(column_slot.lt(slot).or(column_slot.eq(slot).and(column_id.lt(id))));
+        }
+
+        let rows = statement
+            .order((column_slot.desc(), column_id.desc()))
+            .limit(query.limit)
+            .load::<BridgeTxRow>(conn)?;
+
+        Ok(rows)
+    }
+
+    /// Proven withdrawals with a final proof whose challenge window ended by `now`, after the `(slot, id)` cursor in leaf order.
+    pub fn claimable(&self, now: chrono::NaiveDateTime, cursor: (i64, i64), limit: i64) -> Result<Vec<BridgeTxRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let (slot, id) = cursor;
+        let rows = table_bridge_transaction
+            .filter(column_status.eq(BridgeLifecycle::Proven))
+            .filter(column_claimable_on.le(now))
+            .filter(column_finalized.eq(true))
+            .filter(column_slot.gt(slot).or(column_slot.eq(slot).and(column_id.gt(id))))
+            .order((column_slot.asc(), column_id.asc()))
+            .limit(limit)
+            .load::<BridgeTxRow>(conn)?;
+
+        Ok(rows)
+    }
+
+    /// Move the message `signature` to `to`, recording the transition. Illegal moves are rejected.
+    pub fn transition(&self, signature: &str, to: BridgeLifecycle) -> Result<BridgeTxRow, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        conn.transaction::<_, NodeError, _>(|conn| {
+            let row = lock_row(conn, signature)?;
+            transition_row(conn, &row, to)
+        })
+    }
+
+    /// The transitions of the message `signature`, oldest first.
+    pub fn transitions(&self, signature: &str) -> Result<Vec<BridgeTransitionRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let rows = table_bridge_transition
+            .filter(transition_signature.eq(signature))
+            .order(transition_id.asc())
+            .load::<BridgeTransitionRow>(conn)?;
+
+        Ok(rows)
+    }
+
+    /// Messages per lifecycle status.
+    pub fn count_by_status(&self) -> Result<Vec<(BridgeLifecycle, i64)>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let counts = table_bridge_transaction
+            .group_by(column_status)
+            .select((column_status, count_star()))
+            .load::<(BridgeLifecycle, i64)>(conn)?;
+
+        Ok(counts)
+    }
+
+    /// The withdrawal `signature` was claimed with `l1_signature`.
+    pub fn mark_claimed(&self, signature: &str, l1_signature: &str) -> Result<BridgeTxRow, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        conn.transaction::<_, NodeError, _>(|conn| {
+            let row = lock_row(conn, signature)?;
+            claim_row(conn, &row)?;
+
+            let updated_row = diesel::update(table_bridge_transaction.filter(column_signature.eq(signature)))
+                .set((
+                    column_l1_signature.eq(l1_signature),
+                    column_claim_status.eq(ClaimStatus::Claimed.as_str()),
+                ))
+                .get_result::<BridgeTxRow>(conn)?;
+
+            Ok(updated_row)
+        })
+    }
+
+    /// Proven withdrawals in `claim_status` after the `(slot, id)` cursor, in leaf order.
+    pub fn by_claim_status(&self, claim_status: ClaimStatus, cursor: (i64, i64), limit: i64) -> Result<Vec<BridgeTxRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let (slot, id) = cursor;
+        let rows = table_bridge_transaction
+            .filter(column_is_generated_proof.eq(true))
+            .filter(column_claim_status.eq(claim_status.as_str()))
+            .filter(column_slot.gt(slot).or(column_slot.eq(slot).and(column_id.gt(id))))
+            .order((column_slot.asc(), column_id.asc()))
+            .limit(limit)
+            .load::<BridgeTxRow>(conn)?;
+
+        Ok(rows)
+    }
+
+    pub fn count_by_claim_status(&self, claim_status: ClaimStatus) -> Result<i64, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let count = table_bridge_transaction
+            .filter(column_is_generated_proof.eq(true))
+            .filter(column_claim_status.eq(claim_status.as_str()))
+            .count()
+            .get_result::<i64>(conn)?;
+
+        Ok(count)
+    }
+
+    /// Record the claim observed on the claimed marker account of the withdrawal `signature`.
+    pub fn record_claim(&self, signature: &str, marker: &ClaimMarker) -> Result<BridgeTxRow, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let claim_status = if marker.is_double_claimed() { ClaimStatus::DoubleClaimed } else { ClaimStatus::Claimed };
+        conn.transaction::<_, NodeError, _>(|conn| {
+            let row = lock_row(conn, signature)?;
+            claim_row(conn, &row)?;
+
+            let updated_row = diesel::update(table_bridge_transaction.filter(column_signature.eq(signature)))
+                .set((
+                    column_l1_signature.eq(&marker.signature),
+                    column_claim_status.eq(claim_status.as_str()),
+                    column_claim_slot.eq(marker.slot as i64),
+                    column_claim_attempts.eq(marker.transactions as i32),
+                ))
+                .get_result::<BridgeTxRow>(conn)?;
+
+            Ok(updated_row)
+        })
+    }
+
+    /// Persist the proofs against the root of one L1 commitment along with the commitment itself.
+    ///
+    /// Either every message of the commitment is proven and the commitment recorded, or nothing is,
+    /// so the latest commitment tells where a restart resumes. A commitment recorded before keeps
+    /// its first commitment time, and the messages take theirs from it.
+    pub fn commit_proofs(&self, commitment: &RootCommitmentRecord, records: &[BridgeTxRecord]) -> Result<RootCommitmentRow, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        conn.transaction::<_, NodeError, _>(|conn| {
+            diesel::insert_into(table_root_commitment)
+                .values(commitment)
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+            let commitment_row = table_root_commitment
+                .filter(commitment_slot.eq(commitment.slot))
+                .first::<RootCommitmentRow>(conn)?;
+
+            let mut proof_events = vec![];
+            for record in records {
+                let row = lock_row(conn, &record.signature)?;
+                // rows kept through an invalidation come back in the regenerated window, leave them as they are
+                if !row.status.can_transition(BridgeLifecycle::Committed) {
+                    error!("bridge tx commit skipped. signature: {}, status: {:?}", row.signature, row.status);
+                    continue;
+                }
+
+                let mut record = record.clone();
+                record.l1_commit_slot = commitment_row.l1_slot;
+                record.committed_on = Some(commitment_row.committed_on);
+                record.claimable_on = Some(commitment_row.claimable_on);
+                diesel::update(table_bridge_transaction.filter(column_signature.eq(&record.signature)))
+                    .set((&record, column_finalized.eq(commitment_row.finalized)))
+                    .execute(conn)
+                    .map_err(|e| {
+                        error!("Error updating bridge tx: {:?}", e);
+                        NodeError::new(generate_uuid(), format!("Error updating bridge tx: {:?}", e))
+                    })?;
+
+                let row = transition_row(conn, &row, BridgeLifecycle::Committed)?;
+                transition_row(conn, &row, BridgeLifecycle::Proven)?;
+
+                proof_events.push(ProofEventRecord::from(&record));
+            }
+
+            if !proof_events.is_empty() {
+                diesel::insert_into(table_proof_event)
+                    .values(&proof_events)
+                    .execute(conn)?;
+            }
+
+            Ok(commitment_row)
+        })
+    }
+
+    /// Promote the commitment of `slot` and the proofs against its root to final.
+    pub fn finalize_root(&self, slot: i64) -> Result<usize, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        conn.transaction::<_, NodeError, _>(|conn| {
+            diesel::update(table_root_commitment.filter(commitment_slot.eq(slot)))
+                .set(commitment_finalized.eq(true))
+                .execute(conn)?;
+
+            let count = diesel::update(table_bridge_transaction
+                .filter(column_is_generated_proof.eq(true))
+                .filter(column_root_program_slot.eq(slot)))
+                .set(column_finalized.eq(true))
+                .execute(conn)?;
+
+            Ok(count)
+        })
+    }
+
+    /// Void the proofs against the roots of `slot` and later, which L1 no longer holds.
+    ///
+    /// The messages go back to `Detected` through `Orphaned`, their queued or failed claims are
+    /// dropped, the finalizer cursor moves back before them and the commitments of the roots are
+    /// removed. Claimed messages and those with a claim in flight are left as they are and returned.
+    pub fn invalidate_from(&self, slot: i64) -> Result<(Vec<BridgeTxRow>, Vec<BridgeTxRow>), NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        conn.transaction::<_, NodeError, _>(|conn| {
+            let rows = table_bridge_transaction
+                .filter(column_is_generated_proof.eq(true))
+                .filter(column_root_program_slot.ge(slot))
+                .order((column_slot.asc(), column_id.asc()))
+                .for_update()
+                .load::<BridgeTxRow>(conn)?;
+
+            // a submitted claim settles against the proof it was sent with, the finalizer confirms it
+            let in_flight = table_claim_submission
+                .filter(claim_signature.eq_any(rows.iter().map(|row| row.signature.clone()).collect::<Vec<String>>()))
+                .filter(claim_status.eq(ClaimSubmissionStatus::Submitted.as_str()))
+                .select(claim_signature)
+                .load::<String>(conn)?;
+
+            let mut invalidated = vec![];
+            let mut kept = vec![];
+            for row in rows {
+                if in_flight.contains(&row.signature) || !row.status.can_transition(BridgeLifecycle::Orphaned) {
+                    kept.push(row);
+                    continue;
+                }
+                let row = transition_row(conn, &row, BridgeLifecycle::Orphaned)?;
+                transition_row(conn, &row, BridgeLifecycle::Detected)?;
+
+                let updated_row = diesel::update(table_bridge_transaction.filter(column_signature.eq(&row.signature)))
+                    .set((
+                        column_proof.eq(""),
+                        column_current_mt_root.eq(Vec::<u8>::new()),
+                        column_root_program_slot.eq(0),
+                        column_proven_on.eq(None::<chrono::NaiveDateTime>),
+                        column_l1_commit_slot.eq(0),
+                        column_committed_on.eq(None::<chrono::NaiveDateTime>),
+                        column_claimable_on.eq(None::<chrono::NaiveDateTime>),
+                        column_finalized.eq(false),
+                    ))
+                    .get_result::<BridgeTxRow>(conn)?;
+
+                // queued again once the regenerated proof is claimable
+                diesel::delete(table_claim_submission
+                    .filter(claim_signature.eq(&row.signature))
+                    .filter(claim_status.eq_any([ClaimSubmissionStatus::Queued.as_str(), ClaimSubmissionStatus::Failed.as_str()])))
+                    .execute(conn)?;
+
+                invalidated.push(updated_row);
+            }
+
+            // the rows are in slot order, the finalizer looks at the regenerated ones again from the first
+            if let Some(first) = invalidated.first() {
+                diesel::update(table_scan_checkpoint
+                    .filter(checkpoint_name.eq(FINALIZER_CURSOR))
+                    .filter(checkpoint_slot.ge(first.slot)))
+                    .set(checkpoint_slot.eq(first.slot - 1))
+                    .execute(conn)?;
+            }
+
+            diesel::delete(table_root_commitment.filter(commitment_slot.ge(slot))).execute(conn)?;
+
+            Ok((invalidated, kept))
+        })
+    }
+}
+
+// the trees are built from the messages of `CommitmentWindow`s, leaves are counted the same way
+fn leaf_at(leaf_index: i64) -> BoxedBridgeTxQuery {
+    table_bridge_transaction
+        .filter(CommitmentWindow::all().filter())
+        .order((column_slot.asc(), column_id.asc()))
+        .offset(leaf_index)
+        .into_boxed()
+}
+
+fn leaves_before(row: &BridgeTxRow) -> Result<BoxedBridgeTxQuery, NodeError> {
+    let window = CommitmentWindow::through(row.slot);
+    if !window.contains(row.slot) {
+        return Err(NodeError::new(generate_uuid(), format!("bridge tx of slot {} is not a leaf. signature: {}", row.slot, row.signature)));
+    }
+
+    Ok(table_bridge_transaction
+        .filter(window.filter())
+        .filter(column_slot.lt(row.slot).or(column_slot.eq(row.slot).and(column_id.lt(row.id))))
+        .into_boxed())
+}
+
+fn lock_row(conn: &mut PgConnection, signature: &str) -> Result<BridgeTxRow, NodeError> {
+    let row = table_bridge_transaction
+        .filter(column_signature.eq(signature))
+        .for_update()
+        .first::<BridgeTxRow>(conn)
+        .optional()?;
+
+    row.ok_or_else(|| NodeError::new(generate_uuid(), format!("bridge tx not found. signature: {}", signature)))
+}
+
+fn transition_row(conn: &mut PgConnection, row: &BridgeTxRow, to: BridgeLifecycle) -> Result<BridgeTxRow, NodeError> {
+    let status = row.status.transition(to)?;
+
+    let updated_row = diesel::update(table_bridge_transaction.filter(column_signature.eq(&row.signature)))
+        .set((column_status.eq(status), column_is_generated_proof.eq(status.has_proof())))
+        .get_result::<BridgeTxRow>(conn)?;
+
+    diesel::insert_into(table_bridge_transition)
+        .values(&BridgeTransitionRecord {
+            signature: row.signature.clone(),
+            from_status: row.status,
+            to_status: status,
+        })
+        .execute(conn)?;
+
+    Ok(updated_row)
+}
+
+// a claim seen on L1 also shows the challenge window passed
+fn claim_row(conn: &mut PgConnection, row: &BridgeTxRow) -> Result<(), NodeError> {
+    let mut row = row.clone();
+    if row.status == BridgeLifecycle::Proven {
+        row = transition_row(conn, &row, BridgeLifecycle::ChallengeWindowElapsed)?;
+    }
+    if row.status != BridgeLifecycle::Claimed {
+        transition_row(conn, &row, BridgeLifecycle::Claimed)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod test {
+    use diesel::pg::Pg;
+
+    use crate::models::bridge_transaction_model::{BridgeLifecycle, BridgeTxRow};
+    use crate::repositories::bridge_tx_repo::{leaf_at, leaves_before};
+
+    fn prepare_row(id: i64, slot: i64) -> BridgeTxRow {
+        BridgeTxRow {
+            id,
+            slot,
+            signature: format!("sig{}", id),
+            tx_info_hash: vec![id as u8; 32],
+            proof: String::new(),
+            is_generated_proof: false,
+            current_mt_root: vec![],
+            root_program_slot: 0,
+            sender: "sender".to_string(),
+            recipient: "recipient".to_string(),
+            amount: 5,
+            bridge_tx_index: 0,
+            status: BridgeLifecycle::Detected,
+            l1_signature: String::new(),
+            proven_on: None,
+            claim_status: "unclaimed".to_string(),
+            claim_slot: 0,
+            claim_attempts: 0,
+            l1_commit_slot: 0,
+            committed_on: None,
+            claimable_on: None,
+            finalized: false,
+            created_on: chrono::NaiveDateTime::default(),
+            updated_on: chrono::NaiveDateTime::default(),
+        }
+    }
+
+    // slot 0 carries no leaf, the offsets and counts skip it like the trees do
+    #[test]
+    fn test_leaf_queries() {
+        let sql = diesel::debug_query::<Pg, _>(&leaf_at(3)).to_string();
+        assert!(sql.contains("\"bridge_transaction\".\"slot\" BETWEEN $1 AND $2"), "{}", sql);
+        assert!(sql.ends_with(&format!("-- binds: [1, {}, 3]", i64::MAX)), "{}", sql);
+
+        let sql = diesel::debug_query::<Pg, _>(&leaves_before(&prepare_row(7, 5)).unwrap()).to_string();
+        assert!(sql.contains("\"bridge_transaction\".\"slot\" BETWEEN $1 AND $2"), "{}", sql);
+        assert!(sql.ends_with("-- binds: [1, 5, 5, 5, 7]"), "{}", sql);
+
+        assert!(leaves_before(&prepare_row(1, 0)).is_err());
+    }
+}
@@ -0,0 +1,29 @@
+use crate::common::node_error::NodeError;
+use crate::entities::brief_reclaim_entity::table_brief_reclaim::dsl::table_brief_reclaim;
+use crate::models::brief_reclaim_model::{BriefReclaimRecord, BriefReclaimRow};
+use crate::utils::store_util::{PgConnectionPool, PooledPgConnection};
+use crate::utils::uuid_util::generate_uuid;
+use diesel::prelude::*;
+use diesel::RunQueryDsl;
+use log::error;
+
+pub struct BriefReclaimRepo {
+    pub pool: Box<PgConnectionPool>,
+}
+
+impl BriefReclaimRepo {
+    pub fn insert(&self, record: BriefReclaimRecord) -> Result<Vec<BriefReclaimRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let rows = diesel::insert_into(table_brief_reclaim)
+            .values(&record)
+            .on_conflict_do_nothing()
+            .get_results::<BriefReclaimRow>(conn)
+            .map_err(|e| {
+                error!("Error insert brief reclaim: {:?}", e);
+                NodeError::new(generate_uuid(), format!("Error insert brief reclaim: {:?}", e))
+            })?;
+
+        Ok(rows)
+    }
+}
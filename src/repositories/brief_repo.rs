@@ -1,7 +1,10 @@
 use crate::common::node_error::NodeError;
-use crate::entities::brief_entity::table_brief::column_slot;
+use crate::entities::brief_entity::table_brief::{column_challenged, column_slot};
 use crate::entities::brief_entity::table_brief::dsl::table_brief;
+use crate::entities::brief_reclaim_entity::table_brief_reclaim;
+use crate::entities::challenge_entity::table_challenge;
 use crate::models::brief_model::{BriefRecord, BriefRow};
+use crate::models::challenge_model::ChallengeStatus;
 use crate::utils::store_util::{PgConnectionPool, PooledPgConnection};
 use crate::utils::uuid_util::generate_uuid;
 use diesel::prelude::*;
@@ -36,6 +39,28 @@ impl BriefRepo {
         Ok(row)
     }
 
+    pub fn find_by_slot(&self, slot: i64) -> Result<BriefRow, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let results = table_brief
+            .filter(column_slot.eq(slot))
+            .limit(1)
+            .load::<BriefRow>(conn)
+            .expect("Error loading brief");
+
+        if results.is_empty() {
+            return Err(
+                NodeError::new(generate_uuid(),
+                               format!("Couldn't find brief of slot {} from database", slot),
+                )
+            );
+        }
+
+        let row = results[0].clone();
+
+        Ok(row)
+    }
+
     pub fn insert(&self, records: Vec<BriefRecord>) -> Result<Vec<BriefRow>, NodeError> {
         let conn: &mut PooledPgConnection = &mut self.pool.get()?;
 
@@ -51,4 +76,42 @@ impl BriefRepo {
 
         Ok(rows)
     }
+
+    /// Briefs up to `to_slot` whose accounts were not closed yet and that are not challenged,
+    /// locally or on chain, oldest first.
+    pub fn unreclaimed(&self, to_slot: i64, limit: i64) -> Result<Vec<BriefRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let reclaimed = table_brief_reclaim::table.select(table_brief_reclaim::column_slot);
+        let challenged = table_challenge::table
+            .filter(table_challenge::column_status.eq_any([ChallengeStatus::Pending.as_str(), ChallengeStatus::Submitted.as_str()]))
+            .select(table_challenge::column_slot);
+        let rows = table_brief
+            .filter(column_slot.le(to_slot))
+            .filter(column_challenged.eq(false))
+            .filter(diesel::dsl::not(column_slot.eq_any(reclaimed)))
+            .filter(diesel::dsl::not(column_slot.eq_any(challenged)))
+            .order(column_slot.asc())
+            .limit(limit)
+            .load::<BriefRow>(conn)
+            .map_err(|e| {
+                NodeError::new(generate_uuid(), format!("Error loading unreclaimed brief: {:?}", e))
+            })?;
+
+        Ok(rows)
+    }
+
+    /// Keep the brief of `slot` for good, a challenge account exists for it on chain.
+    pub fn mark_challenged(&self, slot: i64) -> Result<usize, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let count = diesel::update(table_brief.filter(column_slot.eq(slot)))
+            .set(column_challenged.eq(true))
+            .execute(conn)
+            .map_err(|e| {
+                NodeError::new(generate_uuid(), format!("Error marking brief challenged: {:?}", e))
+            })?;
+
+        Ok(count)
+    }
 }
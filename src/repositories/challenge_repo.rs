@@ -0,0 +1,66 @@
+use crate::common::node_error::NodeError;
+use crate::entities::challenge_entity::table_challenge::{column_kind, column_slot, column_status};
+use crate::entities::challenge_entity::table_challenge::dsl::table_challenge;
+use crate::models::challenge_model::{ChallengeRecord, ChallengeRow, ChallengeStatus};
+use crate::utils::store_util::{PgConnectionPool, PooledPgConnection};
+use crate::utils::uuid_util::generate_uuid;
+use diesel::prelude::*;
+use diesel::RunQueryDsl;
+use log::error;
+
+pub struct ChallengeRepo {
+    pub pool: Box<PgConnectionPool>,
+}
+
+impl ChallengeRepo {
+    pub fn insert(&self, record: ChallengeRecord) -> Result<Vec<ChallengeRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let rows = diesel::insert_into(table_challenge)
+            .values(&record)
+            .on_conflict_do_nothing()
+            .get_results::<ChallengeRow>(conn)
+            .map_err(|e| {
+                error!("Error insert challenge: {:?}", e);
+                NodeError::new(generate_uuid(), format!("Error insert challenge: {:?}", e))
+            })?;
+
+        Ok(rows)
+    }
+
+    pub fn update(&self, record: ChallengeRecord) -> Result<ChallengeRow, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let updated_row = diesel::update(table_challenge
+            .filter(column_slot.eq(record.slot))
+            .filter(column_kind.eq(&record.kind)))
+            .set(&record)
+            .get_result::<ChallengeRow>(conn)
+            .map_err(|e| {
+                error!("Error updating challenge: {:?}", e);
+                NodeError::new(generate_uuid(), format!("Error updating challenge: {:?}", e))
+            })?;
+
+        Ok(updated_row)
+    }
+
+    pub fn open(&self) -> Result<Vec<ChallengeRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let open_status = vec![
+            ChallengeStatus::Pending.as_str(),
+            ChallengeStatus::Submitted.as_str(),
+        ];
+
+        let rows = table_challenge
+            .filter(column_status.eq_any(open_status))
+            .order((column_slot.asc(), column_kind.asc()))
+            .load::<ChallengeRow>(conn)
+            .map_err(|e| {
+                error!("Error loading challenge: {:?}", e);
+                NodeError::new(generate_uuid(), format!("Error loading challenge: {:?}", e))
+            })?;
+
+        Ok(rows)
+    }
+}
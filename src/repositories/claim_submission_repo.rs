@@ -0,0 +1,93 @@
+use crate::common::node_error::NodeError;
+use crate::entities::claim_submission_entity::table_claim_submission::{column_attempts, column_l1_signature, column_last_error, column_leaf_index, column_next_attempt_on, column_priority_fee, column_signature, column_status, column_submitted_on};
+use crate::entities::claim_submission_entity::table_claim_submission::dsl::table_claim_submission;
+use crate::models::claim_submission_model::{ClaimSubmissionRecord, ClaimSubmissionRow, ClaimSubmissionStatus};
+use crate::utils::store_util::{PgConnectionPool, PooledPgConnection};
+use crate::utils::uuid_util::generate_uuid;
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use diesel::RunQueryDsl;
+use log::error;
+
+pub struct ClaimSubmissionRepo {
+    pub pool: Box<PgConnectionPool>,
+}
+
+impl ClaimSubmissionRepo {
+    /// Enqueue claims. A leaf index queued or failed before, e.g. proven against a root rewritten
+    /// since, is re-armed with the record; a submitted or confirmed one is left as it is.
+    pub fn insert(&self, records: Vec<ClaimSubmissionRecord>) -> Result<Vec<ClaimSubmissionRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let rows = diesel::insert_into(table_claim_submission)
+            .values(&records)
+            .on_conflict(column_leaf_index)
+            .do_update()
+            .set((
+                column_signature.eq(excluded(column_signature)),
+                column_status.eq(excluded(column_status)),
+                column_attempts.eq(excluded(column_attempts)),
+                column_priority_fee.eq(excluded(column_priority_fee)),
+                column_l1_signature.eq(excluded(column_l1_signature)),
+                column_last_error.eq(excluded(column_last_error)),
+                column_next_attempt_on.eq(excluded(column_next_attempt_on)),
+                column_submitted_on.eq(excluded(column_submitted_on)),
+            ))
+            .filter(column_status.ne_all([ClaimSubmissionStatus::Submitted.as_str(), ClaimSubmissionStatus::Confirmed.as_str()]))
+            .get_results::<ClaimSubmissionRow>(conn)
+            .map_err(|e| {
+                error!("Error insert claim submission: {:?}", e);
+                NodeError::new(generate_uuid(), format!("Error insert claim submission: {:?}", e))
+            })?;
+
+        Ok(rows)
+    }
+
+    pub fn update(&self, record: ClaimSubmissionRecord) -> Result<ClaimSubmissionRow, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let updated_row = diesel::update(table_claim_submission.filter(column_leaf_index.eq(record.leaf_index)))
+            .set(&record)
+            .get_result::<ClaimSubmissionRow>(conn)
+            .map_err(|e| {
+                error!("Error updating claim submission: {:?}", e);
+                NodeError::new(generate_uuid(), format!("Error updating claim submission: {:?}", e))
+            })?;
+
+        Ok(updated_row)
+    }
+
+    /// Queue the failed claims due at `now` again with their attempts reset.
+    pub fn requeue_failed(&self, now: chrono::NaiveDateTime) -> Result<usize, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let count = diesel::update(table_claim_submission
+            .filter(column_status.eq(ClaimSubmissionStatus::Failed.as_str()))
+            .filter(column_next_attempt_on.le(now)))
+            .set((
+                column_status.eq(ClaimSubmissionStatus::Queued.as_str()),
+                column_attempts.eq(0),
+            ))
+            .execute(conn)
+            .map_err(|e| {
+                error!("Error requeue failed claim submission: {:?}", e);
+                NodeError::new(generate_uuid(), format!("Error requeue failed claim submission: {:?}", e))
+            })?;
+
+        Ok(count)
+    }
+
+    /// Submitted claims and queued claims due at `now`, lowest leaf index first.
+    pub fn due(&self, now: chrono::NaiveDateTime, limit: i64) -> Result<Vec<ClaimSubmissionRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let rows = table_claim_submission
+            .filter(column_status.eq(ClaimSubmissionStatus::Submitted.as_str())
+                .or(column_status.eq(ClaimSubmissionStatus::Queued.as_str()).and(column_next_attempt_on.le(now))))
+            .order(column_leaf_index.asc())
+            .limit(limit)
+            .load::<ClaimSubmissionRow>(conn)?;
+
+        Ok(rows)
+    }
+}
@@ -4,3 +4,10 @@ pub mod brief_repo;
 pub mod transaction_repo;
 pub mod block_repo;
 pub mod bridge_tx_repo;
+pub mod challenge_repo;
+pub mod brief_reclaim_repo;
+pub mod proof_event_repo;
+pub mod claim_submission_repo;
+pub mod root_commitment_repo;
+pub mod root_observation_repo;
+pub mod scan_checkpoint_repo;
@@ -0,0 +1,37 @@
+use crate::common::node_error::NodeError;
+use crate::entities::proof_event_entity::table_proof_event::column_id;
+use crate::entities::proof_event_entity::table_proof_event::dsl::table_proof_event;
+use crate::models::proof_event_model::ProofEventRow;
+use crate::utils::store_util::{PgConnectionPool, PooledPgConnection};
+use diesel::dsl::max;
+use diesel::prelude::*;
+use diesel::RunQueryDsl;
+
+pub struct ProofEventRepo {
+    pub pool: Box<PgConnectionPool>,
+}
+
+impl ProofEventRepo {
+    /// Events after `event_id`, oldest first.
+    pub fn after(&self, event_id: i64, limit: i64) -> Result<Vec<ProofEventRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let rows = table_proof_event
+            .filter(column_id.gt(event_id))
+            .order(column_id.asc())
+            .limit(limit)
+            .load::<ProofEventRow>(conn)?;
+
+        Ok(rows)
+    }
+
+    pub fn last_id(&self) -> Result<i64, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let id = table_proof_event
+            .select(max(column_id))
+            .first::<Option<i64>>(conn)?;
+
+        Ok(id.unwrap_or(0))
+    }
+}
@@ -0,0 +1,89 @@
+use crate::common::node_error::NodeError;
+use crate::entities::root_commitment_entity::table_root_commitment::{column_finalized, column_l1_slot, column_slot};
+use crate::entities::root_commitment_entity::table_root_commitment::dsl::table_root_commitment;
+use crate::models::root_commitment_model::RootCommitmentRow;
+use crate::utils::store_util::{PgConnectionPool, PooledPgConnection};
+use diesel::prelude::*;
+use diesel::RunQueryDsl;
+
+pub struct RootCommitmentRepo {
+    pub pool: Box<PgConnectionPool>,
+}
+
+impl RootCommitmentRepo {
+    pub fn find_by_slot(&self, slot: i64) -> Result<Option<RootCommitmentRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let row = table_root_commitment
+            .filter(column_slot.eq(slot))
+            .first::<RootCommitmentRow>(conn)
+            .optional()?;
+
+        Ok(row)
+    }
+
+    /// The latest commitment made at or before L1 slot `l1_slot`. Commitments whose L1 slot is
+    /// unknown, recorded as 0, are left out.
+    pub fn latest_at_l1_slot(&self, l1_slot: i64) -> Result<Option<RootCommitmentRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let row = table_root_commitment
+            .filter(column_l1_slot.gt(0))
+            .filter(column_l1_slot.le(l1_slot))
+            .order(column_slot.desc())
+            .first::<RootCommitmentRow>(conn)
+            .optional()?;
+
+        Ok(row)
+    }
+
+    /// The `limit` commitments of the highest slots, highest first.
+    pub fn latest(&self, limit: i64) -> Result<Vec<RootCommitmentRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let rows = table_root_commitment
+            .order(column_slot.desc())
+            .limit(limit)
+            .load::<RootCommitmentRow>(conn)?;
+
+        Ok(rows)
+    }
+
+    /// The commitments of `slot` and later, highest slot first.
+    pub fn since(&self, slot: i64) -> Result<Vec<RootCommitmentRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let rows = table_root_commitment
+            .filter(column_slot.ge(slot))
+            .order(column_slot.desc())
+            .load::<RootCommitmentRow>(conn)?;
+
+        Ok(rows)
+    }
+
+    /// The commitment of the highest slot below `slot`.
+    pub fn last_before(&self, slot: i64) -> Result<Option<RootCommitmentRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let row = table_root_commitment
+            .filter(column_slot.lt(slot))
+            .order(column_slot.desc())
+            .first::<RootCommitmentRow>(conn)
+            .optional()?;
+
+        Ok(row)
+    }
+
+    /// The commitments whose root is not finalized yet, lowest slot first.
+    pub fn provisional(&self, limit: i64) -> Result<Vec<RootCommitmentRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let rows = table_root_commitment
+            .filter(column_finalized.eq(false))
+            .order(column_slot.asc())
+            .limit(limit)
+            .load::<RootCommitmentRow>(conn)?;
+
+        Ok(rows)
+    }
+}
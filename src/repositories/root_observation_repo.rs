@@ -0,0 +1,45 @@
+use crate::common::node_error::NodeError;
+use crate::entities::root_observation_entity::table_root_observation::{column_id, column_last_seen_on, column_merkle_tree_root, column_slot};
+use crate::entities::root_observation_entity::table_root_observation::dsl::table_root_observation;
+use crate::models::root_observation_model::{RootObservationRecord, RootObservationRow};
+use crate::utils::store_util::{PgConnectionPool, PooledPgConnection};
+use crate::utils::uuid_util::generate_uuid;
+use diesel::prelude::*;
+use diesel::RunQueryDsl;
+use log::error;
+
+pub struct RootObservationRepo {
+    pub pool: Box<PgConnectionPool>,
+}
+
+impl RootObservationRepo {
+    /// Record that `record` was read from L1 now.
+    pub fn observe(&self, record: RootObservationRecord) -> Result<RootObservationRow, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let row = diesel::insert_into(table_root_observation)
+            .values(&record)
+            .on_conflict((column_slot, column_merkle_tree_root))
+            .do_update()
+            .set(column_last_seen_on.eq(chrono::Utc::now().naive_utc()))
+            .get_result::<RootObservationRow>(conn)
+            .map_err(|e| {
+                error!("Error insert root observation: {:?}", e);
+                NodeError::new(generate_uuid(), format!("Error insert root observation: {:?}", e))
+            })?;
+
+        Ok(row)
+    }
+
+    /// Every root observed for `slot`, first observed first.
+    pub fn by_slot(&self, slot: i64) -> Result<Vec<RootObservationRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let rows = table_root_observation
+            .filter(column_slot.eq(slot))
+            .order(column_id.asc())
+            .load::<RootObservationRow>(conn)?;
+
+        Ok(rows)
+    }
+}
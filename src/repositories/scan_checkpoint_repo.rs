@@ -0,0 +1,48 @@
+use crate::common::node_error::NodeError;
+use crate::entities::scan_checkpoint_entity::table_scan_checkpoint::column_name;
+use crate::entities::scan_checkpoint_entity::table_scan_checkpoint::dsl::table_scan_checkpoint;
+use crate::models::scan_checkpoint_model::ScanCheckpointRow;
+use crate::utils::store_util::{PgConnectionPool, PooledPgConnection};
+use crate::utils::uuid_util::generate_uuid;
+use diesel::prelude::*;
+use diesel::RunQueryDsl;
+use log::error;
+
+pub struct ScanCheckpointRepo {
+    pub pool: Box<PgConnectionPool>,
+}
+
+impl ScanCheckpointRepo {
+    pub fn upsert(&self, name: &str, slot: i64) -> Result<ScanCheckpointRow, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let row = ScanCheckpointRow {
+            name: name.to_string(),
+            slot,
+            updated_on: chrono::Utc::now().naive_utc(),
+        };
+        let row = diesel::insert_into(table_scan_checkpoint)
+            .values(&row)
+            .on_conflict(column_name)
+            .do_update()
+            .set(&row)
+            .get_result::<ScanCheckpointRow>(conn)
+            .map_err(|e| {
+                error!("Error upsert scan checkpoint: {:?}", e);
+                NodeError::new(generate_uuid(), format!("Error upsert scan checkpoint: {:?}", e))
+            })?;
+
+        Ok(row)
+    }
+
+    pub fn find(&self, name: &str) -> Result<Option<ScanCheckpointRow>, NodeError> {
+        let conn: &mut PooledPgConnection = &mut self.pool.get()?;
+
+        let row = table_scan_checkpoint
+            .filter(column_name.eq(name))
+            .first::<ScanCheckpointRow>(conn)
+            .optional()?;
+
+        Ok(row)
+    }
+}
@@ -11,6 +11,12 @@ pub const STATE_PDA_SEED: &[u8] = b"fraud_proof_state";
 
 pub const ROOTS_PDA_SEED: &[u8] = b"roots";
 
+pub const CHALLENGE_PDA_SEED: &[u8] = b"fraud_proof_challenge";
+
+pub const CLAIMED_PDA_SEED: &[u8] = b"claimed";
+
+pub const VAULT_PDA_SEED: &[u8] = b"vault";
+
 
 pub struct ChainBasicService<'a> {
     pub rpc_client: &'a RpcClient,
@@ -54,4 +60,18 @@ impl ChainBasicService<'_> {
         let slot_bytes = wrap_slot.slot.to_le_bytes();
         return Pubkey::find_program_address(&[ROOTS_PDA_SEED, slot_bytes.as_ref()], program_id);
     }
+
+    pub fn find_challenge_account_address(program_id: &Pubkey, wrap_slot: WrapSlot) -> (Pubkey, u8) {
+        let slot_bytes = wrap_slot.slot.to_le_bytes();
+        return Pubkey::find_program_address(&[CHALLENGE_PDA_SEED, slot_bytes.as_ref()], program_id);
+    }
+
+    // marks a withdrawal as claimed on the L1 bridge program, one per leaf
+    pub fn find_claimed_account_address(program_id: &Pubkey, leaf_hash: &[u8; 32]) -> (Pubkey, u8) {
+        return Pubkey::find_program_address(&[CLAIMED_PDA_SEED, leaf_hash.as_ref()], program_id);
+    }
+
+    pub fn find_vault_account_address(program_id: &Pubkey) -> (Pubkey, u8) {
+        return Pubkey::find_program_address(&[VAULT_PDA_SEED], program_id);
+    }
 }
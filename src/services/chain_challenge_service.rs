@@ -0,0 +1,120 @@
+use log::{error, info};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
+
+use crate::common::node_error::NodeError;
+use crate::contract::chain_challenge::ChainChallenge;
+use crate::contract::wrap_slot::WrapSlot;
+use crate::services::chain_basic_service::ChainBasicService;
+use crate::utils::uuid_util::generate_uuid;
+
+const SUBMIT_CHALLENGE_DISCRIMINANT: [u8; 8] = [138, 65, 75, 102, 164, 142, 10, 202];
+
+
+/// The outcome of submitting the challenge of a slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChallengeSubmission {
+    Sent(Signature),
+    // the challenge account of the slot is already on chain, a challenge of it landed before
+    Exists,
+}
+
+pub struct ChainChallengeService<'a> {
+    pub rpc_client: &'a RpcClient,
+    pub program_id: &'a Pubkey,
+    pub payer: &'a Keypair,
+}
+
+impl ChainChallengeService<'_> {
+    pub fn submit_challenge(&self, wrap_slot: WrapSlot, chain_challenge: ChainChallenge) -> Option<Signature> {
+        let payer = self.payer;
+        let challenge_account_address: Pubkey = self.find_challenge_account_address(wrap_slot.to_owned());
+        let brief_account_address: Pubkey = self.find_brief_account_address(wrap_slot.to_owned());
+        let state_account_address: Pubkey = self.find_state_account_address();
+        let last_blockhash = self.rpc_client.get_latest_blockhash().ok()?;
+
+        let challenge_account: AccountMeta = AccountMeta::new(challenge_account_address, false);
+        let brief_account: AccountMeta = AccountMeta::new_readonly(brief_account_address, false);
+        let state_account: AccountMeta = AccountMeta::new(state_account_address, false);
+        let payer_account: AccountMeta = AccountMeta::new_readonly(payer.pubkey(), true);
+        let system_program_account: AccountMeta = AccountMeta::new_readonly(solana_sdk::system_program::ID, false);
+
+        let account_metas: Vec<AccountMeta> = vec![
+            challenge_account.to_owned(),
+            brief_account.to_owned(),
+            state_account.to_owned(),
+            payer_account.to_owned(),
+            system_program_account.to_owned(),
+        ];
+
+        let ix = Instruction::new_with_borsh(
+            *self.program_id,
+            &(SUBMIT_CHALLENGE_DISCRIMINANT, chain_challenge.clone()),
+            account_metas.to_owned(),
+        );
+        let message = Message::new(&[ix], Some(&payer.pubkey()));
+        let mut tx = Transaction::new_unsigned(message);
+        tx.sign(&[&payer], last_blockhash);
+
+        match self.rpc_client.send_transaction(&tx) {
+            Ok(signature) => {
+                info!("submit challenge success. slot: {:?}, signature: {:?}", wrap_slot, signature);
+                Some(signature)
+            }
+            Err(err) => {
+                error!("submit challenge fail. slot: {:?}, err: {:?}", wrap_slot, err);
+                None
+            }
+        }
+    }
+
+    // Some(true): the challenge transaction landed successfully
+    // Some(false): the challenge transaction was rejected by the program
+    // None: the transaction is not yet known to the cluster
+    pub fn get_challenge_status(&self, signature: &Signature) -> Option<bool> {
+        match self.rpc_client.get_signature_status(signature) {
+            Ok(Some(result)) => {
+                if let Err(err) = &result {
+                    error!("challenge transaction fail. signature: {:?}, err: {:?}", signature, err);
+                }
+                Some(result.is_ok())
+            }
+            Ok(None) => None,
+            Err(err) => {
+                error!("get challenge status fail. signature: {:?}, err: {:?}", signature, err);
+                None
+            }
+        }
+    }
+
+    /// Whether the challenge account of the slot exists, only a missing account reads as absent.
+    pub fn is_challenge_account_exist(&self, wrap_slot: WrapSlot) -> Result<bool, NodeError> {
+        let challenge_account_address = self.find_challenge_account_address(wrap_slot.clone());
+
+        let response = self.rpc_client
+            .get_account_with_commitment(&challenge_account_address, self.rpc_client.commitment())
+            .map_err(|e| {
+                NodeError::new(generate_uuid(), format!("get challenge account fail. slot: {:?}, address: {}, err: {:?}",
+                    wrap_slot, challenge_account_address, e))
+            })?;
+
+        Ok(response.value.is_some())
+    }
+
+    pub fn find_state_account_address(&self) -> Pubkey {
+        return ChainBasicService::find_state_account_address(self.program_id).0;
+    }
+
+    pub fn find_brief_account_address(&self, wrap_slot: WrapSlot) -> Pubkey {
+        return ChainBasicService::find_brief_account_address(self.program_id, wrap_slot.to_owned()).0;
+    }
+
+    pub fn find_challenge_account_address(&self, wrap_slot: WrapSlot) -> Pubkey {
+        return ChainBasicService::find_challenge_account_address(self.program_id, wrap_slot.to_owned()).0;
+    }
+}
@@ -0,0 +1,354 @@
+use std::str::FromStr;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+
+use crate::common::node_error::NodeError;
+use crate::contract::chain_claim::ChainClaim;
+use crate::contract::wrap_slot::WrapSlot;
+use crate::models::bridge_transaction_model::BridgeTxRow;
+use crate::services::chain_basic_service::ChainBasicService;
+use crate::utils::uuid_util::generate_uuid;
+
+const CLAIM_DISCRIMINANT: [u8; 8] = [62, 198, 214, 193, 213, 159, 108, 210];
+
+
+/// An unsigned claim transaction, ready for the recipient's wallet to sign and send.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimTransaction {
+    pub signature: String,
+    pub payer: String,
+    pub recipient: String,
+    pub roots_account: String,
+    pub claimed_account: String,
+    pub recent_blockhash: String,
+    // base64 of the bincode serialized unsigned `Transaction`
+    pub transaction: String,
+    // base64 of the serialized `Message`, the bytes a wallet signs
+    pub message: String,
+}
+
+/// The claim of a withdrawal as seen on its claimed marker account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClaimMarker {
+    // the first successful transaction on the marker account, the one that created it
+    pub signature: String,
+    pub slot: u64,
+    // every transaction on the marker account, failed ones included
+    pub transactions: usize,
+    // successful transactions claiming the withdrawal, see `is_claim_of`
+    pub claims: usize,
+}
+
+impl ClaimMarker {
+    /// `statuses` are the transactions on the marker account, newest first as the rpc returns them.
+    ///
+    /// Every successful transaction is counted as a claim, when there are several `claims` has to be
+    /// narrowed down to the ones carrying a claim instruction.
+    pub fn from_statuses(statuses: &[RpcConfirmedTransactionStatusWithSignature]) -> Option<Self> {
+        let claim = statuses.iter().rev().find(|s| s.err.is_none())?;
+
+        Some(ClaimMarker {
+            signature: claim.signature.clone(),
+            slot: claim.slot,
+            transactions: statuses.len(),
+            claims: statuses.iter().filter(|s| s.err.is_none()).count(),
+        })
+    }
+
+    pub fn is_double_claimed(&self) -> bool {
+        self.claims > 1
+    }
+}
+
+/// Whether `tx` carries a claim instruction of the bridge program on `claimed_account`, a transfer to
+/// the marker account touches it without claiming. Only static account keys are looked at, the claim
+/// transactions built here don't use lookup tables.
+pub fn is_claim_of(tx: &VersionedTransaction, bridge_program_id: &Pubkey, claimed_account: &Pubkey) -> bool {
+    let keys = tx.message.static_account_keys();
+
+    tx.message.instructions().iter().any(|ix| {
+        keys.get(ix.program_id_index as usize) == Some(bridge_program_id)
+            && ix.data.starts_with(&CLAIM_DISCRIMINANT)
+            && ix.accounts.first().and_then(|index| keys.get(*index as usize)) == Some(claimed_account)
+    })
+}
+
+/// Build claim instructions for the L1 bridge program.
+pub struct ChainClaimService<'a> {
+    pub bridge_program_id: &'a Pubkey,
+    pub root_mgr_program_id: &'a Pubkey,
+}
+
+impl ChainClaimService<'_> {
+    /// The claim arguments of a proven withdrawal at `leaf_index` of the bridge merkle tree.
+    pub fn claim_args(&self, row: &BridgeTxRow, leaf_index: i64) -> Result<ChainClaim, NodeError> {
+        if !row.is_generated_proof {
+            return Err(NodeError::new(generate_uuid(), format!("withdrawal has no proof yet. signature: {}", row.signature)));
+        }
+
+        let proof = hex::decode(&row.proof).map_err(|e| {
+            NodeError::new(generate_uuid(), format!("invalid proof. signature: {}, err: {:?}", row.signature, e))
+        })?;
+        if proof.len() % 32 != 0 {
+            return Err(NodeError::new(generate_uuid(), format!("invalid proof length. signature: {}", row.signature)));
+        }
+
+        Ok(ChainClaim {
+            root_slot: row.root_program_slot as u64,
+            leaf_index: leaf_index as u64,
+            from: parse_pubkey(&row.sender)?.to_bytes(),
+            to: parse_pubkey(&row.recipient)?.to_bytes(),
+            amount: row.amount as u64,
+            bridge_tx_index: row.bridge_tx_index as u64,
+            // only native transfers are bridged
+            message_type: 0,
+            proof: proof.chunks(32).map(|h| h.try_into().expect("checked length")).collect(),
+        })
+    }
+
+    pub fn claim_instruction(&self, payer: &Pubkey, leaf_hash: &[u8; 32], claim: &ChainClaim) -> Instruction {
+        let claimed_account_address = self.find_claimed_account_address(leaf_hash);
+        let roots_account_address = self.find_roots_account_address(WrapSlot { slot: claim.root_slot });
+        let vault_account_address = self.find_vault_account_address();
+        let recipient = Pubkey::new_from_array(claim.to);
+
+        let account_metas: Vec<AccountMeta> = vec![
+            AccountMeta::new(claimed_account_address, false),
+            AccountMeta::new_readonly(roots_account_address, false),
+            AccountMeta::new(vault_account_address, false),
+            AccountMeta::new(recipient, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+        ];
+
+        Instruction::new_with_borsh(
+            *self.bridge_program_id,
+            &(CLAIM_DISCRIMINANT, claim.clone()),
+            account_metas,
+        )
+    }
+
+    /// An unsigned transaction claiming the proven withdrawal `row`, `payer` defaults to the recipient.
+    pub fn build_claim_transaction(&self, row: &BridgeTxRow, leaf_index: i64, payer: Option<Pubkey>, recent_blockhash: Hash) -> Result<ClaimTransaction, NodeError> {
+        let claim = self.claim_args(row, leaf_index)?;
+        let leaf_hash = leaf_hash(row)?;
+        let payer = payer.unwrap_or(Pubkey::new_from_array(claim.to));
+
+        let ix = self.claim_instruction(&payer, &leaf_hash, &claim);
+        let mut message = Message::new(&[ix], Some(&payer));
+        message.recent_blockhash = recent_blockhash;
+        let tx = Transaction::new_unsigned(message.clone());
+
+        let tx_data = bincode::serialize(&tx).map_err(|e| {
+            NodeError::new(generate_uuid(), format!("serialize claim transaction fail. {:?}", e))
+        })?;
+
+        Ok(ClaimTransaction {
+            signature: row.signature.clone(),
+            payer: payer.to_string(),
+            recipient: row.recipient.clone(),
+            roots_account: self.find_roots_account_address(WrapSlot { slot: claim.root_slot }).to_string(),
+            claimed_account: self.find_claimed_account_address(&leaf_hash).to_string(),
+            recent_blockhash: recent_blockhash.to_string(),
+            transaction: STANDARD.encode(tx_data),
+            message: STANDARD.encode(message.serialize()),
+        })
+    }
+
+    /// A claim transaction paid and signed by `payer`, e.g. the relayer finalizing withdrawals itself.
+    /// A non zero `priority_fee` (micro-lamports per compute unit) is added as a compute budget instruction.
+    pub fn signed_claim_transaction(&self, row: &BridgeTxRow, leaf_index: i64, payer: &Keypair, priority_fee: u64, recent_blockhash: Hash) -> Result<Transaction, NodeError> {
+        let claim = self.claim_args(row, leaf_index)?;
+        let leaf_hash = leaf_hash(row)?;
+
+        let mut ixs = vec![];
+        if priority_fee > 0 {
+            ixs.push(ComputeBudgetInstruction::set_compute_unit_price(priority_fee));
+        }
+        ixs.push(self.claim_instruction(&payer.pubkey(), &leaf_hash, &claim));
+
+        let message = Message::new(&ixs, Some(&payer.pubkey()));
+        let mut tx = Transaction::new_unsigned(message);
+        tx.sign(&[payer], recent_blockhash);
+
+        Ok(tx)
+    }
+
+    pub fn find_roots_account_address(&self, wrap_slot: WrapSlot) -> Pubkey {
+        return ChainBasicService::find_roots_account_address(self.root_mgr_program_id, wrap_slot).0;
+    }
+
+    pub fn find_claimed_account_address(&self, leaf_hash: &[u8; 32]) -> Pubkey {
+        return ChainBasicService::find_claimed_account_address(self.bridge_program_id, leaf_hash).0;
+    }
+
+    pub fn find_vault_account_address(&self) -> Pubkey {
+        return ChainBasicService::find_vault_account_address(self.bridge_program_id).0;
+    }
+}
+
+pub fn leaf_hash(row: &BridgeTxRow) -> Result<[u8; 32], NodeError> {
+    row.tx_info_hash.as_slice().try_into().map_err(|_| {
+        NodeError::new(generate_uuid(), format!("invalid leaf hash. signature: {}", row.signature))
+    })
+}
+
+fn parse_pubkey(pubkey: &str) -> Result<Pubkey, NodeError> {
+    Pubkey::from_str(pubkey).map_err(|e| {
+        NodeError::new(generate_uuid(), format!("invalid pubkey: {}, err: {:?}", pubkey, e))
+    })
+}
+
+#[cfg(test)]
+pub mod test {
+    use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::instruction::InstructionError;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::Keypair;
+    use solana_sdk::signer::Signer;
+    use solana_sdk::system_instruction;
+    use solana_sdk::transaction::{Transaction, VersionedTransaction};
+
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+
+    use crate::models::bridge_transaction_model::{BridgeLifecycle, BridgeTxRow};
+    use solana_sdk::transaction::TransactionError;
+
+    use crate::services::chain_claim_service::{is_claim_of, ChainClaimService, ClaimMarker};
+
+    fn prepare_row(recipient: &Pubkey) -> BridgeTxRow {
+        BridgeTxRow {
+            id: 1,
+            slot: 10,
+            signature: "sig".to_string(),
+            tx_info_hash: vec![1u8; 32],
+            proof: hex::encode([[2u8; 32], [3u8; 32]].concat()),
+            is_generated_proof: true,
+            current_mt_root: vec![4u8; 32],
+            root_program_slot: 12,
+            sender: recipient.to_string(),
+            recipient: recipient.to_string(),
+            amount: 5,
+            bridge_tx_index: 0,
+            status: BridgeLifecycle::Proven,
+            l1_signature: String::new(),
+            proven_on: None,
+            claim_status: "unclaimed".to_string(),
+            claim_slot: 0,
+            claim_attempts: 0,
+            l1_commit_slot: 0,
+            committed_on: None,
+            claimable_on: None,
+            finalized: false,
+            created_on: chrono::NaiveDateTime::default(),
+            updated_on: chrono::NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn test_build_claim_transaction() {
+        let (bridge_program_id, root_mgr_program_id) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let service = ChainClaimService {
+            bridge_program_id: &bridge_program_id,
+            root_mgr_program_id: &root_mgr_program_id,
+        };
+        let recipient = Pubkey::new_unique();
+        let row = prepare_row(&recipient);
+
+        let claim = service.build_claim_transaction(&row, 3, None, Hash::default()).unwrap();
+        assert_eq!(claim.payer, recipient.to_string());
+
+        let tx: Transaction = bincode::deserialize(&STANDARD.decode(claim.transaction).unwrap()).unwrap();
+        assert_eq!(tx.signatures.len(), 1);
+        assert_eq!(tx.message.instructions.len(), 1);
+        assert_eq!(tx.message.account_keys[0], recipient);
+        assert!(tx.message.account_keys.contains(&service.find_claimed_account_address(&[1u8; 32])));
+
+        let mut unproven = row.clone();
+        unproven.is_generated_proof = false;
+        assert!(service.build_claim_transaction(&unproven, 3, None, Hash::default()).is_err());
+    }
+
+    #[test]
+    fn test_signed_claim_transaction() {
+        let (bridge_program_id, root_mgr_program_id) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let service = ChainClaimService {
+            bridge_program_id: &bridge_program_id,
+            root_mgr_program_id: &root_mgr_program_id,
+        };
+        let relayer = Keypair::new();
+        let row = prepare_row(&Pubkey::new_unique());
+
+        let tx = service.signed_claim_transaction(&row, 3, &relayer, 0, Hash::default()).unwrap();
+        assert_eq!(tx.message.account_keys[0], relayer.pubkey());
+        assert_eq!(tx.message.instructions.len(), 1);
+        assert!(tx.verify().is_ok());
+
+        let tx = service.signed_claim_transaction(&row, 3, &relayer, 1_000, Hash::default()).unwrap();
+        assert_eq!(tx.message.instructions.len(), 2);
+    }
+
+    fn prepare_status(signature: &str, slot: u64, failed: bool) -> RpcConfirmedTransactionStatusWithSignature {
+        RpcConfirmedTransactionStatusWithSignature {
+            signature: signature.to_string(),
+            slot,
+            err: failed.then(|| TransactionError::InstructionError(0, InstructionError::Custom(0))),
+            memo: None,
+            block_time: None,
+            confirmation_status: None,
+        }
+    }
+
+    #[test]
+    fn test_claim_marker() {
+        assert_eq!(ClaimMarker::from_statuses(&[]), None);
+        assert_eq!(ClaimMarker::from_statuses(&[prepare_status("a", 5, true)]), None);
+
+        let marker = ClaimMarker::from_statuses(&[prepare_status("b", 9, false)]).unwrap();
+        assert_eq!((marker.signature.as_str(), marker.slot), ("b", 9));
+        assert!(!marker.is_double_claimed());
+
+        // newest first: a failed second claim after the one that created the marker
+        let marker = ClaimMarker::from_statuses(&[prepare_status("c", 12, true), prepare_status("b", 9, false)]).unwrap();
+        assert_eq!((marker.signature.as_str(), marker.slot), ("b", 9));
+        assert_eq!(marker.transactions, 2);
+        assert!(!marker.is_double_claimed());
+
+        let marker = ClaimMarker::from_statuses(&[prepare_status("d", 15, false), prepare_status("b", 9, false)]).unwrap();
+        assert_eq!((marker.signature.as_str(), marker.claims), ("b", 2));
+        assert!(marker.is_double_claimed());
+    }
+
+    #[test]
+    fn test_is_claim_of() {
+        let (bridge_program_id, root_mgr_program_id) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let service = ChainClaimService {
+            bridge_program_id: &bridge_program_id,
+            root_mgr_program_id: &root_mgr_program_id,
+        };
+        let relayer = Keypair::new();
+        let row = prepare_row(&Pubkey::new_unique());
+        let claimed_account = service.find_claimed_account_address(&[1u8; 32]);
+
+        let tx = service.signed_claim_transaction(&row, 3, &relayer, 1_000, Hash::default()).unwrap();
+        assert!(is_claim_of(&VersionedTransaction::from(tx.clone()), &bridge_program_id, &claimed_account));
+        assert!(!is_claim_of(&VersionedTransaction::from(tx), &bridge_program_id, &Pubkey::new_unique()));
+
+        let ix = system_instruction::transfer(&relayer.pubkey(), &claimed_account, 1);
+        let transfer = Transaction::new_signed_with_payer(&[ix], Some(&relayer.pubkey()), &[&relayer], Hash::default());
+        assert!(!is_claim_of(&VersionedTransaction::from(transfer), &bridge_program_id, &claimed_account));
+    }
+}
@@ -1,13 +1,25 @@
-use std::str::FromStr;
-
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
 use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcAccountInfoConfig;
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::hash;
 use solana_sdk::pubkey::Pubkey;
+use thiserror::Error;
 
-use crate::contract::wrap_slot::{self, WrapSlot};
+use crate::common::node_error::NodeError;
+use crate::contract::wrap_slot::WrapSlot;
+use crate::utils::uuid_util::generate_uuid;
 
 use super::chain_basic_service::ChainBasicService;
 
+// the anchor discriminator, `authority` and `initialized` come before the slots
+const SLOTS_LEN_OFFSET: usize = 8 + 32 + 1;
+
+// the slots follow their u32 length
+const SLOTS_OFFSET: usize = SLOTS_LEN_OFFSET + 4;
+
 #[derive(BorshDeserialize, BorshSerialize, Debug)]
 pub struct SlotsAccount {
     pub authority: Pubkey,
@@ -21,30 +33,237 @@ pub struct RootsInfo {
     pub merkle_tree_root: [u8; 32],
     pub world_state_root: [u8; 32],
 }
+
+/// A root manager account that cannot be trusted to feed roots into proof generation.
+#[derive(Debug, Clone, Error, Eq, PartialEq)]
+pub enum RootMgrError {
+    #[error("root manager account not found. address: {0}")]
+    AccountNotFound(Pubkey),
+    #[error("get root manager account fail. address: {address}, err: {message}")]
+    Rpc { address: Pubkey, message: String },
+    #[error("root manager account owned by another program. address: {address}, expected: {expected}, actual: {actual}")]
+    InvalidOwner { address: Pubkey, expected: Pubkey, actual: Pubkey },
+    #[error("root manager account is not a {account}. address: {address}")]
+    InvalidDiscriminator { address: Pubkey, account: &'static str },
+    #[error("slots account not initialized. address: {0}")]
+    Uninitialized(Pubkey),
+    #[error("slots account has another authority. address: {address}, expected: {expected}, actual: {actual}")]
+    InvalidAuthority { address: Pubkey, expected: Pubkey, actual: Pubkey },
+    #[error("roots account holds another slot. address: {address}, expected: {expected}, actual: {actual}")]
+    SlotMismatch { address: Pubkey, expected: u64, actual: u64 },
+    #[error("decode root manager account fail. address: {address}, err: {message}")]
+    Decode { address: Pubkey, message: String },
+}
+
+impl From<RootMgrError> for NodeError {
+    fn from(error: RootMgrError) -> Self {
+        NodeError::new(generate_uuid(), error.to_string())
+    }
+}
+
 pub struct ChainRootMgrService<'a> {
     pub rpc_client: &'a RpcClient,
     pub program_id: &'a Pubkey,
     pub slots_acc_pubkey: &'a Pubkey,
+    // the authority the slots account must have, not checked when `None`
+    pub authority: Option<&'a Pubkey>,
 }
 
 impl ChainRootMgrService<'_> {
-    pub fn fetch_all_slots(&self) -> Option<Vec<u64>> {
-        let slots_acc_data = self.rpc_client.get_account_data(self.slots_acc_pubkey).unwrap();
-        
-        let all_slots = SlotsAccount::deserialize(&mut &slots_acc_data[8..]).unwrap();
-        Some(all_slots.slots.clone())
+    pub fn fetch_all_slots(&self) -> Result<Vec<u64>, RootMgrError> {
+        let account = self.fetch_account(self.slots_acc_pubkey, None, self.rpc_client.commitment())?;
+
+        decode_slots_account(self.slots_acc_pubkey, &account, self.program_id, self.authority, 0)
+    }
+
+    /// The slots appended to the slots account from `from_index` on, reading only that part of the account.
+    pub fn fetch_slots_since(&self, from_index: usize) -> Result<Vec<u64>, RootMgrError> {
+        let header = self.fetch_account(self.slots_acc_pubkey, Some(UiDataSliceConfig { offset: 0, length: SLOTS_OFFSET }), self.rpc_client.commitment())?;
+        let len = check_slots_account(self.slots_acc_pubkey, &header, self.program_id, self.authority)?;
+        if len <= from_index {
+            return Ok(vec![]);
+        }
+
+        let length = (len - from_index) * 8;
+        let account = self.fetch_account(self.slots_acc_pubkey, Some(UiDataSliceConfig { offset: SLOTS_OFFSET + from_index * 8, length }), self.rpc_client.commitment())?;
+        if account.data.len() != length {
+            return Err(RootMgrError::Decode {
+                address: *self.slots_acc_pubkey,
+                message: format!("slots data too short. expected: {}, actual: {}", length, account.data.len()),
+            });
+        }
+
+        Ok(decode_slots(&account.data))
+    }
+
+    pub fn fetch_roots_by_slot(&self, slot: u64) -> Result<RootsInfo, RootMgrError> {
+        self.fetch_roots_by_slot_with_commitment(slot, self.rpc_client.commitment())
     }
 
-    pub fn fetch_roots_by_slot(&self, slot: u64) -> Option<RootsInfo> {
+    pub fn fetch_roots_by_slot_with_commitment(&self, slot: u64, commitment: CommitmentConfig) -> Result<RootsInfo, RootMgrError> {
         let wrap_slot: WrapSlot = WrapSlot { slot };
         let roots_pda = self.find_roots_account_address(wrap_slot);
-        let roots_acc_data = self.rpc_client.get_account_data(&roots_pda).unwrap();
-        let roots_info = RootsInfo::deserialize(&mut &roots_acc_data[8..]).unwrap();
-        //assert_eq!(slot, roots_info.slot, "slot error, slot: {}, roots_info.slot: {}", slot, roots_info.slot); //todo tmp del
-        Some(roots_info)
+        let account = self.fetch_account(&roots_pda, None, commitment)?;
+
+        decode_roots_account(&roots_pda, &account, self.program_id, slot)
     }
 
     pub fn find_roots_account_address(&self, wrap_slot: WrapSlot) -> Pubkey {
         return ChainBasicService::find_roots_account_address(self.program_id, wrap_slot.to_owned()).0;
     }
-}
\ No newline at end of file
+
+    fn fetch_account(&self, address: &Pubkey, data_slice: Option<UiDataSliceConfig>, commitment: CommitmentConfig) -> Result<Account, RootMgrError> {
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice,
+            commitment: Some(commitment),
+            min_context_slot: None,
+        };
+
+        let response = self.rpc_client.get_account_with_config(address, config).map_err(|e| {
+            RootMgrError::Rpc { address: *address, message: format!("{:?}", e) }
+        })?;
+
+        response.value.ok_or(RootMgrError::AccountNotFound(*address))
+    }
+}
+
+/// The first 8 bytes anchor puts in front of the data of the account `name`.
+pub fn account_discriminator(name: &str) -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash(format!("account:{}", name).as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
+fn check_account(address: &Pubkey, account: &Account, program_id: &Pubkey, name: &'static str) -> Result<(), RootMgrError> {
+    if account.owner != *program_id {
+        return Err(RootMgrError::InvalidOwner { address: *address, expected: *program_id, actual: account.owner });
+    }
+    if account.data.len() < 8 || account.data[..8] != account_discriminator(name) {
+        return Err(RootMgrError::InvalidDiscriminator { address: *address, account: name });
+    }
+
+    Ok(())
+}
+
+/// Check the owner, discriminator, `initialized` and `authority` of the slots account, whose data
+/// may stop after the slots length. Returns the count of slots.
+pub fn check_slots_account(address: &Pubkey, account: &Account, program_id: &Pubkey, authority: Option<&Pubkey>) -> Result<usize, RootMgrError> {
+    check_account(address, account, program_id, "SlotsAccount")?;
+
+    let header = account.data.get(8..SLOTS_OFFSET).ok_or_else(|| RootMgrError::Decode {
+        address: *address,
+        message: format!("slots account too short. length: {}", account.data.len()),
+    })?;
+    let mut header = header;
+    let actual_authority = Pubkey::deserialize(&mut header).map_err(|e| RootMgrError::Decode { address: *address, message: e.to_string() })?;
+    let initialized = bool::deserialize(&mut header).map_err(|e| RootMgrError::Decode { address: *address, message: e.to_string() })?;
+    let len = u32::deserialize(&mut header).map_err(|e| RootMgrError::Decode { address: *address, message: e.to_string() })?;
+
+    if !initialized {
+        return Err(RootMgrError::Uninitialized(*address));
+    }
+    if let Some(expected) = authority {
+        if actual_authority != *expected {
+            return Err(RootMgrError::InvalidAuthority { address: *address, expected: *expected, actual: actual_authority });
+        }
+    }
+
+    Ok(len as usize)
+}
+
+/// The slots of the whole slots `account` from `from_index` on, without decoding the ones before.
+pub fn decode_slots_account(address: &Pubkey, account: &Account, program_id: &Pubkey, authority: Option<&Pubkey>, from_index: usize) -> Result<Vec<u64>, RootMgrError> {
+    let len = check_slots_account(address, account, program_id, authority)?;
+    if len <= from_index {
+        return Ok(vec![]);
+    }
+
+    let slots_data = account.data.get(SLOTS_OFFSET + from_index * 8..SLOTS_OFFSET + len * 8).ok_or_else(|| RootMgrError::Decode {
+        address: *address,
+        message: format!("slots account too short. slots: {}, length: {}", len, account.data.len()),
+    })?;
+
+    Ok(decode_slots(slots_data))
+}
+
+/// The roots of `slot` in the roots `account`, which must be owned by the root manager and hold `slot`.
+pub fn decode_roots_account(address: &Pubkey, account: &Account, program_id: &Pubkey, slot: u64) -> Result<RootsInfo, RootMgrError> {
+    check_account(address, account, program_id, "RootsInfo")?;
+
+    let roots_info = RootsInfo::deserialize(&mut &account.data[8..]).map_err(|e| {
+        RootMgrError::Decode { address: *address, message: e.to_string() }
+    })?;
+    if roots_info.slot != slot {
+        return Err(RootMgrError::SlotMismatch { address: *address, expected: slot, actual: roots_info.slot });
+    }
+
+    Ok(roots_info)
+}
+
+fn decode_slots(data: &[u8]) -> Vec<u64> {
+    data.chunks_exact(8)
+        .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+        .collect()
+}
+
+#[cfg(test)]
+pub mod test {
+    use borsh::BorshSerialize;
+    use solana_sdk::account::Account;
+    use solana_sdk::pubkey::Pubkey;
+
+    use crate::services::chain_root_mgr_service::{account_discriminator, decode_roots_account, decode_slots_account, RootMgrError, RootsInfo, SlotsAccount};
+
+    fn prepare_account<T: BorshSerialize>(name: &str, value: &T, owner: Pubkey) -> Account {
+        let mut data = account_discriminator(name).to_vec();
+        value.serialize(&mut data).unwrap();
+
+        Account { lamports: 1, data, owner, executable: false, rent_epoch: 0 }
+    }
+
+    #[test]
+    fn test_decode_slots_account() {
+        let address = Pubkey::new_unique();
+        let program_id = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let slots = SlotsAccount { authority, initialized: true, slots: vec![10, 20, 30] };
+        let account = prepare_account("SlotsAccount", &slots, program_id);
+
+        assert_eq!(decode_slots_account(&address, &account, &program_id, Some(&authority), 0), Ok(vec![10, 20, 30]));
+        assert_eq!(decode_slots_account(&address, &account, &program_id, None, 2), Ok(vec![30]));
+        assert_eq!(decode_slots_account(&address, &account, &program_id, None, 5), Ok(vec![]));
+
+        let other = Pubkey::new_unique();
+        assert_eq!(decode_slots_account(&address, &account, &other, None, 0),
+            Err(RootMgrError::InvalidOwner { address, expected: other, actual: program_id }));
+        assert_eq!(decode_slots_account(&address, &account, &program_id, Some(&other), 0),
+            Err(RootMgrError::InvalidAuthority { address, expected: other, actual: authority }));
+
+        let roots = prepare_account("RootsInfo", &slots, program_id);
+        assert_eq!(decode_slots_account(&address, &roots, &program_id, None, 0),
+            Err(RootMgrError::InvalidDiscriminator { address, account: "SlotsAccount" }));
+
+        let uninitialized = SlotsAccount { authority, initialized: false, slots: vec![] };
+        let account = prepare_account("SlotsAccount", &uninitialized, program_id);
+        assert_eq!(decode_slots_account(&address, &account, &program_id, None, 0), Err(RootMgrError::Uninitialized(address)));
+
+        let mut truncated = prepare_account("SlotsAccount", &slots, program_id);
+        truncated.data.pop();
+        assert!(matches!(decode_slots_account(&address, &truncated, &program_id, None, 0), Err(RootMgrError::Decode { .. })));
+    }
+
+    #[test]
+    fn test_decode_roots_account() {
+        let address = Pubkey::new_unique();
+        let program_id = Pubkey::new_unique();
+        let roots = RootsInfo { slot: 7, merkle_tree_root: [1u8; 32], world_state_root: [2u8; 32] };
+        let account = prepare_account("RootsInfo", &roots, program_id);
+
+        assert_eq!(decode_roots_account(&address, &account, &program_id, 7).unwrap().merkle_tree_root, [1u8; 32]);
+        assert!(matches!(decode_roots_account(&address, &account, &program_id, 8),
+            Err(RootMgrError::SlotMismatch { expected: 8, actual: 7, .. })));
+        assert!(matches!(decode_roots_account(&address, &account, &Pubkey::new_unique(), 7),
+            Err(RootMgrError::InvalidOwner { .. })));
+    }
+}
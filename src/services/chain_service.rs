@@ -1,38 +1,60 @@
 use log::{error, info};
 use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
 use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
-use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_transaction_status::UiTransactionEncoding;
 use std::str::FromStr;
 
 use crate::common::node_configs::ChainConfiguration;
 use crate::common::node_error::NodeError;
 use crate::contract::chain_brief::ChainBrief;
+use crate::contract::chain_challenge::ChainChallenge;
 use crate::contract::wrap_slot::WrapSlot;
+use crate::models::bridge_transaction_model::BridgeTxRow;
+use crate::utils::uuid_util::generate_uuid;
 use crate::services::chain_basic_service::ChainBasicService;
 use crate::services::chain_brief_service::ChainBriefService;
+use crate::services::chain_challenge_service::{ChainChallengeService, ChallengeSubmission};
+use crate::services::chain_claim_service::{self, ChainClaimService, ClaimMarker, ClaimTransaction};
 use crate::services::chain_state_service::ChainStateService;
 use crate::services::chain_tally_service::ChainTallyService;
 
-use super::chain_root_mgr_service::{self, ChainRootMgrService, RootsInfo};
+use super::chain_root_mgr_service::{self, ChainRootMgrService, RootMgrError, RootsInfo};
+
+// getMultipleAccounts takes at most this many addresses
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
 
 pub struct ChainService {
+    // reads at the configured read commitment
     rpc_client: RpcClient,
+    // sends and confirms transactions at the configured send commitment
+    send_client: RpcClient,
     chain_config: ChainConfiguration,
 }
 
 impl ChainService {
     pub fn new(config: &ChainConfiguration) -> Result<Self, NodeError> {
-        let rpc_client: RpcClient = RpcClient::new_with_commitment(config.clone().url, CommitmentConfig::confirmed());
+        let read_commitment = parse_commitment(&config.read_commitment)?;
+        let send_commitment = parse_commitment(&config.send_commitment)?;
+        let rpc_client: RpcClient = RpcClient::new_with_commitment(config.clone().url, read_commitment);
+        let send_client: RpcClient = RpcClient::new_with_commitment(config.clone().url, send_commitment);
 
-        info!("Created RpcClient.");
+        info!("Created RpcClient. read commitment: {:?}, send commitment: {:?}", read_commitment.commitment, send_commitment.commitment);
 
         Ok(Self {
             rpc_client: rpc_client,
+            send_client: send_client,
             chain_config: config.clone(),
         })
     }
 
+    /// Whether reads only see finalized state, so roots read are final as soon as they are read.
+    pub fn reads_finalized(&self) -> bool {
+        self.rpc_client.commitment().is_finalized()
+    }
+
     pub fn get_role(&self, keypair: &str) -> Option<Keypair> {
         let mut is_success: bool = true;
 
@@ -62,7 +84,7 @@ impl ChainService {
         let execute_node = self.get_role(&self.chain_config.clone().execute_keypair).unwrap();
 
         let chain_state_service = ChainStateService {
-            rpc_client: &self.rpc_client,
+            rpc_client: &self.send_client,
             program_id: fraud_proof_native_program_id,
             payer: &execute_node,
         };
@@ -93,7 +115,7 @@ impl ChainService {
         let execute_node = self.get_role(&self.chain_config.clone().execute_keypair).unwrap();
 
         let chain_tally_service = ChainTallyService {
-            rpc_client: &self.rpc_client,
+            rpc_client: &self.send_client,
             program_id: fraud_proof_native_program_id,
             payer: &execute_node,
         };
@@ -125,7 +147,7 @@ impl ChainService {
         let execute_node = self.get_role(&self.chain_config.clone().execute_keypair).unwrap();
 
         let chain_brief_service = ChainBriefService {
-            rpc_client: &self.rpc_client,
+            rpc_client: &self.send_client,
             program_id: fraud_proof_native_program_id,
             payer: &execute_node,
         };
@@ -159,6 +181,319 @@ impl ChainService {
         return is_success.clone();
     }
 
+    /// The slot of the last brief the fraud proof program accepted, `None` before the tally account exists.
+    pub fn get_present_slot(&self) -> Option<u64> {
+        let fraud_proof_native_program_id_binding = Pubkey::from_str(&self.chain_config.fraud_proof_native_program_id);
+        let fraud_proof_native_program_id = fraud_proof_native_program_id_binding.as_ref().unwrap();
+
+        let execute_node = Keypair::from_base58_string(&self.chain_config.execute_keypair);
+
+        let chain_tally_service = ChainTallyService {
+            rpc_client: &self.rpc_client,
+            program_id: fraud_proof_native_program_id,
+            payer: &execute_node,
+        };
+
+        if !chain_tally_service.is_tally_account_exist() {
+            return None;
+        }
+
+        chain_tally_service.get_max_wrap_slot().map(|wrap_slot| wrap_slot.slot)
+    }
+
+    /// The challenge period of the fraud proof program in seconds, `None` before the state account exists.
+    pub fn get_challenge_period(&self) -> Option<u64> {
+        let fraud_proof_native_program_id_binding = Pubkey::from_str(&self.chain_config.fraud_proof_native_program_id);
+        let fraud_proof_native_program_id = fraud_proof_native_program_id_binding.as_ref().unwrap();
+
+        let execute_node = Keypair::from_base58_string(&self.chain_config.execute_keypair);
+
+        let chain_state_service = ChainStateService {
+            rpc_client: &self.rpc_client,
+            program_id: fraud_proof_native_program_id,
+            payer: &execute_node,
+        };
+
+        chain_state_service.fetch_state_account().map(|state| state.challenge_period)
+    }
+
+    pub fn fetch_brief(&self, slot: u64) -> Option<ChainBrief> {
+        let fraud_proof_native_program_id_binding = Pubkey::from_str(&self.chain_config.fraud_proof_native_program_id);
+        let fraud_proof_native_program_id = fraud_proof_native_program_id_binding.as_ref().unwrap();
+
+        let execute_node = Keypair::from_base58_string(&self.chain_config.execute_keypair);
+
+        let chain_brief_service = ChainBriefService {
+            rpc_client: &self.rpc_client,
+            program_id: fraud_proof_native_program_id,
+            payer: &execute_node,
+        };
+
+        let wrap_slot: WrapSlot = WrapSlot {
+            slot,
+        };
+
+        if !chain_brief_service.is_brief_account_exist(wrap_slot.clone()) {
+            return None;
+        }
+
+        chain_brief_service.fetch_brief_account(wrap_slot)
+    }
+
+    /// Close the brief account of `slot` and return the rent it held to the payer.
+    /// Returns the reclaimed lamports, `None` when the account is still open.
+    pub fn destroy_brief_account(&mut self, slot: u64) -> Option<u64> {
+        let fraud_proof_native_program_id_binding = Pubkey::from_str(&self.chain_config.clone().fraud_proof_native_program_id);
+        let fraud_proof_native_program_id = fraud_proof_native_program_id_binding.as_ref().unwrap();
+
+        let execute_node = Keypair::from_base58_string(&self.chain_config.execute_keypair);
+
+        let chain_brief_service = ChainBriefService {
+            rpc_client: &self.send_client,
+            program_id: fraud_proof_native_program_id,
+            payer: &execute_node,
+        };
+
+        let wrap_slot: WrapSlot = WrapSlot {
+            slot,
+        };
+
+        let brief_account_address = chain_brief_service.find_brief_account_address(wrap_slot.clone());
+        let lamports = match self.rpc_client.get_balance(&brief_account_address) {
+            Ok(lamports) => lamports,
+            Err(e) => {
+                error!("get brief account balance fail. slot: {:?}, err: {:?}", wrap_slot, e);
+                return None;
+            }
+        };
+        // already closed, e.g. before a restart
+        if lamports == 0 {
+            return Some(0);
+        }
+
+        if !chain_brief_service.destroy_brief_account(wrap_slot.clone())
+            || chain_brief_service.is_brief_account_exist(wrap_slot.clone()) {
+            error!("destroy brief account fail. slot: {:?}", wrap_slot);
+            return None;
+        }
+
+        Some(lamports)
+    }
+
+    pub fn is_challenge_account_exist(&self, slot: u64) -> Result<bool, NodeError> {
+        let fraud_proof_native_program_id_binding = Pubkey::from_str(&self.chain_config.fraud_proof_native_program_id);
+        let fraud_proof_native_program_id = fraud_proof_native_program_id_binding.as_ref().unwrap();
+
+        let execute_node = Keypair::from_base58_string(&self.chain_config.execute_keypair);
+
+        let chain_challenge_service = ChainChallengeService {
+            rpc_client: &self.rpc_client,
+            program_id: fraud_proof_native_program_id,
+            payer: &execute_node,
+        };
+
+        chain_challenge_service.is_challenge_account_exist(WrapSlot { slot })
+    }
+
+    /// An unsigned transaction claiming the proven withdrawal `row` from the L1 bridge program.
+    pub fn build_claim_transaction(&self, row: &BridgeTxRow, leaf_index: i64, payer: Option<Pubkey>) -> Result<ClaimTransaction, NodeError> {
+        let (bridge_program_id, root_mgr_program_id) = self.claim_program_ids()?;
+
+        let chain_claim_service = ChainClaimService {
+            bridge_program_id: &bridge_program_id,
+            root_mgr_program_id: &root_mgr_program_id,
+        };
+
+        let recent_blockhash = self.rpc_client.get_latest_blockhash().map_err(|e| {
+            NodeError::new(generate_uuid(), format!("get latest blockhash fail. err: {:?}", e))
+        })?;
+
+        chain_claim_service.build_claim_transaction(row, leaf_index, payer, recent_blockhash)
+    }
+
+    /// Claim the proven withdrawal `row` on L1, paid and signed by the execute keypair.
+    pub fn submit_claim(&self, row: &BridgeTxRow, leaf_index: i64, priority_fee: u64) -> Result<Signature, NodeError> {
+        let (bridge_program_id, root_mgr_program_id) = self.claim_program_ids()?;
+
+        let execute_node = Keypair::from_base58_string(&self.chain_config.execute_keypair);
+
+        let chain_claim_service = ChainClaimService {
+            bridge_program_id: &bridge_program_id,
+            root_mgr_program_id: &root_mgr_program_id,
+        };
+
+        let recent_blockhash = self.send_client.get_latest_blockhash().map_err(|e| {
+            NodeError::new(generate_uuid(), format!("get latest blockhash fail. err: {:?}", e))
+        })?;
+        let tx = chain_claim_service.signed_claim_transaction(row, leaf_index, &execute_node, priority_fee, recent_blockhash)?;
+
+        self.send_client.send_transaction(&tx).map_err(|e| {
+            NodeError::new(generate_uuid(), format!("send claim transaction fail. signature: {}, err: {:?}", row.signature, e))
+        })
+    }
+
+    // Some(true): the claim transaction landed successfully
+    // Some(false): the claim transaction failed on chain
+    // None: the transaction is not yet known to the cluster
+    pub fn get_claim_status(&self, signature: &Signature) -> Option<bool> {
+        match self.send_client.get_signature_status(signature) {
+            Ok(Some(result)) => {
+                if let Err(err) = &result {
+                    error!("claim transaction fail. signature: {:?}, err: {:?}", signature, err);
+                }
+                Some(result.is_ok())
+            }
+            Ok(None) => None,
+            Err(err) => {
+                error!("get claim status fail. signature: {:?}, err: {:?}", signature, err);
+                None
+            }
+        }
+    }
+
+    /// Whether the claimed marker account of the withdrawal with `leaf_hash` exists on L1.
+    pub fn is_claimed(&self, leaf_hash: &[u8; 32]) -> Result<bool, NodeError> {
+        let (bridge_program_id, _) = self.claim_program_ids()?;
+
+        let claimed_account_address = ChainBasicService::find_claimed_account_address(&bridge_program_id, leaf_hash).0;
+
+        // only a missing account means unclaimed, an rpc error says nothing about the claim
+        let account = self.rpc_client.get_account_with_commitment(&claimed_account_address, self.rpc_client.commitment())
+            .map_err(|e| {
+                NodeError::new(generate_uuid(), format!("get claimed account fail. address: {}, err: {:?}", claimed_account_address, e))
+            })?;
+
+        Ok(account.value.is_some())
+    }
+
+    /// Whether the claimed marker accounts of the withdrawals with `leaf_hashes` exist on L1, in order.
+    pub fn are_claimed(&self, leaf_hashes: &[[u8; 32]]) -> Result<Vec<bool>, NodeError> {
+        let (bridge_program_id, _) = self.claim_program_ids()?;
+
+        let addresses: Vec<Pubkey> = leaf_hashes.iter()
+            .map(|leaf_hash| ChainBasicService::find_claimed_account_address(&bridge_program_id, leaf_hash).0)
+            .collect();
+        let mut claimed = Vec::with_capacity(addresses.len());
+        for chunk in addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let accounts = self.rpc_client.get_multiple_accounts_with_commitment(chunk, self.rpc_client.commitment())
+                .map_err(|e| {
+                    NodeError::new(generate_uuid(), format!("get claimed accounts fail. count: {}, err: {:?}", chunk.len(), e))
+                })?;
+            claimed.extend(accounts.value.iter().map(|account| account.is_some()));
+        }
+
+        Ok(claimed)
+    }
+
+    /// The claim of the withdrawal with `leaf_hash` from the transactions on its claimed marker account,
+    /// for a marker account known to exist.
+    pub fn claim_marker(&self, leaf_hash: &[u8; 32]) -> Result<Option<ClaimMarker>, NodeError> {
+        let (bridge_program_id, _) = self.claim_program_ids()?;
+
+        let claimed_account_address = ChainBasicService::find_claimed_account_address(&bridge_program_id, leaf_hash).0;
+
+        let statuses = self.rpc_client.get_signatures_for_address(&claimed_account_address).map_err(|e| {
+            NodeError::new(generate_uuid(), format!("get claimed account signatures fail. address: {}, err: {:?}", claimed_account_address, e))
+        })?;
+        let mut marker = match ClaimMarker::from_statuses(&statuses) {
+            Some(marker) => marker,
+            None => return Ok(None),
+        };
+        if !marker.is_double_claimed() {
+            return Ok(Some(marker));
+        }
+
+        // several successful transactions, only count the ones claiming
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(self.rpc_client.commitment()),
+            max_supported_transaction_version: Some(0),
+        };
+        let mut claims = 0;
+        for status in statuses.iter().filter(|s| s.err.is_none()) {
+            let signature = Signature::from_str(&status.signature).map_err(|e| {
+                NodeError::new(generate_uuid(), format!("invalid signature: {}, err: {:?}", status.signature, e))
+            })?;
+            let tx = self.rpc_client.get_transaction_with_config(&signature, config).map_err(|e| {
+                NodeError::new(generate_uuid(), format!("get claimed account transaction fail. signature: {}, err: {:?}", signature, e))
+            })?;
+            if tx.transaction.transaction.decode()
+                .is_some_and(|tx| chain_claim_service::is_claim_of(&tx, &bridge_program_id, &claimed_account_address)) {
+                claims += 1;
+            }
+        }
+        marker.claims = claims;
+
+        Ok(Some(marker))
+    }
+
+    fn claim_program_ids(&self) -> Result<(Pubkey, Pubkey), NodeError> {
+        let bridge_program_id = Pubkey::from_str(&self.chain_config.l1_bridge_program_id).map_err(|e| {
+            NodeError::new(generate_uuid(), format!("invalid l1 bridge program id. err: {:?}", e))
+        })?;
+        let root_mgr_program_id = Pubkey::from_str(&self.chain_config.l1_root_mgr_program_id).map_err(|e| {
+            NodeError::new(generate_uuid(), format!("invalid l1 root mgr program id. err: {:?}", e))
+        })?;
+
+        Ok((bridge_program_id, root_mgr_program_id))
+    }
+
+    /// The root manager program, its slots account and the authority expected on it.
+    pub fn root_mgr_accounts(&self) -> (Pubkey, Pubkey, Option<Pubkey>) {
+        let root_mgr_program_id = Pubkey::from_str(&self.chain_config.l1_root_mgr_program_id).unwrap();
+        let slots_acc_pubkey = Pubkey::from_str(&self.chain_config.l1_slots_account_pubkey).unwrap();
+        let authority = if self.chain_config.l1_root_mgr_authority.is_empty() {
+            None
+        } else {
+            Some(Pubkey::from_str(&self.chain_config.l1_root_mgr_authority).unwrap())
+        };
+
+        (root_mgr_program_id, slots_acc_pubkey, authority)
+    }
+
+    pub fn submit_challenge(&self, challenge: ChainChallenge) -> Result<ChallengeSubmission, NodeError> {
+        let fraud_proof_native_program_id_binding = Pubkey::from_str(&self.chain_config.clone().fraud_proof_native_program_id);
+        let fraud_proof_native_program_id = fraud_proof_native_program_id_binding.as_ref().unwrap();
+
+        let execute_node = Keypair::from_base58_string(&self.chain_config.execute_keypair);
+
+        let chain_challenge_service = ChainChallengeService {
+            rpc_client: &self.send_client,
+            program_id: fraud_proof_native_program_id,
+            payer: &execute_node,
+        };
+
+        let wrap_slot: WrapSlot = WrapSlot {
+            slot: challenge.slot,
+        };
+
+        if chain_challenge_service.is_challenge_account_exist(wrap_slot.clone())? {
+            info!("challenge account is already exist. slot: {:?}", wrap_slot.clone());
+            return Ok(ChallengeSubmission::Exists);
+        }
+        info!("challenge account is not exist. slot: {:?}, challenge: {:?}", wrap_slot.clone(), challenge);
+
+        match chain_challenge_service.submit_challenge(wrap_slot.clone(), challenge) {
+            Some(signature) => Ok(ChallengeSubmission::Sent(signature)),
+            None => Err(NodeError::new(generate_uuid(), format!("submit challenge fail. slot: {:?}", wrap_slot))),
+        }
+    }
+
+    pub fn get_challenge_status(&self, signature: &Signature) -> Option<bool> {
+        let fraud_proof_native_program_id_binding = Pubkey::from_str(&self.chain_config.fraud_proof_native_program_id);
+        let fraud_proof_native_program_id = fraud_proof_native_program_id_binding.as_ref().unwrap();
+
+        let execute_node = Keypair::from_base58_string(&self.chain_config.execute_keypair);
+
+        let chain_challenge_service = ChainChallengeService {
+            rpc_client: &self.send_client,
+            program_id: fraud_proof_native_program_id,
+            payer: &execute_node,
+        };
+
+        chain_challenge_service.get_challenge_status(signature)
+    }
+
     pub fn get_latest_slot_from_chain(&mut self) -> Option<u64> {
         let all_slots = self.get_all_slots_from_chain().unwrap();
         
@@ -170,19 +505,22 @@ impl ChainService {
     }
 
     pub fn get_all_slots_from_chain(&mut self) -> Option<Vec<u64>> {
-        let root_mgr_program_id_binding = Pubkey::from_str(&self.chain_config.l1_root_mgr_program_id);
-        let root_mgr_program_id = root_mgr_program_id_binding.as_ref().unwrap();
-
-        let slots_acc_pubkey_binding = Pubkey::from_str(&self.chain_config.l1_slots_account_pubkey);
-        let slots_acc_pubkey = slots_acc_pubkey_binding.as_ref().unwrap();
+        let (root_mgr_program_id, slots_acc_pubkey, authority) = self.root_mgr_accounts();
 
         let chain_root_mgr_service = ChainRootMgrService{
             rpc_client: &self.rpc_client,
-            program_id: root_mgr_program_id,
-            slots_acc_pubkey,
+            program_id: &root_mgr_program_id,
+            slots_acc_pubkey: &slots_acc_pubkey,
+            authority: authority.as_ref(),
         };
 
-        let all_slots = chain_root_mgr_service.fetch_all_slots()?;
+        let all_slots = match chain_root_mgr_service.fetch_all_slots() {
+            Ok(all_slots) => all_slots,
+            Err(e) => {
+                error!("fetch all slots fail. {}", e);
+                return None;
+            }
+        };
         //info!("all slots: {:?}", all_slots);
 
         Some(all_slots)
@@ -192,19 +530,71 @@ impl ChainService {
         // None
     }
 
-    pub fn get_roots_info_by_slot(&mut self, slot: u64) -> Option<RootsInfo> {
-        let root_mgr_program_id_binding = Pubkey::from_str(&self.chain_config.l1_root_mgr_program_id);
-        let root_mgr_program_id = root_mgr_program_id_binding.as_ref().unwrap();
+    /// The slots appended to the root manager from `from_index` on.
+    pub fn get_slots_since(&self, from_index: usize) -> Result<Vec<u64>, RootMgrError> {
+        let (root_mgr_program_id, slots_acc_pubkey, authority) = self.root_mgr_accounts();
+
+        let chain_root_mgr_service = ChainRootMgrService{
+            rpc_client: &self.rpc_client,
+            program_id: &root_mgr_program_id,
+            slots_acc_pubkey: &slots_acc_pubkey,
+            authority: authority.as_ref(),
+        };
+        chain_root_mgr_service.fetch_slots_since(from_index)
+    }
 
-        let slots_acc_pubkey_binding = Pubkey::from_str(&self.chain_config.l1_slots_account_pubkey);
-        let slots_acc_pubkey = slots_acc_pubkey_binding.as_ref().unwrap();
+    /// The roots of `slot` as finalized on L1.
+    pub fn get_finalized_roots_info_by_slot(&self, slot: u64) -> Result<RootsInfo, RootMgrError> {
+        let (root_mgr_program_id, slots_acc_pubkey, authority) = self.root_mgr_accounts();
 
         let chain_root_mgr_service = ChainRootMgrService{
             rpc_client: &self.rpc_client,
-            program_id: root_mgr_program_id,
-            slots_acc_pubkey,
+            program_id: &root_mgr_program_id,
+            slots_acc_pubkey: &slots_acc_pubkey,
+            authority: authority.as_ref(),
+        };
+        chain_root_mgr_service.fetch_roots_by_slot_with_commitment(slot, CommitmentConfig::finalized())
+    }
+
+    pub fn get_roots_info_by_slot(&mut self, slot: u64) -> Result<RootsInfo, RootMgrError> {
+        let (root_mgr_program_id, slots_acc_pubkey, authority) = self.root_mgr_accounts();
+
+        let chain_root_mgr_service = ChainRootMgrService{
+            rpc_client: &self.rpc_client,
+            program_id: &root_mgr_program_id,
+            slots_acc_pubkey: &slots_acc_pubkey,
+            authority: authority.as_ref(),
         };
         chain_root_mgr_service.fetch_roots_by_slot(slot)
     }
+
+    /// The L1 slot and block time of the transaction that created the roots account of `slot`,
+    /// `None` while the roots account has no transaction.
+    pub fn get_roots_commit(&self, slot: u64) -> Result<Option<(u64, Option<i64>)>, NodeError> {
+        let (_, root_mgr_program_id) = self.claim_program_ids()?;
+
+        let roots_pda = ChainBasicService::find_roots_account_address(&root_mgr_program_id, WrapSlot { slot }).0;
+
+        let statuses = self.rpc_client.get_signatures_for_address(&roots_pda).map_err(|e| {
+            NodeError::new(generate_uuid(), format!("get roots account signatures fail. slot: {}, err: {:?}", slot, e))
+        })?;
+        // newest first, the roots account is created by the oldest
+        let created = match statuses.iter().rev().find(|status| status.err.is_none()) {
+            Some(status) => status,
+            None => return Ok(None),
+        };
+
+        let block_time = match created.block_time {
+            Some(block_time) => Some(block_time),
+            None => self.rpc_client.get_block_time(created.slot).ok(),
+        };
+
+        Ok(Some((created.slot, block_time)))
+    }
 }
 
+fn parse_commitment(level: &str) -> Result<CommitmentConfig, NodeError> {
+    CommitmentConfig::from_str(level).map_err(|e| {
+        NodeError::new(generate_uuid(), format!("invalid commitment: {}, err: {:?}", level, e))
+    })
+}
@@ -0,0 +1,213 @@
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use log::{error, info, warn};
+use solana_account_decoder::UiAccountEncoding;
+use solana_cli_config::Config;
+use solana_client::pubsub_client::{AccountSubscription, PubsubClient};
+use solana_client::rpc_config::RpcAccountInfoConfig;
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::common::node_configs::ChainConfiguration;
+use crate::services::chain_root_mgr_service;
+use crate::services::chain_service::ChainService;
+
+// with a subscription the slots account is polled only this often, to catch a lost notification
+const SUBSCRIBED_POLL_SECONDS: u64 = 30;
+
+// without a subscription the slots account is polled this often
+const UNSUBSCRIBED_POLL_SECONDS: u64 = 1;
+
+// a failed or closed subscription is opened again after this long
+const RESUBSCRIBE_SECONDS: u64 = 30;
+
+// the latest slots handed out are read again every round to catch a reorder or removal
+const RECHECK_SLOTS: usize = 32;
+
+/// What changed in the slots account since the last read.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SlotsUpdate {
+    // the first slot handed out before that is no longer at its place, its root and the later ones are void
+    pub rewritten_from: Option<u64>,
+    // the slots to process, in account order
+    pub slots: Vec<u64>,
+    // appended slots not above the slot before them
+    pub out_of_order: Vec<u64>,
+}
+
+/// Compare the slots `read` from the account with the `recent` ones handed out from the same index.
+pub fn diff_slots(recent: &[u64], read: &[u64]) -> SlotsUpdate {
+    let mut update = SlotsUpdate::default();
+
+    let kept = recent.iter().zip(read.iter()).take_while(|(known, now)| known == now).count();
+    if kept < recent.len() {
+        update.rewritten_from = Some(recent[kept]);
+    }
+
+    let mut last = if kept > 0 { Some(read[kept - 1]) } else { None };
+    for &slot in read[kept..].iter() {
+        if last.map_or(false, |last| slot <= last) {
+            update.out_of_order.push(slot);
+        } else {
+            update.slots.push(slot);
+            last = Some(slot);
+        }
+    }
+
+    update
+}
+
+/// Follow the slots appended to the root manager slots account.
+///
+/// Updates are pushed by an `account_subscribe` on the slots account. While the subscription is
+/// down the account is polled, and in both cases only the slots after the ones already handed out
+/// are decoded or read, along with the latest `RECHECK_SLOTS` handed out to tell a rewrite.
+pub struct ChainSlotsFeed {
+    ws_url: String,
+    commitment: CommitmentConfig,
+    slots_acc_pubkey: Pubkey,
+    subscription: Option<AccountSubscription>,
+    last_subscribe: Option<Instant>,
+    // the count of slots handed out so far
+    next_index: usize,
+    // the latest slots handed out, the last at `next_index - 1`
+    recent: Vec<u64>,
+}
+
+impl ChainSlotsFeed {
+    pub fn new(config: &ChainConfiguration) -> Self {
+        let ws_url = if config.ws_url.is_empty() {
+            Config::compute_websocket_url(&config.url)
+        } else {
+            config.ws_url.clone()
+        };
+
+        Self {
+            ws_url,
+            commitment: CommitmentConfig::from_str(&config.read_commitment).unwrap_or(CommitmentConfig::confirmed()),
+            slots_acc_pubkey: Pubkey::from_str(&config.l1_slots_account_pubkey).unwrap(),
+            subscription: None,
+            last_subscribe: None,
+            next_index: 0,
+            recent: vec![],
+        }
+    }
+
+    /// Wait for slots appended or rewritten since the last call, empty when none arrived in time.
+    pub fn next(&mut self, chain_service: &ChainService) -> SlotsUpdate {
+        self.subscribe();
+
+        let pushed = match self.subscription.as_ref() {
+            Some((_, receiver)) => match receiver.recv_timeout(Duration::from_secs(SUBSCRIBED_POLL_SECONDS)) {
+                // only the latest state of the account matters
+                Ok(response) => Some(receiver.try_iter().last().unwrap_or(response)),
+                Err(e) if e.is_timeout() => None,
+                Err(_) => {
+                    warn!("slots account subscription closed, fall back to polling. ws url: {}", self.ws_url);
+                    self.unsubscribe();
+                    None
+                }
+            },
+            None => {
+                std::thread::sleep(Duration::from_secs(UNSUBSCRIBED_POLL_SECONDS));
+                None
+            }
+        };
+
+        let from_index = self.next_index - self.recent.len();
+        let read = match pushed {
+            Some(response) => match response.value.decode::<Account>() {
+                Some(account) => {
+                    let (program_id, _, authority) = chain_service.root_mgr_accounts();
+                    chain_root_mgr_service::decode_slots_account(&self.slots_acc_pubkey, &account, &program_id, authority.as_ref(), from_index)
+                }
+                None => {
+                    error!("decode slots account notification fail. slot: {}", response.context.slot);
+                    return SlotsUpdate::default();
+                }
+            },
+            None => chain_service.get_slots_since(from_index),
+        };
+        let read = match read {
+            Ok(read) => read,
+            Err(e) => {
+                error!("read slots account fail. {}", e);
+                return SlotsUpdate::default();
+            }
+        };
+
+        let update = diff_slots(&self.recent, &read);
+        self.next_index = from_index + read.len();
+        self.recent = read[read.len().saturating_sub(RECHECK_SLOTS)..].to_vec();
+
+        update
+    }
+
+    fn subscribe(&mut self) {
+        if self.subscription.is_some() {
+            return;
+        }
+        if let Some(last_subscribe) = self.last_subscribe {
+            if last_subscribe.elapsed() < Duration::from_secs(RESUBSCRIBE_SECONDS) {
+                return;
+            }
+        }
+        self.last_subscribe = Some(Instant::now());
+
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice: None,
+            commitment: Some(self.commitment),
+            min_context_slot: None,
+        };
+        match PubsubClient::account_subscribe(&self.ws_url, &self.slots_acc_pubkey, Some(config)) {
+            Ok(subscription) => {
+                info!("slots account subscribed. ws url: {}, pubkey: {}", self.ws_url, self.slots_acc_pubkey);
+                self.subscription = Some(subscription);
+            }
+            Err(e) => {
+                warn!("subscribe slots account fail, fall back to polling. ws url: {}, err: {:?}", self.ws_url, e);
+            }
+        }
+    }
+
+    fn unsubscribe(&mut self) {
+        if let Some((mut client, _)) = self.subscription.take() {
+            let _ = client.shutdown();
+        }
+    }
+}
+
+impl Drop for ChainSlotsFeed {
+    fn drop(&mut self) {
+        self.unsubscribe();
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::services::chain_slots_feed::{diff_slots, SlotsUpdate};
+
+    #[test]
+    fn test_diff_slots() {
+        // appended
+        assert_eq!(diff_slots(&[10, 20], &[10, 20, 30, 40]), SlotsUpdate { slots: vec![30, 40], ..Default::default() });
+        assert_eq!(diff_slots(&[10, 20], &[10, 20]), SlotsUpdate::default());
+        assert_eq!(diff_slots(&[], &[10, 20]), SlotsUpdate { slots: vec![10, 20], ..Default::default() });
+
+        // overwritten
+        assert_eq!(diff_slots(&[10, 20, 30], &[10, 25, 30]),
+            SlotsUpdate { rewritten_from: Some(20), slots: vec![25, 30], ..Default::default() });
+
+        // removed
+        assert_eq!(diff_slots(&[10, 20, 30], &[10]), SlotsUpdate { rewritten_from: Some(20), ..Default::default() });
+
+        // out of order
+        assert_eq!(diff_slots(&[10, 20], &[10, 20, 15, 30]),
+            SlotsUpdate { slots: vec![30], out_of_order: vec![15], ..Default::default() });
+        assert_eq!(diff_slots(&[10, 20], &[10, 20, 20]),
+            SlotsUpdate { out_of_order: vec![20], ..Default::default() });
+    }
+}
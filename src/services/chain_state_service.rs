@@ -7,12 +7,17 @@ use solana_sdk::{
 use solana_sdk::instruction::{AccountMeta, Instruction};
 use solana_sdk::signature::Keypair;
 
+use crate::contract::chain_state::ChainState;
 use crate::contract::chain_tally::ChainTally;
 use crate::contract::wrap_slot::WrapSlot;
 use crate::services::chain_basic_service::ChainBasicService;
+use crate::services::chain_root_mgr_service::account_discriminator;
 
 const INITIALIZE_DISCRIMINANT: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
 
+// the anchor account name of `ChainState` in the fraud proof program
+const STATE_ACCOUNT_NAME: &str = "State";
+
 pub struct ChainStateService<'a> {
     pub rpc_client: &'a RpcClient,
     pub program_id: &'a Pubkey,
@@ -68,6 +73,38 @@ impl ChainStateService<'_> {
         return is_ok;
     }
 
+    pub fn fetch_state_account(&self) -> Option<ChainState> {
+        let state_account_address = self.find_state_account_address();
+
+        let state_account = match self.rpc_client.get_account(&state_account_address) {
+            Ok(account) => account,
+            Err(err) => {
+                error!("get state account fail. address: {:?}, err: {:?}", state_account_address, err);
+                return None;
+            }
+        };
+        // only read the fields of an account the fraud proof program created as its `State`
+        if state_account.owner != *self.program_id {
+            error!("state account has the wrong owner. address: {:?}, owner: {:?}", state_account_address, state_account.owner);
+            return None;
+        }
+        if state_account.data.len() < 8 || state_account.data[..8] != account_discriminator(STATE_ACCOUNT_NAME) {
+            error!("state account is not a {} account. address: {:?}", STATE_ACCOUNT_NAME, state_account_address);
+            return None;
+        }
+
+        match ChainState::deserialize(&mut &state_account.data[8..]) {
+            Ok(state) if state.challenge_period == 0 => {
+                error!("state account has no challenge period. address: {:?}", state_account_address);
+                None
+            }
+            Ok(state) => Some(state),
+            Err(err) => {
+                error!("deserialize state account fail. err: {:?}", err);
+                None
+            }
+        }
+    }
 
     pub fn find_state_account_address(&self) -> Pubkey {
         return ChainBasicService::find_state_account_address(self.program_id).0;
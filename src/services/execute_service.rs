@@ -3,13 +3,20 @@ use crate::common::node_error::NodeError;
 use crate::contract::chain_brief::ChainBrief;
 use crate::models::account_audit_row::AccountAuditRow;
 use crate::models::brief_model::convert_chain_briefs_to_brief_records;
+use crate::models::challenge_model::{ChallengeKind, ChallengeRecord};
+use crate::models::root_commitment_model::{RootCommitmentRecord, RootCommitmentRow};
+use crate::models::root_observation_model::{RootObservationRecord, RootObservationRow};
 use crate::models::transaction_model::TransactionRow;
-use crate::models::bridge_transaction_model::{BridgeTxInfo, BridgeTxRecord, BridgeTxRow, MessageType};
+use crate::models::bridge_transaction_model::{BridgeLifecycle, BridgeTxInfo, BridgeTxRecord, BridgeTxRow, CommitmentWindow, MessageType};
 use crate::repositories::account_audit_repo::AccountAuditRepo;
 use crate::repositories::block_repo::BlockRepo;
 use crate::repositories::bridge_tx_repo::BridgeTxRepo;
 use crate::repositories::brief_repo::BriefRepo;
 use crate::repositories::chain_repo::ChainRepo;
+use crate::repositories::challenge_repo::ChallengeRepo;
+use crate::repositories::root_commitment_repo::RootCommitmentRepo;
+use crate::repositories::root_observation_repo::RootObservationRepo;
+use crate::repositories::scan_checkpoint_repo::ScanCheckpointRepo;
 use crate::repositories::transaction_repo::TransactionRepo;
 use crate::utils::store_util::{create_one, create_pool, PgConnectionPool};
 use crate::utils::time_util;
@@ -187,6 +194,21 @@ impl ExecuteService {
         repo.upsert(slot);
     }
 
+    pub fn update_scan_checkpoint(&self, name: &str, slot: i64) -> Result<(), NodeError> {
+        let repo = ScanCheckpointRepo { pool: Box::from(self.client_pool.to_owned()) };
+
+        repo.upsert(name, slot)?;
+
+        Ok(())
+    }
+
+    /// The slot of the checkpoint `name` in postgres, 0 before it is written.
+    pub fn get_scan_checkpoint(&self, name: &str) -> Result<i64, NodeError> {
+        let repo = ScanCheckpointRepo { pool: Box::from(self.client_pool.to_owned()) };
+
+        Ok(repo.find(name)?.map_or(0, |row| row.slot))
+    }
+
     pub fn insert_briefs(&self, chain_briefs: Vec<ChainBrief>) -> Result<u32, NodeError> {
         let repo = BriefRepo { pool: Box::from(self.client_pool.to_owned()) };
 
@@ -199,6 +221,19 @@ impl ExecuteService {
         Ok(count)
     }
 
+    pub fn record_challenge(&self, slot: i64, local_root: Vec<u8>, chain_root: Vec<u8>) -> Result<u32, NodeError> {
+        let brief_repo = BriefRepo { pool: Box::from(self.client_pool.to_owned()) };
+        let repo = ChallengeRepo { pool: Box::from(self.client_pool.to_owned()) };
+
+        // the brief of the disputed slot backs the challenge, it may not have been produced yet
+        let brief = brief_repo.find_by_slot(slot).ok();
+        let record = ChallengeRecord::pending(slot, ChallengeKind::MerkleRoot, local_root, chain_root, brief);
+
+        let rows = repo.insert(record)?;
+
+        Ok(rows.len() as u32)
+    }
+
     pub fn filter_bridge_tx(&mut self, start_slot: i64, end_slot: i64) -> Result<Vec<BridgeTxRecord>, NodeError> {
         if end_slot < start_slot {
             error!("end_slot should greater than or equal start_slot  start_slot: {:?},end_slot: {:?}",
@@ -300,6 +335,15 @@ impl ExecuteService {
             is_generated_proof: false,
             root_program_slot: 0 as i64,
             current_mt_root: vec![],
+            sender: bridge_tx_info.from.to_string(),
+            recipient: bridge_tx_info.to.to_string(),
+            amount: bridge_tx_info.amount as i64,
+            bridge_tx_index: bridge_tx_info.bridge_tx_index as i64,
+            l1_signature: String::new(),
+            proven_on: None,
+            l1_commit_slot: 0,
+            committed_on: None,
+            claimable_on: None,
         })
     }
     pub fn insert_bridge_txs(&self, bridge_txs: Vec<BridgeTxRecord>) -> Result<u32, NodeError> {
@@ -311,16 +355,16 @@ impl ExecuteService {
         Ok(count)
     }
 
-    pub fn brige_txs_hashes(&self, from_slot: i64, to_slot: i64) -> Result<Vec<Vec<u8>>, NodeError> {
+    pub fn brige_txs_hashes(&self, window: CommitmentWindow) -> Result<Vec<Vec<u8>>, NodeError> {
         let repo = BridgeTxRepo{pool: Box::from(self.client_pool.to_owned())};
 
-        repo.bridge_tx_hashes(from_slot, to_slot)
+        repo.bridge_tx_hashes(window)
     }
 
-    pub fn bridge_tx_range(&self, from_slot: i64, to_slot: i64) -> Result<Vec<BridgeTxRecord>, NodeError>{
+    pub fn bridge_tx_range(&self, window: CommitmentWindow) -> Result<Vec<BridgeTxRecord>, NodeError>{
         let repo = BridgeTxRepo{pool: Box::from(self.client_pool.to_owned())};
 
-        let bridge_tx_rows = repo.range(from_slot, to_slot).unwrap();
+        let bridge_tx_rows = repo.range(window)?;
 
         let bridge_tx_records = bridge_tx_rows.into_iter().map(BridgeTxRecord::from).collect();
         
@@ -335,6 +379,71 @@ impl ExecuteService {
         
         Ok(row)
     }
-}
 
+    pub fn bridge_tx_transition(&self, signature: &str, to: BridgeLifecycle) -> Result<BridgeTxRow, NodeError> {
+        let repo = BridgeTxRepo{pool: Box::from(self.client_pool.to_owned())};
+
+        repo.transition(signature, to)
+    }
+
+    /// Prove `bridge_txs` against the root of `commitment` and record the commitment, all or nothing.
+    pub fn commit_proofs(&self, commitment: &RootCommitmentRecord, bridge_txs: &[BridgeTxRecord]) -> Result<RootCommitmentRow, NodeError> {
+        let repo = BridgeTxRepo { pool: Box::from(self.client_pool.to_owned()) };
+
+        repo.commit_proofs(commitment, bridge_txs)
+    }
+
+    /// The `limit` latest root commitments, highest slot first.
+    pub fn recent_root_commitments(&self, limit: i64) -> Result<Vec<RootCommitmentRow>, NodeError> {
+        let repo = RootCommitmentRepo { pool: Box::from(self.client_pool.to_owned()) };
+
+        repo.latest(limit)
+    }
 
+    /// The root commitments of `slot` and later, highest slot first.
+    pub fn root_commitments_since(&self, slot: i64) -> Result<Vec<RootCommitmentRow>, NodeError> {
+        let repo = RootCommitmentRepo { pool: Box::from(self.client_pool.to_owned()) };
+
+        repo.since(slot)
+    }
+
+    pub fn last_root_commitment_before(&self, slot: i64) -> Result<Option<RootCommitmentRow>, NodeError> {
+        let repo = RootCommitmentRepo { pool: Box::from(self.client_pool.to_owned()) };
+
+        repo.last_before(slot)
+    }
+
+    pub fn observe_root(&self, slot: i64, merkle_tree_root: Vec<u8>) -> Result<RootObservationRow, NodeError> {
+        let repo = RootObservationRepo { pool: Box::from(self.client_pool.to_owned()) };
+
+        repo.observe(RootObservationRecord { slot, merkle_tree_root })
+    }
+
+    pub fn root_observations(&self, slot: i64) -> Result<Vec<RootObservationRow>, NodeError> {
+        let repo = RootObservationRepo { pool: Box::from(self.client_pool.to_owned()) };
+
+        repo.by_slot(slot)
+    }
+
+    /// The commitments whose root is not finalized yet, lowest slot first.
+    pub fn provisional_root_commitments(&self, limit: i64) -> Result<Vec<RootCommitmentRow>, NodeError> {
+        let repo = RootCommitmentRepo { pool: Box::from(self.client_pool.to_owned()) };
+
+        repo.provisional(limit)
+    }
+
+    /// Promote the commitment of `slot` and the proofs against its root to final.
+    pub fn finalize_root(&self, slot: i64) -> Result<usize, NodeError> {
+        let repo = BridgeTxRepo { pool: Box::from(self.client_pool.to_owned()) };
+
+        repo.finalize_root(slot)
+    }
+
+    /// Void the proofs and commitments of the roots of `slot` and later. Returns the invalidated
+    /// messages and the claimed or being claimed ones that were kept.
+    pub fn invalidate_roots_from(&self, slot: i64) -> Result<(Vec<BridgeTxRow>, Vec<BridgeTxRow>), NodeError> {
+        let repo = BridgeTxRepo { pool: Box::from(self.client_pool.to_owned()) };
+
+        repo.invalidate_from(slot)
+    }
+}
@@ -2,8 +2,11 @@ pub mod chain_brief_service;
 pub mod chain_basic_service;
 pub mod chain_tally_service;
 pub mod chain_root_mgr_service;
+pub mod chain_challenge_service;
+pub mod chain_claim_service;
 
 pub mod execute_service;
 pub mod chain_state_service;
 pub mod chain_service;
-
+pub mod proof_service;
+pub mod chain_slots_feed;
@@ -0,0 +1,351 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use dd_merkle_tree::{HashingAlgorithm, MerkleTree};
+
+use crate::common::node_error::NodeError;
+use crate::models::bridge_transaction_model::{BridgeLifecycle, BridgeTxRow, ClaimBacklog, ClaimStatus, CommitmentWindow, WithdrawalPage, WithdrawalProof, WithdrawalQuery, WithdrawalSummary};
+use crate::models::bridge_transition_model::BridgeTransitionRow;
+use crate::models::root_commitment_model::RootCommitmentRow;
+use crate::models::scan_checkpoint_model::{ScanProgress, FILTER_CHECKPOINT, MONITOR_TARGET};
+use crate::repositories::block_repo::BlockRepo;
+use crate::repositories::bridge_tx_repo::BridgeTxRepo;
+use crate::repositories::root_commitment_repo::RootCommitmentRepo;
+use crate::repositories::scan_checkpoint_repo::ScanCheckpointRepo;
+use crate::utils::store_util::PgConnectionPool;
+use crate::utils::uuid_util::generate_uuid;
+
+// the most trees of past roots kept, the oldest built is dropped first
+const TREE_CACHE_SIZE: usize = 16;
+
+/// Look up withdrawals and their proofs in `bridge_transaction`.
+///
+/// Clones share the cache of trees built for past roots.
+#[derive(Clone)]
+pub struct ProofService {
+    pub pool: PgConnectionPool,
+    tree_cache: Arc<Mutex<TreeCache<ProofTree>>>,
+}
+
+impl ProofService {
+    pub fn new(pool: PgConnectionPool) -> Self {
+        ProofService {
+            pool,
+            tree_cache: Arc::new(Mutex::new(TreeCache::new(TREE_CACHE_SIZE))),
+        }
+    }
+
+    pub fn find_by_signature(&self, signature: &str) -> Result<Option<WithdrawalProof>, NodeError> {
+        let repo = BridgeTxRepo { pool: Box::from(self.pool.to_owned()) };
+
+        let row = repo.find_by_signature(signature)?;
+
+        self.to_proof(&repo, row)
+    }
+
+    pub fn find_by_leaf_hash(&self, leaf_hash: &[u8]) -> Result<Option<WithdrawalProof>, NodeError> {
+        let repo = BridgeTxRepo { pool: Box::from(self.pool.to_owned()) };
+
+        let row = repo.find_by_tx_info_hash(leaf_hash)?;
+
+        self.to_proof(&repo, row)
+    }
+
+    pub fn find_by_leaf_index(&self, leaf_index: i64) -> Result<Option<WithdrawalProof>, NodeError> {
+        let repo = BridgeTxRepo { pool: Box::from(self.pool.to_owned()) };
+
+        let row = repo.find_by_leaf_index(leaf_index)?;
+
+        Ok(row.map(|row| WithdrawalProof::new(&row, leaf_index)))
+    }
+
+    pub fn find_row_by_leaf_hash(&self, leaf_hash: &[u8]) -> Result<Option<(BridgeTxRow, i64)>, NodeError> {
+        let repo = BridgeTxRepo { pool: Box::from(self.pool.to_owned()) };
+
+        match repo.find_by_tx_info_hash(leaf_hash)? {
+            Some(row) => {
+                let leaf_index = repo.leaf_index(&row)?;
+                Ok(Some((row, leaf_index)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub fn find_row_by_leaf_index(&self, leaf_index: i64) -> Result<Option<(BridgeTxRow, i64)>, NodeError> {
+        let repo = BridgeTxRepo { pool: Box::from(self.pool.to_owned()) };
+
+        Ok(repo.find_by_leaf_index(leaf_index)?.map(|row| (row, leaf_index)))
+    }
+
+    /// The latest root committed at or before L1 slot `l1_slot`.
+    pub fn commitment_at(&self, l1_slot: i64) -> Result<Option<RootCommitmentRow>, NodeError> {
+        let repo = RootCommitmentRepo { pool: Box::from(self.pool.to_owned()) };
+
+        repo.latest_at_l1_slot(l1_slot)
+    }
+
+    /// Prove the leaf of `row` against the root of `commitment`, which must cover it.
+    ///
+    /// The tree is rebuilt from the messages through the slot of the commitment, so the proof holds
+    /// against that root whichever root the message was proven against. Trees are cached by
+    /// commitment slot and root, a rewritten root is a different entry.
+    pub fn prove_against(&self, row: &BridgeTxRow, leaf_index: i64, commitment: &RootCommitmentRow) -> Result<WithdrawalProof, NodeError> {
+        let key = (commitment.slot, commitment.merkle_tree_root.clone());
+        if let Some(tree) = self.tree_cache.lock().unwrap().get_mut(&key) {
+            let proof = tree.prove(leaf_index as usize, &row.tx_info_hash)?;
+            return Ok(WithdrawalProof::against(row, leaf_index, commitment, &proof));
+        }
+
+        // built without the lock, a concurrent miss of the same root builds it twice
+        let repo = BridgeTxRepo { pool: Box::from(self.pool.to_owned()) };
+        let hashes = repo.bridge_tx_hashes(CommitmentWindow::through(commitment.slot))?;
+        let mut tree = ProofTree::build(hashes)?;
+        if tree.root != commitment.merkle_tree_root {
+            return Err(NodeError::new(generate_uuid(), format!(
+                "rebuilt root differs from the committed root. slot: {}, rebuilt: {}, committed: {}",
+                commitment.slot, hex::encode(&tree.root), hex::encode(&commitment.merkle_tree_root))));
+        }
+        let proof = tree.prove(leaf_index as usize, &row.tx_info_hash)?;
+
+        self.tree_cache.lock().unwrap().insert(key, tree);
+
+        Ok(WithdrawalProof::against(row, leaf_index, commitment, &proof))
+    }
+
+    /// A withdrawal row with its leaf index in the bridge merkle tree.
+    pub fn find_row_by_signature(&self, signature: &str) -> Result<Option<(BridgeTxRow, i64)>, NodeError> {
+        let repo = BridgeTxRepo { pool: Box::from(self.pool.to_owned()) };
+
+        match repo.find_by_signature(signature)? {
+            Some(row) => {
+                let leaf_index = repo.leaf_index(&row)?;
+                Ok(Some((row, leaf_index)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub fn history(&self, query: &WithdrawalQuery) -> Result<WithdrawalPage, NodeError> {
+        let repo = BridgeTxRepo { pool: Box::from(self.pool.to_owned()) };
+
+        let rows = repo.history(query)?;
+
+        // a short page is the last one
+        let next_cursor = match rows.last() {
+            Some(last) if rows.len() as i64 == query.limit => Some(format!("{}:{}", last.slot, last.id)),
+            _ => None,
+        };
+
+        Ok(WithdrawalPage {
+            items: rows.iter().map(WithdrawalSummary::from).collect(),
+            next_cursor,
+        })
+    }
+
+    /// Proven withdrawals in `claim_status`, oldest first, after the `(slot, id)` cursor.
+    pub fn claim_backlog(&self, claim_status: ClaimStatus, cursor: (i64, i64), limit: i64) -> Result<ClaimBacklog, NodeError> {
+        let repo = BridgeTxRepo { pool: Box::from(self.pool.to_owned()) };
+
+        let total = repo.count_by_claim_status(claim_status)?;
+        let rows = repo.by_claim_status(claim_status, cursor, limit)?;
+
+        let next_cursor = match rows.last() {
+            Some(last) if rows.len() as i64 == limit => Some(format!("{}:{}", last.slot, last.id)),
+            _ => None,
+        };
+
+        Ok(ClaimBacklog {
+            claim_status: claim_status.as_str().to_string(),
+            total,
+            items: rows.iter().map(WithdrawalSummary::from).collect(),
+            next_cursor,
+        })
+    }
+
+    pub fn transitions(&self, signature: &str) -> Result<Vec<BridgeTransitionRow>, NodeError> {
+        let repo = BridgeTxRepo { pool: Box::from(self.pool.to_owned()) };
+
+        repo.transitions(signature)
+    }
+
+    /// Messages per lifecycle status, every status included.
+    pub fn count_by_status(&self) -> Result<Vec<(BridgeLifecycle, i64)>, NodeError> {
+        let repo = BridgeTxRepo { pool: Box::from(self.pool.to_owned()) };
+
+        let counts: HashMap<BridgeLifecycle, i64> = repo.count_by_status()?.into_iter().collect();
+
+        Ok(BridgeLifecycle::ALL.iter().map(|s| (*s, counts.get(s).copied().unwrap_or(0))).collect())
+    }
+
+    pub fn scan_progress(&self) -> Result<ScanProgress, NodeError> {
+        let repo = ScanCheckpointRepo { pool: Box::from(self.pool.to_owned()) };
+        let mut block_repo = BlockRepo { pool: Box::from(self.pool.to_owned()) };
+
+        Ok(ScanProgress {
+            filter_slot: repo.find(FILTER_CHECKPOINT)?.map_or(0, |row| row.slot),
+            monitor_target_slot: repo.find(MONITOR_TARGET)?.map_or(0, |row| row.slot),
+            head_slot: block_repo.show().map_or(0, |row| row.slot),
+        })
+    }
+
+    fn to_proof(&self, repo: &BridgeTxRepo, row: Option<BridgeTxRow>) -> Result<Option<WithdrawalProof>, NodeError> {
+        match row {
+            Some(row) => {
+                let leaf_index = repo.leaf_index(&row)?;
+                Ok(Some(WithdrawalProof::new(&row, leaf_index)))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// The merklized tree of the messages through a commitment.
+struct ProofTree {
+    tree: MerkleTree,
+    leaves: Vec<Vec<u8>>,
+    root: Vec<u8>,
+}
+
+impl ProofTree {
+    fn build(hashes: Vec<Vec<u8>>) -> Result<Self, NodeError> {
+        let leaves = hashes.clone();
+        let mut tree = MerkleTree::new(HashingAlgorithm::Sha256d, 32);
+        tree.add_hashes(hashes)
+            .map_err(|e| NodeError::new(generate_uuid(), format!("add tree leaves fail: {:?}", e)))?;
+        tree.merklize()
+            .map_err(|e| NodeError::new(generate_uuid(), format!("merklize tree fail: {:?}", e)))?;
+
+        let root = tree.get_merkle_root()
+            .map_err(|e| NodeError::new(generate_uuid(), format!("get tree root fail: {:?}", e)))?;
+
+        Ok(ProofTree { tree, leaves, root })
+    }
+
+    /// The hex encoded proof of the leaf at `leaf_index`, which must be `leaf_hash`.
+    fn prove(&mut self, leaf_index: usize, leaf_hash: &[u8]) -> Result<String, NodeError> {
+        match self.leaves.get(leaf_index) {
+            Some(leaf) if leaf.as_slice() == leaf_hash => {}
+            Some(leaf) => return Err(NodeError::new(generate_uuid(), format!("leaf {} of the tree is {}, not {}",
+                leaf_index, hex::encode(leaf), hex::encode(leaf_hash)))),
+            None => return Err(NodeError::new(generate_uuid(), format!("leaf {} is not in a tree of {} leaves", leaf_index, self.leaves.len()))),
+        }
+
+        let proof = self.tree.merkle_proof_index(leaf_index)
+            .map_err(|e| NodeError::new(generate_uuid(), format!("prove leaf {} fail: {:?}", leaf_index, e)))?;
+
+        Ok(hex::encode(proof.get_pairing_hashes()))
+    }
+}
+
+// (commitment slot, root)
+type TreeKey = (i64, Vec<u8>);
+
+/// Trees of past roots, the oldest inserted dropped once `capacity` is reached.
+struct TreeCache<T> {
+    capacity: usize,
+    trees: HashMap<TreeKey, T>,
+    order: VecDeque<TreeKey>,
+}
+
+impl<T> TreeCache<T> {
+    fn new(capacity: usize) -> Self {
+        TreeCache { capacity, trees: HashMap::new(), order: VecDeque::new() }
+    }
+
+    fn get_mut(&mut self, key: &TreeKey) -> Option<&mut T> {
+        self.trees.get_mut(key)
+    }
+
+    fn insert(&mut self, key: TreeKey, tree: T) {
+        if self.trees.insert(key.clone(), tree).is_some() {
+            return;
+        }
+        self.order.push_back(key);
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.trees.remove(&oldest);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use dd_merkle_tree::{HashingAlgorithm, MerkleTree};
+
+    use crate::models::bridge_transaction_model::CommitmentWindow;
+    use crate::services::proof_service::{ProofTree, TreeCache};
+
+    #[test]
+    fn test_proof_tree() {
+        let hashes: Vec<Vec<u8>> = (0..5u8).map(|i| vec![i; 32]).collect();
+
+        // a leaf proven against an earlier, smaller tree and the latest one
+        let mut tree_3 = ProofTree::build(hashes[..3].to_vec()).unwrap();
+        let mut tree_5 = ProofTree::build(hashes.clone()).unwrap();
+        assert_ne!(tree_3.root, tree_5.root);
+        let proof_3 = tree_3.prove(1, &hashes[1]).unwrap();
+        assert_ne!(proof_3, tree_5.prove(1, &hashes[1]).unwrap());
+
+        let mut tree = MerkleTree::new(HashingAlgorithm::Sha256d, 32);
+        tree.add_hashes(hashes[..3].to_vec()).unwrap();
+        tree.merklize().unwrap();
+        assert_eq!(tree_3.root, tree.get_merkle_root().unwrap());
+        assert_eq!(proof_3, hex::encode(tree.merkle_proof_index(1).unwrap().get_pairing_hashes()));
+
+        // a cached tree proves every leaf again
+        assert_eq!(tree_3.prove(1, &hashes[1]).unwrap(), proof_3);
+        assert_eq!(tree_3.prove(0, &hashes[0]).unwrap(), hex::encode(tree.merkle_proof_index(0).unwrap().get_pairing_hashes()));
+
+        // a leaf after the commitment is not covered by its root, an index of another leaf proves nothing
+        assert!(tree_3.prove(3, &hashes[3]).is_err());
+        assert!(tree_3.prove(2, &hashes[1]).is_err());
+    }
+
+    // a withdrawal proven against a commitment before the latest one, next to a slot 0 row no tree holds
+    #[test]
+    fn test_prove_against_earlier_commitment() {
+        // (slot, leaf hash) in (slot, id) order
+        let rows: Vec<(i64, Vec<u8>)> = vec![(0, vec![9u8; 32]), (3, vec![1u8; 32]), (5, vec![2u8; 32]), (9, vec![3u8; 32])];
+        let hashes = |window: CommitmentWindow| -> Vec<Vec<u8>> {
+            rows.iter().filter(|(slot, _)| window.contains(*slot)).map(|(_, hash)| hash.clone()).collect()
+        };
+        // counted over the same windows as `BridgeTxRepo::leaf_index`
+        let leaf_index = |position: usize| rows[..position].iter().filter(|(slot, _)| CommitmentWindow::all().contains(*slot)).count();
+
+        let mut earlier = ProofTree::build(hashes(CommitmentWindow::through(5))).unwrap();
+        let mut latest = ProofTree::build(hashes(CommitmentWindow::through(9))).unwrap();
+        assert_ne!(earlier.root, latest.root);
+
+        let (_, leaf_hash) = &rows[2];
+        assert_eq!(leaf_index(2), 1);
+        let proof = earlier.prove(leaf_index(2), leaf_hash).unwrap();
+
+        let mut tree = MerkleTree::new(HashingAlgorithm::Sha256d, 32);
+        tree.add_hashes(vec![vec![1u8; 32], vec![2u8; 32]]).unwrap();
+        tree.merklize().unwrap();
+        assert_eq!(earlier.root, tree.get_merkle_root().unwrap());
+        assert_eq!(proof, hex::encode(tree.merkle_proof_index(1).unwrap().get_pairing_hashes()));
+        assert_ne!(proof, latest.prove(leaf_index(2), leaf_hash).unwrap());
+
+        // counting the slot 0 row would name a leaf the earlier tree does not have
+        assert!(earlier.prove(2, leaf_hash).is_err());
+    }
+
+    #[test]
+    fn test_tree_cache() {
+        let mut cache = TreeCache::new(2);
+        cache.insert((1, vec![1]), "a".to_string());
+        cache.insert((2, vec![2]), "b".to_string());
+        cache.insert((1, vec![1]), "a".to_string());
+        assert_eq!(cache.get_mut(&(1, vec![1])).cloned(), Some("a".to_string()));
+
+        cache.insert((3, vec![3]), "c".to_string());
+        assert_eq!(cache.get_mut(&(1, vec![1])), None);
+        assert_eq!(cache.get_mut(&(2, vec![2])).cloned(), Some("b".to_string()));
+        assert_eq!(cache.get_mut(&(3, vec![3])).cloned(), Some("c".to_string()));
+
+        // the same slot with a rewritten root is another tree
+        assert_eq!(cache.get_mut(&(3, vec![4])), None);
+    }
+}
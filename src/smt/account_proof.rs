@@ -0,0 +1,189 @@
+use std::sync::Arc;
+
+use rocksdb::DB;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use sparse_merkle_tree::traits::{StoreReadOps, Value};
+use sparse_merkle_tree::H256;
+
+use crate::common::node_error::NodeError;
+use crate::smt::account_smt::{DatabaseStoreAccountSMT, SMTAccount};
+use crate::smt::rocks_store::RocksStore;
+use crate::smt::state_tree::{commitment_root, latest_commitment};
+use crate::utils::uuid_util::generate_uuid;
+
+/// Inclusion (or exclusion) proof of an L2 account against the `world_state_root` of an L1 commitment.
+///
+/// `proof` is a compiled sparse merkle tree proof of the single leaf (`leaf_key`, `leaf_value`),
+/// it is checked on L1 with `verify-smt` against `world_state_root`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AccountProof {
+    pub slot: u64,
+    pub pubkey: String,
+    pub world_state_root: [u8; 32],
+    pub leaf_key: [u8; 32],
+    // zero when the account does not exist at `slot`
+    pub leaf_value: [u8; 32],
+    // `SMTAccount::to_vec` of the account, empty when the account does not exist at `slot`
+    pub account: Vec<u8>,
+    pub proof: Vec<u8>,
+}
+
+impl AccountProof {
+    /// Check the proof the way L1 does, with `verify-smt`.
+    pub fn verify(&self) -> Result<bool, NodeError> {
+        let leaves = vec![(verify_smt::H256::from(self.leaf_key), verify_smt::H256::from(self.leaf_value))];
+
+        verify_smt::CompiledMerkleProof(self.proof.clone())
+            .verify::<verify_smt::blake2b::Blake2bHasher>(&verify_smt::H256::from(self.world_state_root), leaves)
+            .map_err(|e| NodeError::new(generate_uuid(), format!("verify account proof fail. pubkey: {}, err: {:?}", self.pubkey, e)))
+    }
+}
+
+#[derive(Clone)]
+pub struct AccountProver {
+    db: Arc<DB>,
+}
+
+impl AccountProver {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self { db }
+    }
+
+    /// The latest L1 commitment whose world state was verified.
+    pub fn latest_slot(&self) -> Result<Option<u64>, NodeError> {
+        Ok(latest_commitment(&self.db)?.map(|(slot, _)| slot))
+    }
+
+    pub fn is_committed(&self, slot: u64) -> Result<bool, NodeError> {
+        Ok(commitment_root(&self.db, slot)?.is_some())
+    }
+
+    /// Prove the state of `pubkey` at the L1 commitment `slot`.
+    pub fn prove(&self, pubkey: &Pubkey, slot: u64) -> Result<AccountProof, NodeError> {
+        let committed_root = commitment_root(&self.db, slot)?.ok_or_else(|| {
+            NodeError::new(generate_uuid(), format!("state of slot {} is not committed or was pruned", slot))
+        })?;
+
+        let smt = DatabaseStoreAccountSMT::new_with_store(RocksStore::at_version(self.db.clone(), slot))
+            .map_err(|e| NodeError::new(generate_uuid(), format!("load state tree fail. err: {:?}", e)))?;
+        let root: [u8; 32] = smt.root().clone().into();
+        if root != committed_root {
+            return Err(NodeError::new(generate_uuid(),
+                format!("state tree of slot {} doesn't match its commitment", slot)));
+        }
+
+        let leaf_key = SMTAccount { pubkey: *pubkey, ..Default::default() }.smt_key();
+        let leaf: Option<SMTAccount> = smt.store().get_leaf(&leaf_key)
+            .map_err(|e| NodeError::new(generate_uuid(), format!("read account leaf fail. err: {:?}", e)))?;
+        let (leaf_value, account) = match leaf {
+            Some(account) => (account.to_h256(), account.to_vec()),
+            None => (H256::zero(), vec![]),
+        };
+
+        let proof = smt.merkle_proof(vec![leaf_key])
+            .and_then(|proof| proof.compile(vec![leaf_key]))
+            .map_err(|e| NodeError::new(generate_uuid(), format!("generate account proof fail. err: {:?}", e)))?;
+
+        Ok(AccountProof {
+            slot,
+            pubkey: pubkey.to_string(),
+            world_state_root: root,
+            leaf_key: leaf_key.into(),
+            leaf_value: leaf_value.into(),
+            account,
+            proof: proof.0,
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use solana_sdk::pubkey::Pubkey;
+
+    use crate::models::account_audit_row::AccountAuditRow;
+    use crate::smt::state_tree::StateTree;
+
+    fn prepare_row(pubkey: &Pubkey, lamports: i64, slot: i64, write_version: i64) -> AccountAuditRow {
+        AccountAuditRow {
+            id: write_version,
+            pubkey: pubkey.to_bytes().to_vec(),
+            owner: Some(Pubkey::default().to_bytes().to_vec()),
+            lamports,
+            slot,
+            executable: false,
+            rent_epoch: 0,
+            data: Some(vec![1, 2, 3]),
+            write_version,
+            txn_signature: None,
+            updated_on: chrono::Utc::now().naive_utc(),
+        }
+    }
+
+    #[test]
+    fn test_prove_account() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut state_tree = StateTree::open(dir.path()).unwrap();
+
+        let alice = Pubkey::new_unique();
+        let bob = Pubkey::new_unique();
+        let rows = vec![
+            prepare_row(&alice, 10, 5, 1),
+            prepare_row(&bob, 20, 5, 2),
+            prepare_row(&alice, 30, 5, 3),
+        ];
+        state_tree.apply(5, &rows).unwrap();
+        state_tree.commit(5).unwrap();
+        let root5 = state_tree.root();
+
+        // a later slot doesn't change the proofs of a committed one
+        state_tree.apply(6, &vec![prepare_row(&alice, 40, 6, 4)]).unwrap();
+        assert_eq!(state_tree.root_at(5).unwrap(), root5);
+        state_tree.commit(6).unwrap();
+
+        let prover = state_tree.prover();
+        let proof = prover.prove(&alice, 5).unwrap();
+        assert_eq!(proof.world_state_root, root5);
+        assert_eq!(proof.account, rows[2].to_smt_account().unwrap().to_vec());
+
+        assert!(proof.verify().unwrap());
+
+        let absent = prover.prove(&Pubkey::new_unique(), 5).unwrap();
+        assert!(absent.account.is_empty());
+        assert!(absent.verify().unwrap());
+
+        // a proof doesn't hold against another account state
+        let mut forged = proof.clone();
+        forged.leaf_value = absent.leaf_value;
+        assert!(!forged.verify().unwrap_or(false));
+
+        let latest = prover.prove(&alice, 6).unwrap();
+        assert_eq!(latest.world_state_root, state_tree.root());
+        assert_ne!(latest.leaf_value, proof.leaf_value);
+
+        assert!(prover.prove(&alice, 4).is_err());
+        assert!(!prover.is_committed(4).unwrap());
+        assert_eq!(prover.latest_slot().unwrap(), Some(6));
+    }
+
+    #[test]
+    fn test_apply_invalid_row() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut state_tree = StateTree::open(dir.path()).unwrap();
+
+        let alice = Pubkey::new_unique();
+        state_tree.apply(5, &vec![prepare_row(&alice, 10, 5, 1)]).unwrap();
+        state_tree.commit(5).unwrap();
+        let root5 = state_tree.root();
+
+        // a row without owner is not a complete account, the tree stays at the previous slot
+        let mut row = prepare_row(&alice, 20, 6, 2);
+        row.owner = None;
+        assert!(state_tree.apply(6, &vec![row]).is_err());
+        assert_eq!(state_tree.applied_slot().unwrap(), 5);
+        assert_eq!(state_tree.root(), root5);
+
+        assert_eq!(state_tree.committed_slot().unwrap(), Some(5));
+        assert!(state_tree.root_at(6).is_err());
+    }
+}
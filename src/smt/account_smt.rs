@@ -4,9 +4,13 @@ use sparse_merkle_tree::{blake2b::Blake2bHasher, H256, SparseMerkleTree, traits:
 use sparse_merkle_tree::default_store::DefaultStore;
 
 use crate::smt::rocks_store::RocksStore;
+use crate::smt::serde::{check_len, split_version, DecodeError, FORMAT_VERSION};
 
 pub const PUBKEY_BYTES: usize = 32;
 
+// pubkey, lamports, owner, executable and rent_epoch, followed by data
+pub const FIXED_BYTES: usize = PUBKEY_BYTES + 8 + PUBKEY_BYTES + 1 + 8;
+
 #[derive(Default, Clone, Debug)]
 pub struct SMTAccount {
     pub pubkey: Pubkey,
@@ -62,55 +66,91 @@ impl Value for SMTAccount {
     }
 }
 
-impl From<Vec<u8>> for SMTAccount {
-    fn from(bytes: Vec<u8>) -> Self {
+impl SMTAccount {
+    /// Decode the layout of `to_vec`, records written before format versions use it as is.
+    pub fn from_unversioned(bytes: &[u8]) -> Result<Self, DecodeError> {
+        check_len(bytes, FIXED_BYTES)?;
+
         let mut index = 0;
-        let pubkey = Pubkey::try_from(&bytes[0..PUBKEY_BYTES]).unwrap();
-        index = PUBKEY_BYTES;
-
-        let lamports_bytes: [u8; 8] =
-            bytes[index..index + 8]
-                .to_vec()
-                .try_into()
-                .unwrap_or_else(|v: Vec<u8>| {
-                    panic!("Expected a Vec of length {} but it was {}", 8, v.len())
-                });
-        let lamports = i64::from_le_bytes(lamports_bytes);
+        let pubkey = Pubkey::try_from(&bytes[index..index + PUBKEY_BYTES]).expect("checked slice");
+        index += PUBKEY_BYTES;
+
+        let lamports = i64::from_le_bytes(bytes[index..index + 8].try_into().expect("checked slice"));
         index += 8;
 
-        let owner = Pubkey::try_from(&bytes[index..index + PUBKEY_BYTES]).unwrap();
+        let owner = Pubkey::try_from(&bytes[index..index + PUBKEY_BYTES]).expect("checked slice");
         index += PUBKEY_BYTES;
 
         let executable = bytes[index] != 0;
         index += 1;
 
-        let rent_epoch_bytes: [u8; 8] =
-            bytes[index..index + 8]
-                .to_vec()
-                .try_into()
-                .unwrap_or_else(|v: Vec<u8>| {
-                    panic!("Expected a Vec of length {} but it was {}", 8, v.len())
-                });
-        let rent_epoch = i64::from_le_bytes(rent_epoch_bytes);
+        let rent_epoch = i64::from_le_bytes(bytes[index..index + 8].try_into().expect("checked slice"));
         index += 8;
 
         let data = bytes[index..].to_vec();
-        Self {
+        Ok(Self {
             pubkey,
             lamports,
             owner,
             executable,
             rent_epoch,
             data,
-        }
+        })
+    }
+}
+
+impl TryFrom<Vec<u8>> for SMTAccount {
+    type Error = DecodeError;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        Self::from_unversioned(split_version(&bytes)?)
     }
 }
 
 impl Into<Vec<u8>> for SMTAccount {
     fn into(self) -> Vec<u8> {
-        self.to_vec()
+        let mut bytes = vec![FORMAT_VERSION];
+        bytes.extend_from_slice(&self.to_vec());
+        bytes
     }
 }
 
 pub type DatabaseStoreAccountSMT = SparseMerkleTree<Blake2bHasher, SMTAccount, RocksStore<SMTAccount>>;
 pub type MemoryStoreAccountSMT = SparseMerkleTree<Blake2bHasher, SMTAccount, DefaultStore<SMTAccount>>;
+
+
+#[cfg(test)]
+pub mod test {
+    use solana_sdk::pubkey::Pubkey;
+
+    use crate::smt::account_smt::{SMTAccount, FIXED_BYTES};
+    use crate::smt::serde::DecodeError;
+
+    fn prepare_account() -> SMTAccount {
+        SMTAccount {
+            pubkey: Pubkey::new_unique(),
+            lamports: 10,
+            owner: Pubkey::new_unique(),
+            executable: true,
+            rent_epoch: 3,
+            data: vec![1, 2, 3],
+        }
+    }
+
+    #[test]
+    fn test_convert() {
+        let account = prepare_account();
+        let data: Vec<u8> = account.clone().into();
+        let decoded = SMTAccount::try_from(data).unwrap();
+        assert_eq!(decoded.to_vec(), account.to_vec());
+    }
+
+    #[test]
+    fn test_corrupt() {
+        let data: Vec<u8> = prepare_account().into();
+        assert_eq!(SMTAccount::try_from(vec![]).unwrap_err(), DecodeError::Empty);
+        assert_eq!(SMTAccount::try_from(data[1..].to_vec()).unwrap_err(), DecodeError::UnsupportedVersion(data[1]));
+        assert_eq!(SMTAccount::try_from(data[..10].to_vec()).unwrap_err(),
+                   DecodeError::TooShort { expected: FIXED_BYTES, actual: 9 });
+    }
+}
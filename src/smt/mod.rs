@@ -1,3 +1,5 @@
 pub mod rocks_store;
 pub mod serde;
 pub mod account_smt;
+pub mod state_tree;
+pub mod account_proof;
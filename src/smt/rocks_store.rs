@@ -1,4 +1,6 @@
+use std::collections::BTreeMap;
 use std::marker::PhantomData;
+use std::sync::Arc;
 
 use rocksdb::*;
 use sparse_merkle_tree::{
@@ -7,71 +9,225 @@ use sparse_merkle_tree::{
     BranchKey, BranchNode, H256,
 };
 
-use super::serde::{branch_key_to_vec, branch_node_to_vec, slice_to_branch_node};
+use super::serde::{branch_key_to_vec, branch_node_to_vec, slice_to_branch_node, versioned_key};
+
+pub const BRANCH_TAG: u8 = b'b';
+pub const LEAF_TAG: u8 = b'l';
 
 /// A SMT `Store` implementation backed by a RocksDB database, using the default column family.
+///
+/// Every node is stored under `tag | node key | version (8 bytes, be)`, an update writes a new
+/// version of the node instead of overwriting it and a removal writes an empty tombstone. Reading at
+/// version `v` returns the newest version not greater than `v`, so the tree of any version can be
+/// rebuilt until it is pruned.
+///
+/// Between `begin_batch` and `commit_batch` writes are kept in memory and visible to reads of this
+/// store only, `commit_batch` writes them to the database in one atomic `WriteBatch`.
 pub struct RocksStore<W> {
     // The RocksDB database which stores the data, can be a `DB` / `OptimisticTransactionDB` / `Snapshot` etc.
-    inner: DB,
+    inner: Arc<DB>,
+    // the version new nodes are written at
+    write_version: u64,
+    // the newest version visible to reads
+    read_version: u64,
+    // versioned key -> value written since `begin_batch`
+    pending: Option<BTreeMap<Vec<u8>, Vec<u8>>>,
     // A generic write options, can be a `WriteOptions` / `()` etc.
     write_options: PhantomData<W>,
 }
 
 impl<W> RocksStore<W> {
     pub fn new(db: DB) -> Self {
+        Self::new_shared(Arc::new(db))
+    }
+
+    /// Open a store over a database that is shared with other stores, e.g. a read only view for proofs.
+    pub fn new_shared(db: Arc<DB>) -> Self {
         RocksStore {
             inner: db,
+            write_version: 0,
+            read_version: u64::MAX,
+            pending: None,
             write_options: PhantomData,
         }
     }
+
+    /// A view of the tree as it was at `version`.
+    pub fn at_version(db: Arc<DB>, version: u64) -> Self {
+        let mut store = Self::new_shared(db);
+        store.read_version = version;
+        store
+    }
+
+    /// Write the following changes at `version`, versions must not go backwards.
+    pub fn set_version(&mut self, version: u64) {
+        self.write_version = version;
+        self.read_version = u64::MAX;
+    }
+
+    /// Collect the following writes in memory until `commit_batch` or `discard_batch`.
+    pub fn begin_batch(&mut self) {
+        self.pending = Some(BTreeMap::new());
+    }
+
+    /// Atomically write the collected changes together with `extra` records, e.g. a progress marker,
+    /// and sync the write-ahead log. Returns the number of written node records.
+    pub fn commit_batch(&mut self, extra: Vec<(Vec<u8>, Vec<u8>)>) -> Result<usize, Error> {
+        let pending = self
+            .pending
+            .take()
+            .ok_or_else(|| Error::Store("there is no batch to commit".to_string()))?;
+
+        let mut batch = WriteBatch::default();
+        pending.iter().for_each(|(key, value)| batch.put(key, value));
+        extra.iter().for_each(|(key, value)| batch.put(key, value));
+
+        let mut write_options = WriteOptions::default();
+        write_options.set_sync(true);
+        self.inner
+            .write_opt(batch, &write_options)
+            .map_err(|e| Error::Store(e.to_string()))?;
+
+        Ok(pending.len())
+    }
+
+    /// Drop the collected changes.
+    pub fn discard_batch(&mut self) {
+        self.pending = None;
+    }
+
+    /// Remove the node versions that are not needed to read any version from `keep_from` on.
+    /// Returns the number of removed records.
+    pub fn prune(&self, keep_from: u64) -> Result<usize, Error> {
+        let mut batch = WriteBatch::default();
+        let mut pruned = 0;
+        let mut group_prefix: Vec<u8> = vec![];
+        let mut group: Vec<(Box<[u8]>, u64, bool)> = vec![];
+
+        for item in self.inner.iterator(IteratorMode::Start) {
+            let (key, value) = item.map_err(|e| Error::Store(e.to_string()))?;
+            if key.len() <= 8 || (key[0] != BRANCH_TAG && key[0] != LEAF_TAG) {
+                continue;
+            }
+
+            let (prefix, version_bytes) = key.split_at(key.len() - 8);
+            if prefix != group_prefix.as_slice() {
+                pruned += prune_group(&mut batch, &group, keep_from);
+                group.clear();
+                group_prefix = prefix.to_vec();
+            }
+            let version = u64::from_be_bytes(version_bytes.try_into().expect("checked slice"));
+            group.push((key.clone(), version, value.is_empty()));
+        }
+        pruned += prune_group(&mut batch, &group, keep_from);
+
+        self.inner
+            .write(batch)
+            .map_err(|e| Error::Store(e.to_string()))?;
+
+        Ok(pruned)
+    }
+
+    fn get_versioned(&self, prefix: &[u8]) -> Result<Option<Box<[u8]>>, Error> {
+        // pending writes are the newest version of a node
+        if let Some(pending) = &self.pending {
+            if let Some(value) = pending.get(&versioned_key(prefix, self.write_version)) {
+                return Ok(if value.is_empty() { None } else { Some(value.clone().into_boxed_slice()) });
+            }
+        }
+
+        let key = versioned_key(prefix, self.read_version);
+        let mut iter = self.inner.iterator(IteratorMode::From(key.as_slice(), Direction::Reverse));
+        match iter.next() {
+            Some(Ok((found, value))) => {
+                if found.len() == key.len() && found.starts_with(prefix) && !value.is_empty() {
+                    Ok(Some(value))
+                } else {
+                    Ok(None)
+                }
+            }
+            Some(Err(e)) => Err(Error::Store(e.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    fn put_versioned(&mut self, prefix: &[u8], value: &[u8]) -> Result<(), Error> {
+        if let Some(pending) = self.pending.as_mut() {
+            pending.insert(versioned_key(prefix, self.write_version), value.to_vec());
+            return Ok(());
+        }
+
+        self.inner
+            .put(versioned_key(prefix, self.write_version), value)
+            .map_err(|e| Error::Store(e.to_string()))
+    }
+}
+
+// `group` holds every version of one node in ascending order
+fn prune_group(batch: &mut WriteBatch, group: &[(Box<[u8]>, u64, bool)], keep_from: u64) -> usize {
+    // the newest version at or before `keep_from` still serves reads of `keep_from`,
+    // unless it is a tombstone which reads the same as no record at all
+    let end = match group.iter().rposition(|(_, version, _)| *version <= keep_from) {
+        Some(base) if group[base].2 => base + 1,
+        Some(base) => base,
+        None => 0,
+    };
+
+    group[..end].iter().for_each(|(key, _, _)| batch.delete(key));
+
+    end
+}
+
+fn branch_prefix(branch_key: &BranchKey) -> Vec<u8> {
+    let mut prefix = vec![BRANCH_TAG];
+    prefix.extend_from_slice(&branch_key_to_vec(branch_key));
+    prefix
+}
+
+fn leaf_prefix(leaf_key: &H256) -> Vec<u8> {
+    let mut prefix = vec![LEAF_TAG];
+    prefix.extend_from_slice(leaf_key.as_slice());
+    prefix
 }
 
 impl<V, W> StoreReadOps<V> for RocksStore<W>
 where
-    V: Value + std::convert::From<std::vec::Vec<u8>>,
+    V: Value + TryFrom<Vec<u8>>,
+    <V as TryFrom<Vec<u8>>>::Error: std::fmt::Display,
 {
     fn get_branch(&self, branch_key: &BranchKey) -> Result<Option<BranchNode>, Error> {
-        self.inner
-            .get(&branch_key_to_vec(branch_key))
-            .map(|s| s.map(|v| slice_to_branch_node(&v)))
-            .map_err(|e| Error::Store(e.to_string()))
+        self.get_versioned(&branch_prefix(branch_key))?
+            .map(|v| slice_to_branch_node(&v).map_err(|e| Error::Store(e.to_string())))
+            .transpose()
     }
 
     fn get_leaf(&self, leaf_key: &H256) -> Result<Option<V>, Error> {
-        self.inner
-            .get(leaf_key.as_slice())
-            .map(|s| s.map(|v| v.into()))
-            .map_err(|e| Error::Store(e.to_string()))
+        self.get_versioned(&leaf_prefix(leaf_key))?
+            .map(|v| V::try_from(v.into_vec()).map_err(|e| Error::Store(e.to_string())))
+            .transpose()
     }
 }
 
 impl<V, W> StoreWriteOps<V> for RocksStore<W>
 where
-    V: Value + std::convert::From<std::vec::Vec<u8>> + Into<Vec<u8>>,
+    V: Value + TryFrom<Vec<u8>> + Into<Vec<u8>>,
+    <V as TryFrom<Vec<u8>>>::Error: std::fmt::Display,
 {
     fn insert_branch(&mut self, node_key: BranchKey, branch: BranchNode) -> Result<(), Error> {
-        self.inner
-            .put(&branch_key_to_vec(&node_key), &branch_node_to_vec(&branch))
-            .map_err(|e| Error::Store(e.to_string()))
+        self.put_versioned(&branch_prefix(&node_key), &branch_node_to_vec(&branch))
     }
 
     fn insert_leaf(&mut self, leaf_key: H256, leaf: V) -> Result<(), Error> {
         let v: Vec<u8> = leaf.into();
-        self.inner
-            .put(leaf_key.as_slice(), v)
-            .map_err(|e| Error::Store(e.to_string()))
+        self.put_versioned(&leaf_prefix(&leaf_key), &v)
     }
 
     fn remove_branch(&mut self, node_key: &BranchKey) -> Result<(), Error> {
-        self.inner
-            .delete(&branch_key_to_vec(node_key))
-            .map_err(|e| Error::Store(e.to_string()))
+        self.put_versioned(&branch_prefix(node_key), &[])
     }
 
     fn remove_leaf(&mut self, leaf_key: &H256) -> Result<(), Error> {
-        self.inner
-            .delete(leaf_key.as_slice())
-            .map_err(|e| Error::Store(e.to_string()))
+        self.put_versioned(&leaf_prefix(leaf_key), &[])
     }
 }
 
@@ -87,6 +243,7 @@ mod tests {
     use super::RocksStore;
     use blake2b_rs::{Blake2b, Blake2bBuilder};
     use std::path::Path;
+    use std::sync::Arc;
 
     #[allow(dead_code)]
     pub fn new_blake2b() -> Blake2b {
@@ -207,4 +364,88 @@ mod tests {
             assert_eq!(root2, root);
         }
     }
+
+    #[test]
+    fn test_rocks_store_versions() {
+        let kvs = "The quick brown fox jumps over the lazy dog"
+            .split_whitespace()
+            .enumerate()
+            .map(|(i, word)| {
+                let mut buf = [0u8; 32];
+                let mut hasher = new_blake2b();
+                hasher.update(&(i as u32).to_le_bytes());
+                hasher.finalize(&mut buf);
+                (buf.into(), Word(word.to_string()))
+            })
+            .collect::<Vec<(H256, Word)>>();
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Arc::new(DB::open_default(dir.path()).unwrap());
+
+        // version 1 holds the first half of the words, version 2 all of them
+        let mut smt = RocksStoreSMT::new_with_store(RocksStore::new_shared(db.clone())).unwrap();
+        smt.store_mut().set_version(1);
+        for (key, value) in kvs[..4].iter() {
+            smt.update(key.clone(), value.clone()).unwrap();
+        }
+        let root1 = smt.root().clone();
+        smt.store_mut().set_version(2);
+        for (key, value) in kvs[4..].iter() {
+            smt.update(key.clone(), value.clone()).unwrap();
+        }
+        smt.update(kvs[0].0.clone(), Word::zero()).unwrap();
+        let root2 = smt.root().clone();
+        assert_ne!(root1, root2);
+
+        let read = |version: u64| {
+            let smt = RocksStoreSMT::new_with_store(RocksStore::at_version(db.clone(), version)).unwrap();
+            (smt.root().clone(), smt.get(&kvs[0].0).unwrap().0)
+        };
+        assert_eq!(read(1), (root1.clone(), kvs[0].1 .0.clone()));
+        assert_eq!(read(2), (root2.clone(), String::new()));
+
+        // pruning at 1 keeps both versions readable, pruning at 2 drops version 1
+        let store: RocksStore<Word> = RocksStore::new_shared(db.clone());
+        assert_eq!(store.prune(1).unwrap(), 0);
+        assert_eq!(read(1).0, root1);
+        assert!(store.prune(2).unwrap() > 0);
+        assert_eq!(read(2), (root2, String::new()));
+    }
+
+    #[test]
+    fn test_rocks_store_batch() {
+        let kvs = "The quick brown fox jumps over the lazy dog"
+            .split_whitespace()
+            .enumerate()
+            .map(|(i, word)| {
+                let mut buf = [0u8; 32];
+                let mut hasher = new_blake2b();
+                hasher.update(&(i as u32).to_le_bytes());
+                hasher.finalize(&mut buf);
+                (buf.into(), Word(word.to_string()))
+            })
+            .collect::<Vec<(H256, Word)>>();
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Arc::new(DB::open_default(dir.path()).unwrap());
+
+        let mut memory_store_smt = MemoryStoreSMT::new_with_store(Default::default()).unwrap();
+        memory_store_smt.update_all(kvs.clone()).unwrap();
+
+        let mut smt = RocksStoreSMT::new_with_store(RocksStore::new_shared(db.clone())).unwrap();
+        smt.store_mut().begin_batch();
+        for (key, value) in kvs.iter() {
+            smt.update(key.clone(), value.clone()).unwrap();
+        }
+        assert_eq!(smt.root(), memory_store_smt.root());
+
+        // nothing reaches the database before the commit
+        let read = || RocksStoreSMT::new_with_store(RocksStore::new_shared(db.clone())).unwrap().root().clone();
+        assert_eq!(read(), H256::zero());
+
+        let marker = (b"marker".to_vec(), 1u64.to_le_bytes().to_vec());
+        assert!(smt.store_mut().commit_batch(vec![marker]).unwrap() > 0);
+        assert_eq!(&read(), memory_store_smt.root());
+        assert_eq!(db.get(b"marker").unwrap().unwrap(), 1u64.to_le_bytes().to_vec());
+    }
 }
@@ -1,6 +1,39 @@
 use std::convert::TryInto;
 
 use sparse_merkle_tree::{merge::MergeValue, BranchKey, BranchNode};
+use thiserror::Error;
+
+/// The leading byte of every branch and leaf record.
+pub const FORMAT_VERSION: u8 = 1;
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum DecodeError {
+    #[error("record is empty")]
+    Empty,
+    #[error("unsupported format version {0}")]
+    UnsupportedVersion(u8),
+    #[error("unknown branch node tag {0}")]
+    UnknownTag(u8),
+    #[error("record too short, expected {expected} bytes but it was {actual}")]
+    TooShort { expected: usize, actual: usize },
+}
+
+/// Strip the format version byte of a record.
+pub fn split_version(slice: &[u8]) -> Result<&[u8], DecodeError> {
+    match slice.split_first() {
+        None => Err(DecodeError::Empty),
+        Some((&FORMAT_VERSION, rest)) => Ok(rest),
+        Some((&version, _)) => Err(DecodeError::UnsupportedVersion(version)),
+    }
+}
+
+/// Fail with `TooShort` unless `slice` holds at least `expected` bytes.
+pub fn check_len(slice: &[u8], expected: usize) -> Result<(), DecodeError> {
+    if slice.len() < expected {
+        return Err(DecodeError::TooShort { expected, actual: slice.len() });
+    }
+    Ok(())
+}
 
 /// Serialize a `BranchKey` into a `Vec<u8>` for use as a key in the key-value store.
 pub fn branch_key_to_vec(key: &BranchKey) -> Vec<u8> {
@@ -10,8 +43,28 @@ pub fn branch_key_to_vec(key: &BranchKey) -> Vec<u8> {
     ret
 }
 
-/// Serialize a `BranchNode` into a `Vec<u8>` for use as a key in the key-value store.
+/// Append a big-endian `version` to `prefix`, so the versions of one node sort next to each other in ascending order.
+pub fn versioned_key(prefix: &[u8], version: u64) -> Vec<u8> {
+    let mut ret = Vec::with_capacity(prefix.len() + 8);
+    ret.extend_from_slice(prefix);
+    ret.extend_from_slice(&version.to_be_bytes());
+    ret
+}
+
+/// Serialize a `BranchNode` into a versioned `Vec<u8>` for use as a value in the key-value store.
 pub fn branch_node_to_vec(node: &BranchNode) -> Vec<u8> {
+    let mut ret = vec![FORMAT_VERSION];
+    ret.extend_from_slice(&branch_node_to_vec_unversioned(node));
+    ret
+}
+
+/// Deserialize a `BranchNode` from a slice that was previously serialized with `branch_node_to_vec`.
+pub fn slice_to_branch_node(slice: &[u8]) -> Result<BranchNode, DecodeError> {
+    slice_to_branch_node_unversioned(split_version(slice)?)
+}
+
+/// Serialize a `BranchNode` without the format version, the layout written before versioned records.
+pub fn branch_node_to_vec_unversioned(node: &BranchNode) -> Vec<u8> {
     match (&node.left, &node.right) {
         (MergeValue::Value(left), MergeValue::Value(right)) => {
             let mut ret = Vec::with_capacity(33);
@@ -158,9 +211,22 @@ pub fn branch_node_to_vec(node: &BranchNode) -> Vec<u8> {
     }
 }
 
-/// Deserialize a `BranchNode` from a slice that was previously serialized with `branch_node_to_vec`.
-pub fn slice_to_branch_node(slice: &[u8]) -> BranchNode {
-    match slice[0] {
+/// Deserialize a `BranchNode` from a slice without the format version.
+pub fn slice_to_branch_node_unversioned(slice: &[u8]) -> Result<BranchNode, DecodeError> {
+    let expected = match slice.first() {
+        None => return Err(DecodeError::Empty),
+        Some(0) => 65,
+        Some(1) | Some(2) => 98,
+        Some(3) => 131,
+        #[cfg(feature = "trie")]
+        Some(4) | Some(5) => 98,
+        #[cfg(feature = "trie")]
+        Some(6) | Some(7) | Some(8) => 131,
+        Some(tag) => return Err(DecodeError::UnknownTag(*tag)),
+    };
+    check_len(slice, expected)?;
+
+    let node = match slice[0] {
         0 => {
             let left: [u8; 32] = slice[1..33].try_into().expect("checked slice");
             let right: [u8; 32] = slice[33..65].try_into().expect("checked slice");
@@ -313,5 +379,43 @@ pub fn slice_to_branch_node(slice: &[u8]) -> BranchNode {
         _ => {
             unreachable!()
         }
+    };
+
+    Ok(node)
+}
+
+#[cfg(test)]
+pub mod test {
+    use sparse_merkle_tree::{merge::MergeValue, BranchNode};
+
+    use crate::smt::serde::{branch_node_to_vec, slice_to_branch_node, DecodeError};
+
+    fn prepare_branch_node() -> BranchNode {
+        BranchNode {
+            left: MergeValue::Value([1u8; 32].into()),
+            right: MergeValue::MergeWithZero {
+                base_node: [2u8; 32].into(),
+                zero_bits: [3u8; 32].into(),
+                zero_count: 4,
+            },
+        }
+    }
+
+    #[test]
+    fn test_convert() {
+        let node = prepare_branch_node();
+        let data = branch_node_to_vec(&node);
+        assert_eq!(slice_to_branch_node(&data).unwrap(), node);
+    }
+
+    #[test]
+    fn test_corrupt() {
+        let data = branch_node_to_vec(&prepare_branch_node());
+        assert_eq!(slice_to_branch_node(&[]), Err(DecodeError::Empty));
+        let mut unknown_version = data.clone();
+        unknown_version[0] = 2;
+        assert_eq!(slice_to_branch_node(&unknown_version), Err(DecodeError::UnsupportedVersion(2)));
+        assert_eq!(slice_to_branch_node(&[1, 9]), Err(DecodeError::UnknownTag(9)));
+        assert_eq!(slice_to_branch_node(&data[..50]), Err(DecodeError::TooShort { expected: 98, actual: 49 }));
     }
 }
@@ -0,0 +1,369 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use log::info;
+use rocksdb::{Direction, IteratorMode, WriteBatch, DB};
+use sparse_merkle_tree::H256;
+
+use crate::common::node_error::NodeError;
+use crate::models::account_audit_row::AccountAuditRow;
+use crate::smt::account_proof::AccountProver;
+use crate::smt::account_smt::{DatabaseStoreAccountSMT, SMTAccount};
+use crate::smt::rocks_store::{RocksStore, BRANCH_TAG, LEAF_TAG};
+use crate::smt::serde::{branch_node_to_vec, slice_to_branch_node_unversioned, versioned_key, FORMAT_VERSION};
+use crate::utils::uuid_util::generate_uuid;
+
+// `COMMITMENT_TAG | slot (8 bytes, be)` -> world state root of the L1 commitment at slot
+pub const COMMITMENT_TAG: u8 = b'c';
+
+// marks a database written with versioned nodes
+pub const LAYOUT_KEY: &[u8] = b"m:layout:versioned";
+
+// the record format of the stored nodes (1 byte), absent for records written without a version byte
+pub const FORMAT_KEY: &[u8] = b"m:format";
+
+// the last L2 slot applied to the tree (8 bytes, le), written in the same batch as the slot's nodes
+pub const APPLIED_SLOT_KEY: &[u8] = b"m:applied_slot";
+
+/// One tree per process, shared by the brief producer and the state verifier.
+pub type SharedStateTree = Arc<Mutex<StateTree>>;
+
+/// The L2 world state: every account of `account_audit` kept in a sparse merkle tree persisted in RocksDB.
+///
+/// Nodes are versioned by the L2 slot that wrote them, the tree of every committed slot stays
+/// readable until it falls out of the retention window and is pruned.
+pub struct StateTree {
+    db: Arc<DB>,
+    smt: DatabaseStoreAccountSMT,
+}
+
+impl StateTree {
+    pub fn open(path: &Path) -> Result<Self, NodeError> {
+        let db = DB::open_default(path).map_err(|e| {
+            NodeError::new(generate_uuid(), format!("open state tree db fail. path: {:?}, err: {:?}", path, e))
+        })?;
+        check_layout(&db, path)?;
+        check_format(&db)?;
+        let db = Arc::new(db);
+
+        let smt = DatabaseStoreAccountSMT::new_with_store(RocksStore::new_shared(db.clone())).map_err(|e| {
+            NodeError::new(generate_uuid(), format!("load state tree fail. path: {:?}, err: {:?}", path, e))
+        })?;
+
+        Ok(Self { db, smt })
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.smt.root().clone().into()
+    }
+
+    /// The root of the tree as it was after `slot` was applied.
+    pub fn root_at(&self, slot: u64) -> Result<[u8; 32], NodeError> {
+        let applied_slot = self.applied_slot()?;
+        if slot > applied_slot {
+            return Err(NodeError::new(generate_uuid(),
+                format!("state tree is behind the slot. slot: {}, applied slot: {}", slot, applied_slot)));
+        }
+
+        let smt = DatabaseStoreAccountSMT::new_with_store(RocksStore::at_version(self.db.clone(), slot)).map_err(|e| {
+            NodeError::new(generate_uuid(), format!("load state tree fail. slot: {}, err: {:?}", slot, e))
+        })?;
+
+        Ok(smt.root().clone().into())
+    }
+
+    /// Apply the `account_audit` rows of `slot`, rows must be ordered by `write_version`.
+    ///
+    /// The nodes of the slot and the applied slot marker are written atomically, a crash leaves the
+    /// tree at the previous slot.
+    pub fn apply(&mut self, slot: u64, rows: &[AccountAuditRow]) -> Result<[u8; 32], NodeError> {
+        // an account may be written several times in a slot, only the last write is part of the state
+        let mut accounts: BTreeMap<H256, SMTAccount> = BTreeMap::new();
+        for row in rows {
+            let account = row.to_smt_account()?;
+            accounts.insert(account.smt_key(), account);
+        }
+
+        self.smt.store_mut().set_version(slot);
+        self.smt.store_mut().begin_batch();
+        let ret = self.smt.update_all(accounts.into_iter().collect())
+            .map(|_| ())
+            .and_then(|_| {
+                let marker = (APPLIED_SLOT_KEY.to_vec(), slot.to_le_bytes().to_vec());
+                self.smt.store_mut().commit_batch(vec![marker]).map(|_| ())
+            });
+
+        if let Err(e) = ret {
+            // the in memory root already moved, load it back from the database
+            self.smt.store_mut().discard_batch();
+            self.reload()?;
+            return Err(NodeError::new(generate_uuid(), format!("update state tree fail. slot: {}, err: {:?}", slot, e)));
+        }
+
+        Ok(self.root())
+    }
+
+    /// The last L2 slot applied to the tree, 0 for an empty tree.
+    pub fn applied_slot(&self) -> Result<u64, NodeError> {
+        let value = self.db.get(APPLIED_SLOT_KEY).map_err(|e| {
+            NodeError::new(generate_uuid(), format!("read applied slot fail. err: {:?}", e))
+        })?;
+
+        Ok(value
+            .and_then(|v| v.as_slice().try_into().ok())
+            .map(u64::from_le_bytes)
+            .unwrap_or(0))
+    }
+
+    fn reload(&mut self) -> Result<(), NodeError> {
+        self.smt = DatabaseStoreAccountSMT::new_with_store(RocksStore::new_shared(self.db.clone())).map_err(|e| {
+            NodeError::new(generate_uuid(), format!("reload state tree fail. err: {:?}", e))
+        })?;
+
+        Ok(())
+    }
+
+    /// Record the tree of `slot` as the state of the L1 commitment at `slot`.
+    pub fn commit(&self, slot: u64) -> Result<(), NodeError> {
+        self.db.put(commitment_key(slot), self.root_at(slot)?).map_err(|e| {
+            NodeError::new(generate_uuid(), format!("commit state tree fail. slot: {}, err: {:?}", slot, e))
+        })
+    }
+
+    /// The latest L1 commitment the tree was recorded for.
+    pub fn committed_slot(&self) -> Result<Option<u64>, NodeError> {
+        Ok(latest_commitment(&self.db)?.map(|(slot, _)| slot))
+    }
+
+    /// A handle that builds account proofs from committed trees, it can be moved to another thread.
+    pub fn prover(&self) -> AccountProver {
+        AccountProver::new(self.db.clone())
+    }
+
+    /// A handle that prunes old versions, it can be moved to another thread.
+    pub fn pruner(&self) -> StatePruner {
+        StatePruner { db: self.db.clone() }
+    }
+}
+
+#[derive(Clone)]
+pub struct StatePruner {
+    db: Arc<DB>,
+}
+
+impl StatePruner {
+    /// Keep the trees of the last `retention_slots` slots before the latest commitment, drop older versions.
+    pub fn prune(&self, retention_slots: u64) -> Result<usize, NodeError> {
+        let latest_slot = match latest_commitment(&self.db)? {
+            Some((slot, _)) => slot,
+            None => return Ok(0),
+        };
+        if latest_slot <= retention_slots {
+            return Ok(0);
+        }
+        let keep_from = latest_slot - retention_slots;
+
+        let store: RocksStore<SMTAccount> = RocksStore::new_shared(self.db.clone());
+        let pruned = store.prune(keep_from).map_err(|e| {
+            NodeError::new(generate_uuid(), format!("prune state tree fail. keep from: {}, err: {:?}", keep_from, e))
+        })?;
+
+        // commitments older than the window can no longer be proven
+        let mut batch = WriteBatch::default();
+        batch.delete_range(commitment_key(0), commitment_key(keep_from));
+        self.db.write(batch).map_err(|e| {
+            NodeError::new(generate_uuid(), format!("prune commitments fail. keep from: {}, err: {:?}", keep_from, e))
+        })?;
+
+        info!("state tree pruned. keep from slot: {}, removed records: {}", keep_from, pruned);
+        Ok(pruned)
+    }
+}
+
+pub fn commitment_key(slot: u64) -> Vec<u8> {
+    let mut key = vec![COMMITMENT_TAG];
+    key.extend_from_slice(&slot.to_be_bytes());
+    key
+}
+
+/// The world state root recorded for the L1 commitment at `slot`.
+pub fn commitment_root(db: &DB, slot: u64) -> Result<Option<[u8; 32]>, NodeError> {
+    let value = db.get(commitment_key(slot)).map_err(|e| {
+        NodeError::new(generate_uuid(), format!("read commitment fail. slot: {}, err: {:?}", slot, e))
+    })?;
+
+    Ok(value.and_then(|v| v.as_slice().try_into().ok()))
+}
+
+pub fn latest_commitment(db: &DB) -> Result<Option<(u64, [u8; 32])>, NodeError> {
+    let key = commitment_key(u64::MAX);
+    let mut iter = db.iterator(IteratorMode::From(key.as_slice(), Direction::Reverse));
+    match iter.next() {
+        Some(Ok((found, value))) if found.len() == 9 && found[0] == COMMITMENT_TAG => {
+            let slot = u64::from_be_bytes(found[1..9].try_into().expect("checked slice"));
+            let root: [u8; 32] = value.as_ref().try_into().map_err(|_| {
+                NodeError::new(generate_uuid(), format!("invalid commitment root. slot: {}", slot))
+            })?;
+            Ok(Some((slot, root)))
+        }
+        Some(Err(e)) => Err(NodeError::new(generate_uuid(), format!("read commitment fail. err: {:?}", e))),
+        _ => Ok(None),
+    }
+}
+
+// the first layout stored nodes without versions, an existing store of it is migrated in place
+fn check_layout(db: &DB, path: &Path) -> Result<(), NodeError> {
+    let is_versioned = db.get(LAYOUT_KEY).map_err(|e| {
+        NodeError::new(generate_uuid(), format!("read state tree layout fail. err: {:?}", e))
+    })?.is_some();
+    if is_versioned {
+        return Ok(());
+    }
+
+    let is_empty = db.iterator(IteratorMode::Start).next().is_none();
+    if !is_empty {
+        return migrate_unversioned(db, path);
+    }
+
+    db.put(LAYOUT_KEY, b"").map_err(|e| {
+        NodeError::new(generate_uuid(), format!("write state tree layout fail. err: {:?}", e))
+    })
+}
+
+// the first layout keyed branches by `height | node key` (33 bytes) and leaves by the leaf key
+// (32 bytes), with records in the unversioned format. Every record is moved to
+// `tag | key | version 0` in the current format, in one batch with the layout and format markers.
+fn migrate_unversioned(db: &DB, path: &Path) -> Result<(), NodeError> {
+    let mut batch = WriteBatch::default();
+    let mut migrated = 0;
+    for item in db.iterator(IteratorMode::Start) {
+        let (key, value) = item.map_err(|e| {
+            NodeError::new(generate_uuid(), format!("read state tree fail. err: {:?}", e))
+        })?;
+
+        let (tag, encoded) = match key.len() {
+            33 => (BRANCH_TAG, slice_to_branch_node_unversioned(&value).map(|node| branch_node_to_vec(&node))),
+            32 => (LEAF_TAG, SMTAccount::from_unversioned(&value).map(|account| account.into())),
+            _ => return Err(NodeError::new(generate_uuid(),
+                format!("state tree at {:?} has an unknown record, key: {:?}", path, key))),
+        };
+        let encoded = encoded.map_err(|e| {
+            NodeError::new(generate_uuid(), format!("migrate state tree record fail. key: {:?}, err: {}", key, e))
+        })?;
+
+        let mut prefix = vec![tag];
+        prefix.extend_from_slice(&key);
+        batch.delete(&key);
+        batch.put(versioned_key(&prefix, 0), encoded);
+        migrated += 1;
+    }
+    batch.put(LAYOUT_KEY, b"");
+    batch.put(FORMAT_KEY, [FORMAT_VERSION]);
+
+    db.write(batch).map_err(|e| {
+        NodeError::new(generate_uuid(), format!("migrate state tree layout fail. path: {:?}, err: {:?}", path, e))
+    })?;
+    info!("state tree migrated to the versioned layout at version 0. path: {:?}, records: {}", path, migrated);
+
+    Ok(())
+}
+
+// records written before the format version byte are re-encoded once, together with the format marker
+fn check_format(db: &DB) -> Result<(), NodeError> {
+    let format = db.get(FORMAT_KEY).map_err(|e| {
+        NodeError::new(generate_uuid(), format!("read state tree format fail. err: {:?}", e))
+    })?;
+    match format.as_deref() {
+        Some([FORMAT_VERSION]) => return Ok(()),
+        Some(other) => return Err(NodeError::new(generate_uuid(),
+            format!("unsupported state tree format: {:?}", other))),
+        None => {}
+    }
+
+    let mut batch = WriteBatch::default();
+    let mut migrated = 0;
+    for item in db.iterator(IteratorMode::Start) {
+        let (key, value) = item.map_err(|e| {
+            NodeError::new(generate_uuid(), format!("read state tree fail. err: {:?}", e))
+        })?;
+        // tombstones carry no record
+        if key.len() <= 8 || value.is_empty() {
+            continue;
+        }
+
+        let encoded = match key[0] {
+            BRANCH_TAG => slice_to_branch_node_unversioned(&value)
+                .map(|node| branch_node_to_vec(&node)),
+            LEAF_TAG => SMTAccount::from_unversioned(&value)
+                .map(|account| account.into()),
+            _ => continue,
+        }.map_err(|e| {
+            NodeError::new(generate_uuid(), format!("migrate state tree record fail. key: {:?}, err: {}", key, e))
+        })?;
+        batch.put(&key, encoded);
+        migrated += 1;
+    }
+    batch.put(FORMAT_KEY, [FORMAT_VERSION]);
+
+    db.write(batch).map_err(|e| {
+        NodeError::new(generate_uuid(), format!("write state tree format fail. err: {:?}", e))
+    })?;
+    if migrated > 0 {
+        info!("state tree records migrated to format {}. records: {}", FORMAT_VERSION, migrated);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod test {
+    use rocksdb::DB;
+    use solana_sdk::pubkey::Pubkey;
+
+    use crate::smt::account_smt::{MemoryStoreAccountSMT, SMTAccount};
+    use crate::smt::serde::{branch_key_to_vec, branch_node_to_vec_unversioned, FORMAT_VERSION};
+    use crate::smt::state_tree::{StateTree, FORMAT_KEY, LAYOUT_KEY};
+
+    fn prepare_account(lamports: i64) -> SMTAccount {
+        SMTAccount {
+            pubkey: Pubkey::new_unique(),
+            lamports,
+            owner: Pubkey::new_unique(),
+            executable: false,
+            rent_epoch: 0,
+            data: vec![lamports as u8; 4],
+        }
+    }
+
+    #[test]
+    fn test_open_unversioned() {
+        let accounts: Vec<SMTAccount> = (1..=5).map(prepare_account).collect();
+        let mut memory_smt = MemoryStoreAccountSMT::new_with_store(Default::default()).unwrap();
+        memory_smt.update_all(accounts.iter().map(|account| (account.smt_key(), account.clone())).collect()).unwrap();
+
+        // a store written in the first layout, unversioned keys and records
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let db = DB::open_default(dir.path()).unwrap();
+            for (key, node) in memory_smt.store().branches_map().iter() {
+                db.put(branch_key_to_vec(key), branch_node_to_vec_unversioned(node)).unwrap();
+            }
+            for (key, account) in memory_smt.store().leaves_map().iter() {
+                db.put(key.as_slice(), account.to_vec()).unwrap();
+            }
+        }
+
+        let expected: [u8; 32] = memory_smt.root().clone().into();
+        let state_tree = StateTree::open(dir.path()).unwrap();
+        assert_eq!(state_tree.root(), expected);
+        assert_eq!(state_tree.root_at(0).unwrap(), expected);
+        assert_eq!(state_tree.db.get(LAYOUT_KEY).unwrap(), Some(vec![]));
+        assert_eq!(state_tree.db.get(FORMAT_KEY).unwrap(), Some(vec![FORMAT_VERSION]));
+        drop(state_tree);
+
+        // migrated once, opened as is afterwards
+        let state_tree = StateTree::open(dir.path()).unwrap();
+        assert_eq!(state_tree.root(), expected);
+    }
+}
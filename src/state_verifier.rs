@@ -0,0 +1,213 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use itertools::Itertools;
+use log::{error, info};
+
+use crate::common::node_configs::{ChainConfiguration, StoreConfiguration};
+use crate::common::node_error::NodeError;
+use crate::models::account_audit_row::AccountAuditRow;
+use crate::models::challenge_model::{ChallengeKind, ChallengeRecord};
+use crate::repositories::account_audit_repo::AccountAuditRepo;
+use crate::repositories::block_repo::BlockRepo;
+use crate::repositories::brief_repo::BriefRepo;
+use crate::repositories::challenge_repo::ChallengeRepo;
+use crate::services::chain_service::ChainService;
+use crate::smt::account_proof::AccountProver;
+use crate::smt::state_tree::{SharedStateTree, StateTree};
+use crate::utils::store_util::{create_pool, PgConnectionPool};
+use crate::utils::time_util;
+
+pub struct StateVerifier {
+    client_pool: Option<PgConnectionPool>,
+    chain_service: Option<ChainService>,
+    state_tree: Option<SharedStateTree>,
+    store_config: Option<StoreConfiguration>,
+    chain_config: Option<ChainConfiguration>,
+}
+
+impl StateVerifier {
+    pub fn new() -> Self {
+        Self {
+            client_pool: None,
+            chain_service: None,
+            state_tree: None,
+            store_config: None,
+            chain_config: None,
+        }
+    }
+
+    pub fn load_store_config(mut self, store_config: &StoreConfiguration) -> Self {
+        self.store_config = Some(store_config.clone());
+        self
+    }
+
+    pub fn load_chain_config(mut self, chain_config: &ChainConfiguration) -> Self {
+        self.chain_config = Some(chain_config.clone());
+        self
+    }
+
+    pub fn load_state_tree(mut self, state_tree: &SharedStateTree) -> Self {
+        self.state_tree = Some(state_tree.clone());
+        self
+    }
+
+    pub fn connect_store(&mut self) -> Result<(), NodeError> {
+        let pool = create_pool(self.store_config.clone().unwrap(), 2);
+
+        self.client_pool = Some(pool);
+
+        Ok(())
+    }
+
+    pub fn connect_chain(&mut self) -> Result<(), NodeError> {
+        let chain_service = ChainService::new(&self.chain_config.clone().unwrap())?;
+
+        self.chain_service = Some(chain_service);
+
+        Ok(())
+    }
+
+    pub fn open_state(&mut self) -> Result<(), NodeError> {
+        let state_tree = StateTree::open(Path::new("./relayer/verifier/smt"))?;
+        self.state_tree = Some(Arc::new(Mutex::new(state_tree)));
+
+        Ok(())
+    }
+
+    pub fn start(&mut self) -> Result<(), NodeError> {
+        self.connect_store()?;
+        self.connect_chain()?;
+        if self.state_tree.is_none() {
+            self.open_state()?;
+        }
+
+        // the brief producer may have applied the tree further, the verifier continues from its last commitment
+        let (mut last_applied_slot, pruner) = {
+            let state_tree = self.state_tree.as_ref().unwrap().lock().unwrap();
+            (state_tree.committed_slot()?.unwrap_or(0) as i64, state_tree.pruner())
+        };
+        info!("state verifier last verified slot: {}", last_applied_slot);
+
+        let retention_slots = self.chain_config.as_ref().unwrap().state_retention_slots;
+        let _ = thread::spawn(move || {
+            loop {
+                if let Err(e) = pruner.prune(retention_slots) {
+                    error!("{:?}", e);
+                }
+                time_util::sleep_seconds(60);
+            }
+        });
+
+        let mut unverified_slot: Option<u64> = None;
+
+        loop {
+            if let Some(commitment_slot) = unverified_slot {
+                match self.verify(commitment_slot) {
+                    Ok(_) => unverified_slot = None,
+                    Err(e) => {
+                        error!("verify world state fail, retry later. slot: {}, err: {:?}", commitment_slot, e);
+                        time_util::sleep_seconds(1);
+                        continue;
+                    }
+                }
+            }
+
+            let chain_all_slots = self.chain_service.as_mut().unwrap().get_all_slots_from_chain().unwrap_or_default();
+            let commitment_slot = match chain_all_slots.into_iter().filter(|&s| s > last_applied_slot as u64).min() {
+                Some(slot) => slot,
+                None => {
+                    time_util::sleep_seconds(1);
+                    continue;
+                }
+            };
+
+            // rows of a slot still being written would replay into a wrong root
+            match self.audited_slot() {
+                Ok(audited_slot) if audited_slot >= commitment_slot as i64 => {}
+                Ok(audited_slot) => {
+                    info!("account_audit behind the commitment, wait. slot: {}, audited slot: {}", commitment_slot, audited_slot);
+                    time_util::sleep_seconds(1);
+                    continue;
+                }
+                Err(e) => {
+                    error!("read audited slot fail. {:?}", e);
+                    time_util::sleep_seconds(1);
+                    continue;
+                }
+            }
+
+            if let Err(e) = self.replay(last_applied_slot + 1, commitment_slot as i64) {
+                error!("replay account_audit fail, retry later. slot: {}, err: {:?}", commitment_slot, e);
+                time_util::sleep_seconds(1);
+                continue;
+            }
+            last_applied_slot = commitment_slot as i64;
+            unverified_slot = Some(commitment_slot);
+        }
+    }
+
+    // the latest slot the plugin finished writing, its account_audit rows are complete
+    fn audited_slot(&self) -> Result<i64, NodeError> {
+        let mut repo = BlockRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+
+        Ok(repo.show().map_or(0, |row| row.slot))
+    }
+
+    // replay account_audit rows slot by slot, every slot is written atomically with the applied slot marker
+    fn replay(&mut self, from_slot: i64, to_slot: i64) -> Result<(), NodeError> {
+        let mut state_tree = self.state_tree.as_ref().unwrap().lock().unwrap();
+        // the brief producer or a replay that failed half way already applied some slots
+        let from_slot = from_slot.max(state_tree.applied_slot()? as i64 + 1);
+
+        let repo = AccountAuditRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+        let rows = repo.range(from_slot, to_slot)?;
+
+        for (slot, slot_rows) in &rows.into_iter().group_by(|row| row.slot) {
+            let slot_rows: Vec<AccountAuditRow> = slot_rows.collect();
+            let root = state_tree.apply(slot as u64, &slot_rows)?;
+            info!("state tree applied slot: {}, accounts: {}, root: {:?}", slot, slot_rows.len(), root);
+        }
+        // slots without account changes leave the tree as it is
+        if state_tree.applied_slot()? < to_slot as u64 {
+            state_tree.apply(to_slot as u64, &[])?;
+        }
+
+        Ok(())
+    }
+
+    fn verify(&mut self, commitment_slot: u64) -> Result<(), NodeError> {
+        let local_root = self.state_tree.as_ref().unwrap().lock().unwrap().root_at(commitment_slot)?;
+
+        let roots_info = self.chain_service.as_mut().unwrap().get_roots_info_by_slot(commitment_slot)?;
+
+        if roots_info.world_state_root == local_root {
+            info!("world state root verified. slot: {}, root: {:?}", commitment_slot, local_root);
+            // only verified state is served to account proofs
+            return self.state_tree.as_ref().unwrap().lock().unwrap().commit(commitment_slot);
+        }
+
+        error!("local world state is different to the state on chain, slot: {}, chain world state root: {:?}, local root: {:?}",
+            commitment_slot, roots_info.world_state_root, local_root);
+
+        if self.chain_config.as_ref().unwrap().enable_challenger {
+            let brief_repo = BriefRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+            let repo = ChallengeRepo { pool: Box::from(self.client_pool.clone().unwrap()) };
+
+            let brief = brief_repo.find_by_slot(commitment_slot as i64).ok();
+            let record = ChallengeRecord::pending(commitment_slot as i64, ChallengeKind::WorldState, local_root.to_vec(), roots_info.world_state_root.to_vec(), brief);
+            match repo.insert(record) {
+                Ok(_) => info!("challenge recorded. slot: {}", commitment_slot),
+                Err(e) => error!("record challenge fail. slot: {}, err: {:?}", commitment_slot, e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Account proofs against verified commitments within the retention window, available once the state is opened.
+    pub fn prover(&self) -> Option<AccountProver> {
+        self.state_tree.as_ref().map(|state_tree| state_tree.lock().unwrap().prover())
+    }
+}
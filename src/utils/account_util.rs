@@ -1,9 +1,14 @@
+use std::collections::BTreeMap;
+
 use blake2b_rs::Blake2bBuilder;
 use solana_sdk::account::Account;
 use solana_sdk::hash::Hash;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Signature;
+use crate::common::node_error::NodeError;
+use crate::models::account_audit_row::AccountAuditRow;
 use crate::smt::account_smt::SMTAccount;
+use crate::utils::uuid_util::generate_uuid;
 
 pub fn account_to_vec(pk: &Pubkey, account: &Account) -> Vec<u8> {
     let mut bytes = vec![];
@@ -32,3 +37,96 @@ pub fn compute_ha(tx_id: &Signature, accounts: &Vec<SMTAccount>, old_ha: &Hash)
     Hash::new(&buf)
 }
 
+/// Chain the hash account over the `account_audit` rows of one slot, ordered by `write_version`.
+///
+/// Transactions are folded in the order of their first write, each with the last write of every
+/// account it touched. Returns the new hash account and the number of transactions.
+pub fn compute_slot_ha(rows: &[AccountAuditRow], old_ha: &Hash) -> Result<(Hash, u32), NodeError> {
+    let steps = compute_slot_ha_steps(rows, old_ha)?;
+    let ha = steps.last().map(|(_, ha)| *ha).unwrap_or(*old_ha);
+
+    Ok((ha, steps.len() as u32))
+}
+
+/// Like `compute_slot_ha`, but returns every transaction with the hash account after it.
+pub fn compute_slot_ha_steps(rows: &[AccountAuditRow], old_ha: &Hash) -> Result<Vec<(Signature, Hash)>, NodeError> {
+    let mut txs: Vec<(Vec<u8>, BTreeMap<Pubkey, SMTAccount>)> = vec![];
+    for row in rows {
+        let tx_id = match &row.txn_signature {
+            Some(tx_id) => tx_id,
+            // startup and rent updates are not part of a transaction
+            None => continue,
+        };
+        let account = row.to_smt_account()?;
+        match txs.iter_mut().find(|(id, _)| id == tx_id) {
+            Some((_, accounts)) => { accounts.insert(account.pubkey, account); }
+            None => txs.push((tx_id.clone(), BTreeMap::from([(account.pubkey, account)]))),
+        }
+    }
+
+    let mut ha = *old_ha;
+    let mut steps = vec![];
+    for (tx_id, accounts) in &txs {
+        // a malformed signature would be hashed as a transaction that never happened
+        let tx_id = Signature::try_from(tx_id.as_slice()).map_err(|_| {
+            NodeError::new(generate_uuid(), format!("invalid transaction signature in account_audit: {}", hex::encode(tx_id)))
+        })?;
+        ha = compute_ha(&tx_id, &accounts.values().cloned().collect(), &ha);
+        steps.push((tx_id, ha));
+    }
+
+    Ok(steps)
+}
+
+#[cfg(test)]
+pub mod test {
+    use solana_sdk::hash::Hash;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::Signature;
+
+    use crate::models::account_audit_row::AccountAuditRow;
+    use crate::utils::account_util::{compute_ha, compute_slot_ha};
+
+    fn prepare_row(pubkey: &Pubkey, lamports: i64, write_version: i64, tx_id: &Signature) -> AccountAuditRow {
+        AccountAuditRow {
+            id: write_version,
+            pubkey: pubkey.to_bytes().to_vec(),
+            owner: Some(Pubkey::default().to_bytes().to_vec()),
+            lamports,
+            slot: 7,
+            executable: false,
+            rent_epoch: 0,
+            data: Some(vec![]),
+            write_version,
+            txn_signature: Some(tx_id.as_ref().to_vec()),
+            updated_on: chrono::NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn test_compute_slot_ha() {
+        let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (tx1, tx2) = (Signature::new_unique(), Signature::new_unique());
+        let rows = vec![
+            prepare_row(&alice, 10, 1, &tx1),
+            prepare_row(&bob, 5, 2, &tx2),
+            prepare_row(&alice, 8, 3, &tx1),
+        ];
+
+        let old_ha = Hash::new_unique();
+        let (ha, transaction_number) = compute_slot_ha(&rows, &old_ha).unwrap();
+
+        let ha1 = compute_ha(&tx1, &vec![rows[2].to_smt_account().unwrap()], &old_ha);
+        let ha2 = compute_ha(&tx2, &vec![rows[1].to_smt_account().unwrap()], &ha1);
+        assert_eq!(ha, ha2);
+        assert_eq!(transaction_number, 2);
+    }
+
+    #[test]
+    fn test_compute_slot_ha_malformed_signature() {
+        let mut row = prepare_row(&Pubkey::new_unique(), 10, 1, &Signature::new_unique());
+        row.txn_signature = Some(vec![1, 2, 3]);
+
+        assert!(compute_slot_ha(&[row], &Hash::new_unique()).is_err());
+    }
+}